@@ -1,7 +1,14 @@
 //! Argument struct for the CLI.
 // use std::env;
-use crate::validator::{is_valid_batch_size, is_valid_data_dir};
+use std::collections::HashMap;
+
+use crate::validator::{is_valid_batch_size, is_valid_concurrent_tasks_size, is_valid_data_dir};
 use clap::{Parser, Subcommand};
+use common::config::{
+    Config, AUTH_TOKEN, DEFAULT_BATCH_SIZE, JOB_PRIORITY, MAX_CONCURRENT_JOBS, TLS_CA, TLS_CERT,
+    TLS_CLIENT_AUTH, TLS_KEY,
+};
+use common::error::Result;
 
 #[derive(Debug, Parser, PartialEq)]
 #[command(author, version, about, long_about= None)]
@@ -30,6 +37,68 @@ pub struct Args {
 
     #[arg(long, help = "Rapidash scheduler port", default_value = "51008")]
     port: Option<u16>,
+
+    #[arg(long, help = "Path of the PEM certificate, enables TLS")]
+    tls_cert: Option<String>,
+
+    #[arg(long, help = "Path of the PEM private key of the certificate")]
+    tls_key: Option<String>,
+
+    #[arg(long, help = "Path of the PEM CA certificate to trust, enables TLS")]
+    tls_ca: Option<String>,
+
+    #[arg(long, help = "Use mutual TLS, clients present their certificate")]
+    tls_client_auth: bool,
+
+    #[arg(long, help = "Token to authenticate to the scheduler with")]
+    token: Option<String>,
+
+    #[arg(long, help = "Priority of the submitted jobs, higher starts first")]
+    priority: Option<u16>,
+}
+
+impl Args {
+    /// Configuration of the connection to the scheduler, its TLS and token
+    pub fn config(&self) -> Result<Config> {
+        let mut settings = HashMap::new();
+        let paths = [
+            (TLS_CERT, &self.tls_cert),
+            (TLS_KEY, &self.tls_key),
+            (TLS_CA, &self.tls_ca),
+        ];
+        for (key, path) in paths {
+            if let Some(path) = path {
+                settings.insert(key.to_string(), path.clone());
+            }
+        }
+        if self.tls_client_auth {
+            settings.insert(TLS_CLIENT_AUTH.to_string(), "true".to_string());
+        }
+        if let Some(token) = &self.token {
+            settings.insert(AUTH_TOKEN.to_string(), token.clone());
+        }
+        Config::with_settings(settings)
+    }
+
+    /// Settings of the session the queries run in
+    pub fn session_settings(&self) -> HashMap<String, String> {
+        let mut settings = HashMap::new();
+        if let Some(batch_size) = self.batch_size {
+            settings.insert(DEFAULT_BATCH_SIZE.to_string(), batch_size.to_string());
+        }
+        if let Some(priority) = self.priority {
+            settings.insert(JOB_PRIORITY.to_string(), priority.to_string());
+        }
+        settings
+    }
+
+    /// Host and port of the scheduler
+    pub fn scheduler(&self) -> (&str, u16) {
+        (
+            self.host.as_deref().unwrap_or("127.0.0.1"),
+            self.port.unwrap_or(51008),
+        )
+    }
 }
 
 /// Level one command.
@@ -40,6 +109,13 @@ pub enum Stage {
     Scheduler {
         #[command(subcommand)]
         command: Operator,
+
+        #[arg(
+            long,
+            help = "Jobs the scheduler runs at the same time",
+            value_parser = is_valid_concurrent_tasks_size,
+        )]
+        max_concurrent_jobs: Option<usize>,
     },
 
     /// Executor
@@ -47,6 +123,29 @@ pub enum Stage {
         #[command(subcommand)]
         command: Operator,
     },
+
+    /// Sql
+    #[command(about = "Run a query on the scheduler and print its result")]
+    Sql { query: String },
+}
+
+impl Stage {
+    /// Settings of a started scheduler, the jobs it runs at once are a
+    /// setting of the whole scheduler rather than of a session
+    pub fn scheduler_settings(&self) -> HashMap<String, String> {
+        let mut settings = HashMap::new();
+        if let Stage::Scheduler {
+            max_concurrent_jobs: Some(max_concurrent_jobs),
+            ..
+        } = self
+        {
+            settings.insert(
+                MAX_CONCURRENT_JOBS.to_string(),
+                max_concurrent_jobs.to_string(),
+            );
+        }
+        settings
+    }
 }
 
 #[derive(Subcommand, PartialEq, Debug)]
@@ -59,3 +158,74 @@ pub enum Operator {
     #[command(about = "Stop Service")]
     Stop,
 }
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    #[test]
+    fn test_settings() {
+        let args = Args::parse_from([
+            "rapidash",
+            "--batch-size",
+            "1024",
+            "--tls-ca",
+            "ca.pem",
+            "--token",
+            "secret",
+            "--priority",
+            "3",
+            "sql",
+            "select 1",
+        ]);
+        assert_eq!(
+            args.command,
+            Stage::Sql {
+                query: "select 1".to_string()
+            }
+        );
+        assert_eq!(args.scheduler(), ("127.0.0.1", 51008));
+
+        // the connection gets the TLS and the token
+        let config = args.config().unwrap();
+        assert_eq!(config.tls_ca(), Some("ca.pem".to_string()));
+        assert_eq!(config.auth_token(), Some("secret".to_string()));
+
+        // the session gets the batch size and the priority, not the token
+        assert_eq!(
+            args.session_settings(),
+            HashMap::from([
+                (DEFAULT_BATCH_SIZE.to_string(), "1024".to_string()),
+                (JOB_PRIORITY.to_string(), "3".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_scheduler_settings() {
+        let args = Args::parse_from([
+            "rapidash",
+            "scheduler",
+            "--max-concurrent-jobs",
+            "4",
+            "start",
+        ]);
+        assert_eq!(
+            args.command.scheduler_settings(),
+            HashMap::from([(MAX_CONCURRENT_JOBS.to_string(), "4".to_string())])
+        );
+        // not a setting of the sessions
+        assert!(args.session_settings().is_empty());
+
+        let result = Args::try_parse_from([
+            "rapidash",
+            "scheduler",
+            "--max-concurrent-jobs",
+            "0",
+            "start",
+        ]);
+        assert!(result.is_err());
+    }
+}
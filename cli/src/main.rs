@@ -9,21 +9,26 @@ static GLOBAL: MiMalloc = MiMalloc;
 
 use clap::Parser;
 use common::error;
+use datafusion::arrow::util::pretty::print_batches;
 use rapidash::cli::{Args, Operator, Stage};
+use rapidash_client::context::RapidashContext;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Args::parse();
 
     // check scheduler service
-    match args.command {
-        Stage::Scheduler { command } => {
+    match &args.command {
+        Stage::Scheduler { command, .. } => {
             println!("scheduler command :{:?}", command);
             match command {
-                Operator::Start { .. } => {
-                    println!("start scheduler");
+                Operator::Start => {
+                    println!(
+                        "start scheduler with settings {:?}",
+                        args.command.scheduler_settings()
+                    );
                 }
-                Operator::Stop { .. } => {
+                Operator::Stop => {
                     println!("stop scheduler");
                 }
             }
@@ -31,14 +36,23 @@ async fn main() -> Result<()> {
         Stage::Executor { command } => {
             println!("executor command :{:?}", command);
             match command {
-                Operator::Start { .. } => {
+                Operator::Start => {
                     println!("start executor");
                 }
-                Operator::Stop { .. } => {
+                Operator::Stop => {
                     println!("stop executor");
                 }
             }
         }
+        Stage::Sql { query } => {
+            let (host, port) = args.scheduler();
+            let mut context =
+                RapidashContext::connect(&args.config()?, host, port, args.session_settings())
+                    .await?;
+            let batches = context.sql(query).await?;
+            print_batches(&batches).map_err(|e| error::RapidashError::General(e.to_string()))?;
+            context.close().await?;
+        }
     }
 
     Ok(())
@@ -10,24 +10,24 @@ pub fn is_valid_file(dir: &str) -> std::result::Result<(), String> {
     }
 }
 
-pub fn is_valid_data_dir(dir: &str) -> std::result::Result<(), String> {
+pub fn is_valid_data_dir(dir: &str) -> std::result::Result<String, String> {
     if Path::new(dir).is_dir() {
-        Ok(())
+        Ok(dir.to_string())
     } else {
         Err(format!("Invalid data directory '{}'", dir))
     }
 }
 
-pub fn is_valid_batch_size(size: &str) -> std::result::Result<(), String> {
+pub fn is_valid_batch_size(size: &str) -> std::result::Result<usize, String> {
     match size.parse::<usize>() {
-        Ok(size) if size > 0 => Ok(()),
+        Ok(size) if size > 0 => Ok(size),
         _ => Err(format!("Invalid batch size '{}'", size)),
     }
 }
 
-pub fn is_valid_concurrent_tasks_size(size: &str) -> std::result::Result<(), String> {
+pub fn is_valid_concurrent_tasks_size(size: &str) -> std::result::Result<usize, String> {
     match size.parse::<usize>() {
-        Ok(size) if size > 0 => Ok(()),
+        Ok(size) if size > 0 => Ok(size),
         _ => Err(format!("Invalid concurrent_tasks size '{}'", size)),
     }
 }
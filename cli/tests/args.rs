@@ -40,7 +40,8 @@ enum LevelTwo {
 }
 
 fn main() {
-    let args = Args::parse();
+    // cargo test runs the harness-less test without arguments
+    let args = Args::parse_from(["arg_test", "scheduler", "--list", "start"]);
 
     println!("argument {:?}", args);
 }
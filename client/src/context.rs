@@ -1 +1,131 @@
 //! Distributed execution context.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use common::config::Config;
+use common::error::{RapidashError, Result};
+use datafusion::arrow::record_batch::RecordBatch;
+use futures::TryStreamExt;
+use transmit::auth::{AuthenticatedChannel, TokenInterceptor};
+use transmit::compressed;
+use transmit::compression::CompressionConfig;
+use transmit::error::from_status;
+use transmit::handshake::{check_compatible, handshake_request};
+use transmit::proto::scheduler_proto_client::SchedulerProtoClient;
+use transmit::proto::{
+    CloseSessionRequest, CreateSessionRequest, PeerRole, QueryRequest, QueryState,
+    QueryStatusRequest,
+};
+use transmit::tls::TlsConfig;
+
+/// Interval between two polls of the state of a running query
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A session on a scheduler, queries run with the settings of the session
+pub struct RapidashContext {
+    scheduler: SchedulerProtoClient<AuthenticatedChannel>,
+    session_id: String,
+}
+
+impl RapidashContext {
+    /// Connect to the scheduler with the TLS, token and rpc compression
+    /// settings of the config, check it is compatible and create a session with the given settings
+    pub async fn connect(
+        config: &Config,
+        host: &str,
+        port: u16,
+        settings: HashMap<String, String>,
+    ) -> Result<Self> {
+        let tls = TlsConfig::from_config(config);
+        let channel = tls
+            .endpoint(tls.url(host, port))?
+            .connect()
+            .await
+            .map_err(|e| RapidashError::GrpcConnectionError(e.to_string()))?;
+        let mut scheduler = compressed!(
+            SchedulerProtoClient::with_interceptor(channel, TokenInterceptor::from_config(config)?),
+            CompressionConfig::from_config(config)
+        );
+        handshake(&mut scheduler).await?;
+        let session_id = scheduler
+            .create_session(CreateSessionRequest { settings })
+            .await
+            .map_err(|e| from_status(&e))?
+            .into_inner()
+            .session_id;
+
+        Ok(Self {
+            scheduler,
+            session_id,
+        })
+    }
+
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Run the sql in the session and collect its result
+    pub async fn sql(&mut self, sql: &str) -> Result<Vec<RecordBatch>> {
+        let request = QueryRequest {
+            sql: sql.to_string(),
+            session_id: self.session_id.clone(),
+        };
+        let id = self
+            .scheduler
+            .query(request)
+            .await
+            .map_err(|e| from_status(&e))?
+            .into_inner()
+            .id;
+
+        loop {
+            let request = QueryStatusRequest { id: id.clone() };
+            let status = self
+                .scheduler
+                .get_query_status(request)
+                .await
+                .map_err(|e| from_status(&e))?
+                .into_inner();
+            match status.state() {
+                QueryState::Queued | QueryState::Running => tokio::time::sleep(POLL_INTERVAL).await,
+                QueryState::Succeeded => break,
+                QueryState::Failed => return Err(RapidashError::Execution(status.error)),
+                QueryState::Cancelled => return Err(RapidashError::Cancelled),
+            }
+        }
+
+        self.scheduler
+            .fetch_record_batches(id)
+            .await
+            .map_err(|e| from_status(&e))?
+            .try_collect()
+            .await
+            .map_err(|e| RapidashError::General(e.to_string()))
+    }
+
+    /// Close the session on the scheduler
+    pub async fn close(mut self) -> Result<()> {
+        let request = CloseSessionRequest {
+            session_id: self.session_id,
+        };
+        self.scheduler
+            .close_session(request)
+            .await
+            .map_err(|e| from_status(&e))?;
+        Ok(())
+    }
+}
+
+/// Exchange capabilities with the scheduler, both sides check the other
+async fn handshake(scheduler: &mut SchedulerProtoClient<AuthenticatedChannel>) -> Result<()> {
+    let response = scheduler
+        .handshake(handshake_request(PeerRole::Client, ""))
+        .await
+        .map_err(|e| from_status(&e))?
+        .into_inner();
+    let capabilities = response
+        .capabilities
+        .ok_or_else(|| RapidashError::Incompatible("Scheduler sent no capabilities".to_string()))?;
+    check_compatible(PeerRole::Scheduler, &capabilities)
+}
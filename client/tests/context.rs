@@ -0,0 +1,93 @@
+//! Context tests against a local scheduler authenticating its clients
+use std::collections::HashMap;
+use std::fs;
+
+use common::config::{Config, AUTH_TOKEN, AUTH_TOKEN_FILE, JOB_PRIORITY, RPC_COMPRESSION};
+use rapidash_client::context::RapidashContext;
+use scheduler::query::Query;
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::service::interceptor::InterceptedService;
+use tonic::transport::Server;
+use transmit::auth::AuthInterceptor;
+use transmit::compressed;
+use transmit::compression::CompressionConfig;
+use transmit::proto::scheduler_proto_server::SchedulerProtoServer;
+use uuid::Uuid;
+
+/// Start a scheduler accepting the token of alice on a free port, return its
+/// port
+async fn serve() -> u16 {
+    let path = std::env::temp_dir().join(format!("rapidash-tokens-{}", Uuid::new_v4()));
+    fs::write(&path, "alice secret\n").unwrap();
+    let settings = HashMap::from([(
+        AUTH_TOKEN_FILE.to_string(),
+        path.to_string_lossy().to_string(),
+    )]);
+    let config = Config::with_settings(settings).unwrap();
+    let auth = AuthInterceptor::from_config(&config).unwrap();
+    let compression = CompressionConfig::from_config(&config);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(
+        Server::builder()
+            .add_service(InterceptedService::new(
+                compressed!(
+                    SchedulerProtoServer::new(Query::new(config).unwrap()),
+                    compression
+                ),
+                auth,
+            ))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+    port
+}
+
+#[tokio::test]
+async fn test_connect_with_token() {
+    let port = serve().await;
+    let session = HashMap::from([(JOB_PRIORITY.to_string(), "3".to_string())]);
+
+    let config = Config::new().unwrap();
+    let error = RapidashContext::connect(&config, "127.0.0.1", port, session.clone())
+        .await
+        .err()
+        .unwrap();
+    assert!(
+        error.to_string().contains("Missing bearer token"),
+        "{}",
+        error
+    );
+
+    let settings = HashMap::from([(AUTH_TOKEN.to_string(), "secret".to_string())]);
+    let config = Config::with_settings(settings).unwrap();
+    let context = RapidashContext::connect(&config, "127.0.0.1", port, session)
+        .await
+        .unwrap();
+    assert!(!context.session_id().is_empty());
+    context.close().await.unwrap();
+
+    // rpcs are compressed as configured
+    let settings = HashMap::from([
+        (AUTH_TOKEN.to_string(), "secret".to_string()),
+        (RPC_COMPRESSION.to_string(), "gzip".to_string()),
+    ]);
+    let context = RapidashContext::connect(
+        &Config::with_settings(settings).unwrap(),
+        "127.0.0.1",
+        port,
+        HashMap::new(),
+    )
+    .await
+    .unwrap();
+    context.close().await.unwrap();
+
+    // the scheduler validates the settings of the session
+    let session = HashMap::from([(JOB_PRIORITY.to_string(), "high".to_string())]);
+    let error = RapidashContext::connect(&config, "127.0.0.1", port, session)
+        .await
+        .err()
+        .unwrap();
+    assert!(error.to_string().contains(JOB_PRIORITY), "{}", error);
+}
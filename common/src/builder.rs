@@ -3,7 +3,7 @@
 use std::collections::HashMap;
 use std::result;
 
-use error::Result;
+use crate::error::Result;
 
 use crate::config::Config;
 
@@ -1,15 +1,74 @@
 //! Configuration for the `config` crate.
 use std::collections::HashMap;
 
+use crate::error::{RapidashError, Result};
 use arrow_schema::DataType;
-use error::{RapidashError, Result};
 
 use crate::builder::ConfigBuilder;
 use crate::builder::ParseResult;
 use crate::entry::ConfigEntry;
 
 pub const JOB_NAME: &str = "rapidash.job.name";
+pub const JOB_PRIORITY: &str = "rapidash.job.priority";
+pub const JOB_WEIGHT: &str = "rapidash.job.weight";
+pub const RESULT_RETENTION: &str = "rapidash.result.retention";
+pub const STATEMENT_RETENTION: &str = "rapidash.statement.retention";
 pub const DEFAULT_BATCH_SIZE: &str = "rapidash.batch.size";
+pub const EXECUTOR_HEARTBEAT_INTERVAL: &str = "rapidash.executor.heartbeat.interval";
+pub const EXECUTOR_TIMEOUT: &str = "rapidash.executor.timeout";
+pub const TLS_CERT: &str = "rapidash.tls.cert";
+pub const TLS_KEY: &str = "rapidash.tls.key";
+pub const TLS_CA: &str = "rapidash.tls.ca";
+pub const TLS_CLIENT_AUTH: &str = "rapidash.tls.client.auth";
+pub const TLS_DOMAIN: &str = "rapidash.tls.domain";
+pub const RPC_CONNECT_TIMEOUT: &str = "rapidash.rpc.connect.timeout";
+pub const RPC_REQUEST_TIMEOUT: &str = "rapidash.rpc.request.timeout";
+pub const RPC_KEEPALIVE_INTERVAL: &str = "rapidash.rpc.keepalive.interval";
+pub const RPC_KEEPALIVE_TIMEOUT: &str = "rapidash.rpc.keepalive.timeout";
+pub const RPC_TCP_KEEPALIVE: &str = "rapidash.rpc.tcp.keepalive";
+pub const RPC_RETRY_MAX: &str = "rapidash.rpc.retry.max";
+pub const RPC_RETRY_BACKOFF: &str = "rapidash.rpc.retry.backoff";
+pub const RPC_RETRY_BACKOFF_MAX: &str = "rapidash.rpc.retry.backoff.max";
+pub const RPC_BREAKER_FAILURES: &str = "rapidash.rpc.breaker.failures";
+pub const RPC_BREAKER_RESET: &str = "rapidash.rpc.breaker.reset";
+pub const RPC_COMPRESSION: &str = "rapidash.rpc.compression";
+pub const SHUFFLE_COMPRESSION: &str = "rapidash.shuffle.compression";
+pub const SHUFFLE_PARTITIONS: &str = "rapidash.shuffle.partitions";
+pub const AUTH_TOKEN: &str = "rapidash.auth.token";
+pub const AUTH_TOKEN_FILE: &str = "rapidash.auth.token.file";
+pub const AUTH_HMAC_SECRET_FILE: &str = "rapidash.auth.hmac.secret.file";
+pub const STATE_BACKEND: &str = "rapidash.state.backend";
+pub const STATE_DIR: &str = "rapidash.state.dir";
+pub const SCHEDULING_POLICY: &str = "rapidash.scheduler.policy";
+pub const MAX_CONCURRENT_JOBS: &str = "rapidash.scheduler.max.concurrent.jobs";
+pub const FAIR_SHARE: &str = "rapidash.scheduler.fair.share";
+pub const PRIORITY_AGING: &str = "rapidash.scheduler.priority.aging";
+pub const QUOTA_CLUSTER_MEMORY: &str = "rapidash.quota.cluster.memory";
+pub const QUOTA_CLUSTER_TASKS: &str = "rapidash.quota.cluster.tasks";
+pub const QUOTA_USER_MEMORY: &str = "rapidash.quota.user.memory";
+pub const QUOTA_USER_TASKS: &str = "rapidash.quota.user.tasks";
+pub const QUOTA_EXCEEDED: &str = "rapidash.quota.exceeded";
+pub const ADMIN_PRINCIPALS: &str = "rapidash.admin.principals";
+pub const EXECUTOR_PRINCIPALS: &str = "rapidash.executor.principals";
+pub const SCHEDULER_PRINCIPALS: &str = "rapidash.scheduler.principals";
+pub const TASK_MAX_ATTEMPTS: &str = "rapidash.task.max.attempts";
+pub const SPECULATION: &str = "rapidash.speculation";
+pub const SPECULATION_INTERVAL: &str = "rapidash.speculation.interval";
+pub const SPECULATION_QUANTILE: &str = "rapidash.speculation.quantile";
+pub const SPECULATION_MULTIPLIER: &str = "rapidash.speculation.multiplier";
+
+/// Values of `rapidash.rpc.compression`
+pub const RPC_COMPRESSION_CODECS: &[&str] = &["none", "gzip"];
+/// Values of `rapidash.shuffle.compression`
+pub const SHUFFLE_COMPRESSION_CODECS: &[&str] = &["none", "lz4", "zstd"];
+/// Values of `rapidash.state.backend`
+pub const STATE_BACKENDS: &[&str] = &["memory", "sled"];
+/// Values of `rapidash.scheduler.policy`
+pub const SCHEDULING_POLICIES: &[&str] = &["push", "pull", "locality"];
+/// Values of `rapidash.scheduler.fair.share`
+pub const FAIR_SHARE_GROUPS: &[&str] = &["user", "session"];
+/// Values of `rapidash.quota.exceeded`
+pub const QUOTA_ACTIONS: &[&str] = &["queue", "reject"];
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Config {
@@ -51,10 +110,63 @@ impl Config {
                 )));
             }
         }
+        for (name, codecs) in [
+            (RPC_COMPRESSION, RPC_COMPRESSION_CODECS),
+            (SHUFFLE_COMPRESSION, SHUFFLE_COMPRESSION_CODECS),
+            (STATE_BACKEND, STATE_BACKENDS),
+            (SCHEDULING_POLICY, SCHEDULING_POLICIES),
+            (FAIR_SHARE, FAIR_SHARE_GROUPS),
+            (QUOTA_EXCEEDED, QUOTA_ACTIONS),
+        ] {
+            if let Some(v) = settings.get(name) {
+                if !codecs.contains(&v.as_str()) {
+                    return Err(RapidashError::General(format!(
+                        "Invalid value '{}' for configuration setting '{}', expected one of {}",
+                        v,
+                        name,
+                        codecs.join(", ")
+                    )));
+                }
+            }
+        }
+        // intervals of background tickers must not be zero, nor the jobs run
+        // at once or the queue would never move
+        for name in [
+            EXECUTOR_HEARTBEAT_INTERVAL,
+            SPECULATION_INTERVAL,
+            MAX_CONCURRENT_JOBS,
+        ] {
+            if let Some(v) = settings.get(name) {
+                if v.parse::<usize>() == Ok(0) {
+                    return Err(RapidashError::General(format!(
+                        "Invalid value '{}' for configuration setting '{}', expected a positive number",
+                        v, name
+                    )));
+                }
+            }
+        }
 
         Ok(Self { settings })
     }
 
+    /// Split command line arguments into positional arguments and `key=value` settings
+    pub fn split_args<I>(args: I) -> (Vec<String>, HashMap<String, String>)
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut positional = vec![];
+        let mut settings = HashMap::new();
+        for arg in args {
+            match arg.split_once('=') {
+                Some((key, value)) => {
+                    settings.insert(key.to_string(), value.to_string());
+                }
+                None => positional.push(arg),
+            }
+        }
+        (positional, settings)
+    }
+
     pub fn parse_value(val: &str, data_type: DataType) -> ParseResult<()> {
         match data_type {
             DataType::UInt16 => {
@@ -84,9 +196,147 @@ impl Config {
             ConfigEntry::new(JOB_NAME.to_string(),
                              "Sets the job name that will appear in the web user interface for any submitted jobs".to_string(),
                              DataType::Utf8, None),
+            ConfigEntry::new(JOB_PRIORITY.to_string(),
+                             "Sets the priority of submitted jobs, jobs of higher priority run first".to_string(),
+                             DataType::UInt16, Some("0".to_string())),
+            ConfigEntry::new(JOB_WEIGHT.to_string(),
+                             "Sets the weight of the fair share of submitted jobs".to_string(),
+                             DataType::UInt16, Some("1".to_string())),
+            ConfigEntry::new(RESULT_RETENTION.to_string(),
+                             "Sets the seconds the result of a succeeded job is kept for its client to fetch".to_string(),
+                             DataType::UInt16, Some("600".to_string())),
+            ConfigEntry::new(STATEMENT_RETENTION.to_string(),
+                             "Sets the seconds an unused prepared statement of flight sql is kept".to_string(),
+                             DataType::UInt16, Some("3600".to_string())),
             ConfigEntry::new(DEFAULT_BATCH_SIZE.to_string(),
                              "Sets the default batch size".to_string(),
                              DataType::UInt16, Some("8192".to_string())),
+            ConfigEntry::new(EXECUTOR_HEARTBEAT_INTERVAL.to_string(),
+                             "Sets the seconds between two heartbeats of an executor".to_string(),
+                             DataType::UInt16, Some("5".to_string())),
+            ConfigEntry::new(EXECUTOR_TIMEOUT.to_string(),
+                             "Sets the seconds without heartbeat before an executor is dropped".to_string(),
+                             DataType::UInt16, Some("30".to_string())),
+            ConfigEntry::new(TLS_CERT.to_string(),
+                             "Sets the path of the PEM certificate of servers, and of clients with mutual TLS".to_string(),
+                             DataType::Utf8, None),
+            ConfigEntry::new(TLS_KEY.to_string(),
+                             "Sets the path of the PEM private key of the certificate".to_string(),
+                             DataType::Utf8, None),
+            ConfigEntry::new(TLS_CA.to_string(),
+                             "Sets the path of the PEM CA certificate that clients, and servers with mutual TLS, trust".to_string(),
+                             DataType::Utf8, None),
+            ConfigEntry::new(TLS_CLIENT_AUTH.to_string(),
+                             "Requires clients to present a certificate signed by the CA".to_string(),
+                             DataType::Boolean, Some("false".to_string())),
+            ConfigEntry::new(TLS_DOMAIN.to_string(),
+                             "Sets the name server certificates are verified against instead of the host".to_string(),
+                             DataType::Utf8, None),
+            ConfigEntry::new(RPC_CONNECT_TIMEOUT.to_string(),
+                             "Sets the seconds to wait for a connection to a server".to_string(),
+                             DataType::UInt16, Some("20".to_string())),
+            ConfigEntry::new(RPC_REQUEST_TIMEOUT.to_string(),
+                             "Sets the seconds to wait for the response of a request".to_string(),
+                             DataType::UInt16, Some("20".to_string())),
+            ConfigEntry::new(RPC_KEEPALIVE_INTERVAL.to_string(),
+                             "Sets the seconds between two http2 keepalive pings".to_string(),
+                             DataType::UInt16, Some("300".to_string())),
+            ConfigEntry::new(RPC_KEEPALIVE_TIMEOUT.to_string(),
+                             "Sets the seconds to wait for the answer of a keepalive ping".to_string(),
+                             DataType::UInt16, Some("20".to_string())),
+            ConfigEntry::new(RPC_TCP_KEEPALIVE.to_string(),
+                             "Sets the idle seconds before tcp keepalive probes of rpc connections, 0 disables them".to_string(),
+                             DataType::UInt16, Some("3600".to_string())),
+            ConfigEntry::new(RPC_RETRY_MAX.to_string(),
+                             "Sets the number of retries of an idempotent request".to_string(),
+                             DataType::UInt16, Some("3".to_string())),
+            ConfigEntry::new(RPC_RETRY_BACKOFF.to_string(),
+                             "Sets the milliseconds of backoff before the first retry, doubled by every retry".to_string(),
+                             DataType::UInt16, Some("100".to_string())),
+            ConfigEntry::new(RPC_RETRY_BACKOFF_MAX.to_string(),
+                             "Sets the maximum milliseconds of backoff before a retry".to_string(),
+                             DataType::UInt16, Some("5000".to_string())),
+            ConfigEntry::new(RPC_BREAKER_FAILURES.to_string(),
+                             "Sets the consecutive failures after which a server is not called anymore".to_string(),
+                             DataType::UInt16, Some("5".to_string())),
+            ConfigEntry::new(RPC_BREAKER_RESET.to_string(),
+                             "Sets the seconds before a server is called again after too many failures".to_string(),
+                             DataType::UInt16, Some("30".to_string())),
+            ConfigEntry::new(RPC_COMPRESSION.to_string(),
+                             "Sets the compression of rpc messages, none or gzip".to_string(),
+                             DataType::Utf8, Some("none".to_string())),
+            ConfigEntry::new(SHUFFLE_COMPRESSION.to_string(),
+                             "Sets the arrow IPC body compression of shuffle data, none, lz4 or zstd".to_string(),
+                             DataType::Utf8, Some("none".to_string())),
+            ConfigEntry::new(SHUFFLE_PARTITIONS.to_string(),
+                             "Sets the partitions of the scans and shuffles of jobs, the cores of the scheduler by default".to_string(),
+                             DataType::UInt16, None),
+            ConfigEntry::new(AUTH_TOKEN.to_string(),
+                             "Sets the token clients authenticate to the scheduler with, and the scheduler to the executors".to_string(),
+                             DataType::Utf8, None),
+            ConfigEntry::new(AUTH_TOKEN_FILE.to_string(),
+                             "Sets the path of the file of static tokens the scheduler, or the executor, accepts".to_string(),
+                             DataType::Utf8, None),
+            ConfigEntry::new(AUTH_HMAC_SECRET_FILE.to_string(),
+                             "Sets the path of the secret HMAC signed tokens are verified with".to_string(),
+                             DataType::Utf8, None),
+            ConfigEntry::new(STATE_BACKEND.to_string(),
+                             "Sets where the scheduler keeps its state, memory or sled on disk".to_string(),
+                             DataType::Utf8, Some("memory".to_string())),
+            ConfigEntry::new(STATE_DIR.to_string(),
+                             "Sets the directory of the sled state backend".to_string(),
+                             DataType::Utf8, Some("rapidash-state".to_string())),
+            ConfigEntry::new(SCHEDULING_POLICY.to_string(),
+                             "Sets how tasks are assigned to executors, push round robin, pull by executors or locality aware".to_string(),
+                             DataType::Utf8, Some("push".to_string())),
+            ConfigEntry::new(MAX_CONCURRENT_JOBS.to_string(),
+                             "Sets the number of jobs running at the same time, others wait in the queue".to_string(),
+                             DataType::UInt16, Some("16".to_string())),
+            ConfigEntry::new(FAIR_SHARE.to_string(),
+                             "Sets whether queued jobs share the scheduler fairly by user or by session".to_string(),
+                             DataType::Utf8, Some("user".to_string())),
+            ConfigEntry::new(PRIORITY_AGING.to_string(),
+                             "Sets the seconds a queued job waits to gain one priority level, 0 disables aging".to_string(),
+                             DataType::UInt16, Some("60".to_string())),
+            ConfigEntry::new(QUOTA_CLUSTER_MEMORY.to_string(),
+                             "Sets the MiB of estimated memory of all running jobs, 0 for no quota".to_string(),
+                             DataType::UInt16, Some("0".to_string())),
+            ConfigEntry::new(QUOTA_CLUSTER_TASKS.to_string(),
+                             "Sets the number of tasks of all running jobs, 0 for no quota".to_string(),
+                             DataType::UInt16, Some("0".to_string())),
+            ConfigEntry::new(QUOTA_USER_MEMORY.to_string(),
+                             "Sets the MiB of estimated memory of the running jobs of a user, 0 for no quota".to_string(),
+                             DataType::UInt16, Some("0".to_string())),
+            ConfigEntry::new(QUOTA_USER_TASKS.to_string(),
+                             "Sets the number of tasks of the running jobs of a user, 0 for no quota".to_string(),
+                             DataType::UInt16, Some("0".to_string())),
+            ConfigEntry::new(QUOTA_EXCEEDED.to_string(),
+                             "Sets whether a job over the quotas waits in the queue or is rejected".to_string(),
+                             DataType::Utf8, Some("queue".to_string())),
+            ConfigEntry::new(ADMIN_PRINCIPALS.to_string(),
+                             "Sets the comma separated principals allowed to call admin rpcs, which need authentication".to_string(),
+                             DataType::Utf8, None),
+            ConfigEntry::new(EXECUTOR_PRINCIPALS.to_string(),
+                             "Sets the comma separated principals allowed to call executor rpcs once clients authenticate".to_string(),
+                             DataType::Utf8, None),
+            ConfigEntry::new(SCHEDULER_PRINCIPALS.to_string(),
+                             "Sets the comma separated principals allowed to launch and cancel tasks on executors once they authenticate".to_string(),
+                             DataType::Utf8, None),
+            ConfigEntry::new(TASK_MAX_ATTEMPTS.to_string(),
+                             "Sets the attempts of a failed task, and of a stage whose input was lost, before its job fails".to_string(),
+                             DataType::UInt16, Some("4".to_string())),
+            ConfigEntry::new(SPECULATION.to_string(),
+                             "Starts copies of slow tasks on other executors, the first result is kept".to_string(),
+                             DataType::Boolean, Some("false".to_string())),
+            ConfigEntry::new(SPECULATION_INTERVAL.to_string(),
+                             "Sets the milliseconds between two checks for slow tasks".to_string(),
+                             DataType::UInt16, Some("100".to_string())),
+            ConfigEntry::new(SPECULATION_QUANTILE.to_string(),
+                             "Sets the percent of the tasks of a stage that finish before its slow tasks are copied".to_string(),
+                             DataType::UInt16, Some("75".to_string())),
+            ConfigEntry::new(SPECULATION_MULTIPLIER.to_string(),
+                             "Sets the percent of the median runtime of a stage above which its tasks are slow".to_string(),
+                             DataType::UInt16, Some("150".to_string())),
         ];
         entries
             .iter()
@@ -98,10 +348,227 @@ impl Config {
         &self.settings
     }
 
+    pub fn job_name(&self) -> Option<String> {
+        self.get_optional_setting(JOB_NAME)
+    }
+
+    pub fn job_priority(&self) -> usize {
+        self.get_usize_setting(JOB_PRIORITY)
+    }
+
+    pub fn job_weight(&self) -> usize {
+        self.get_usize_setting(JOB_WEIGHT)
+    }
+
+    pub fn result_retention(&self) -> usize {
+        self.get_usize_setting(RESULT_RETENTION)
+    }
+
+    pub fn statement_retention(&self) -> usize {
+        self.get_usize_setting(STATEMENT_RETENTION)
+    }
+
     pub fn default_batch_size(&self) -> usize {
         self.get_usize_setting(DEFAULT_BATCH_SIZE)
     }
 
+    pub fn executor_heartbeat_interval(&self) -> usize {
+        self.get_usize_setting(EXECUTOR_HEARTBEAT_INTERVAL)
+    }
+
+    pub fn executor_timeout(&self) -> usize {
+        self.get_usize_setting(EXECUTOR_TIMEOUT)
+    }
+
+    pub fn rpc_connect_timeout(&self) -> usize {
+        self.get_usize_setting(RPC_CONNECT_TIMEOUT)
+    }
+
+    pub fn rpc_request_timeout(&self) -> usize {
+        self.get_usize_setting(RPC_REQUEST_TIMEOUT)
+    }
+
+    pub fn rpc_keepalive_interval(&self) -> usize {
+        self.get_usize_setting(RPC_KEEPALIVE_INTERVAL)
+    }
+
+    pub fn rpc_keepalive_timeout(&self) -> usize {
+        self.get_usize_setting(RPC_KEEPALIVE_TIMEOUT)
+    }
+
+    /// None when tcp keepalive is disabled
+    pub fn rpc_tcp_keepalive(&self) -> Option<usize> {
+        match self.get_usize_setting(RPC_TCP_KEEPALIVE) {
+            0 => None,
+            secs => Some(secs),
+        }
+    }
+
+    pub fn rpc_retry_max(&self) -> usize {
+        self.get_usize_setting(RPC_RETRY_MAX)
+    }
+
+    pub fn rpc_retry_backoff(&self) -> usize {
+        self.get_usize_setting(RPC_RETRY_BACKOFF)
+    }
+
+    pub fn rpc_retry_backoff_max(&self) -> usize {
+        self.get_usize_setting(RPC_RETRY_BACKOFF_MAX)
+    }
+
+    pub fn rpc_breaker_failures(&self) -> usize {
+        self.get_usize_setting(RPC_BREAKER_FAILURES)
+    }
+
+    pub fn rpc_breaker_reset(&self) -> usize {
+        self.get_usize_setting(RPC_BREAKER_RESET)
+    }
+
+    pub fn tls_cert(&self) -> Option<String> {
+        self.get_optional_setting(TLS_CERT)
+    }
+
+    pub fn tls_key(&self) -> Option<String> {
+        self.get_optional_setting(TLS_KEY)
+    }
+
+    pub fn tls_ca(&self) -> Option<String> {
+        self.get_optional_setting(TLS_CA)
+    }
+
+    pub fn tls_client_auth(&self) -> bool {
+        self.get_bool_setting(TLS_CLIENT_AUTH)
+    }
+
+    pub fn tls_domain(&self) -> Option<String> {
+        self.get_optional_setting(TLS_DOMAIN)
+    }
+
+    pub fn rpc_compression(&self) -> String {
+        self.get_string_setting(RPC_COMPRESSION)
+    }
+
+    pub fn shuffle_compression(&self) -> String {
+        self.get_string_setting(SHUFFLE_COMPRESSION)
+    }
+
+    /// None when jobs are split by the cores of the scheduler
+    pub fn shuffle_partitions(&self) -> Option<usize> {
+        self.get_optional_setting(SHUFFLE_PARTITIONS)
+            // infallible because we validate all configs in the constructor,
+            // a job has at least one partition
+            .map(|v| v.parse::<usize>().unwrap().max(1))
+    }
+
+    pub fn auth_token(&self) -> Option<String> {
+        self.get_optional_setting(AUTH_TOKEN)
+    }
+
+    pub fn auth_token_file(&self) -> Option<String> {
+        self.get_optional_setting(AUTH_TOKEN_FILE)
+    }
+
+    pub fn auth_hmac_secret_file(&self) -> Option<String> {
+        self.get_optional_setting(AUTH_HMAC_SECRET_FILE)
+    }
+
+    pub fn state_backend(&self) -> String {
+        self.get_string_setting(STATE_BACKEND)
+    }
+
+    pub fn state_dir(&self) -> String {
+        self.get_string_setting(STATE_DIR)
+    }
+
+    pub fn scheduling_policy(&self) -> String {
+        self.get_string_setting(SCHEDULING_POLICY)
+    }
+
+    pub fn max_concurrent_jobs(&self) -> usize {
+        self.get_usize_setting(MAX_CONCURRENT_JOBS)
+    }
+
+    pub fn fair_share(&self) -> String {
+        self.get_string_setting(FAIR_SHARE)
+    }
+
+    pub fn priority_aging(&self) -> usize {
+        self.get_usize_setting(PRIORITY_AGING)
+    }
+
+    pub fn quota_cluster_memory(&self) -> usize {
+        self.get_usize_setting(QUOTA_CLUSTER_MEMORY)
+    }
+
+    pub fn quota_cluster_tasks(&self) -> usize {
+        self.get_usize_setting(QUOTA_CLUSTER_TASKS)
+    }
+
+    pub fn quota_user_memory(&self) -> usize {
+        self.get_usize_setting(QUOTA_USER_MEMORY)
+    }
+
+    pub fn quota_user_tasks(&self) -> usize {
+        self.get_usize_setting(QUOTA_USER_TASKS)
+    }
+
+    pub fn quota_exceeded(&self) -> String {
+        self.get_string_setting(QUOTA_EXCEEDED)
+    }
+
+    pub fn task_max_attempts(&self) -> usize {
+        self.get_usize_setting(TASK_MAX_ATTEMPTS)
+    }
+
+    pub fn speculation(&self) -> bool {
+        self.get_bool_setting(SPECULATION)
+    }
+
+    pub fn speculation_interval(&self) -> usize {
+        self.get_usize_setting(SPECULATION_INTERVAL)
+    }
+
+    pub fn speculation_quantile(&self) -> usize {
+        self.get_usize_setting(SPECULATION_QUANTILE)
+    }
+
+    pub fn speculation_multiplier(&self) -> usize {
+        self.get_usize_setting(SPECULATION_MULTIPLIER)
+    }
+
+    /// Principals allowed to call admin rpcs
+    pub fn admin_principals(&self) -> Vec<String> {
+        self.get_principals(ADMIN_PRINCIPALS)
+    }
+
+    /// Principals allowed to register executors and report their tasks
+    pub fn executor_principals(&self) -> Vec<String> {
+        self.get_principals(EXECUTOR_PRINCIPALS)
+    }
+
+    /// Principals allowed to launch and cancel tasks on executors
+    pub fn scheduler_principals(&self) -> Vec<String> {
+        self.get_principals(SCHEDULER_PRINCIPALS)
+    }
+
+    /// Comma separated principals of a setting, none when it is not set
+    fn get_principals(&self, key: &str) -> Vec<String> {
+        self.get_optional_setting(key)
+            .map(|principals| {
+                principals
+                    .split(',')
+                    .map(|principal| principal.trim().to_string())
+                    .filter(|principal| !principal.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Value of a setting without default, None when it is not set
+    pub fn get_optional_setting(&self, key: &str) -> Option<String> {
+        self.settings.get(key).cloned()
+    }
+
     pub fn get_usize_setting(&self, key: &str) -> usize {
         if let Some(v) = self.settings.get(key) {
             // infallible because we validate all configs in the constructor
@@ -139,125 +606,27 @@ impl Config {
     }
 }
 
-
-/// Configuration options struct. This can contain values for built-in and custom options
-#[derive(Clone)]
-pub struct ConfigOptions {
-    options: HashMap<String, ScalarValue>,
-}
-
-/// Print the configurations in an ordered way so that we can directly compare the equality of two ConfigOptions by their debug strings
-impl Debug for ConfigOptions {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("ConfigOptions")
-            .field(
-                "options",
-                &format!("{:?}", BTreeMap::from_iter(self.options.iter())),
-            )
-            .finish()
-    }
-}
-
-impl Default for ConfigOptions {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-impl ConfigOptions {
-    /// Create new ConfigOptions struct
-    pub fn new() -> Self {
-        let built_in = BuiltInConfigs::new();
-        let mut options = HashMap::with_capacity(built_in.config_definitions.len());
-        for config_def in &built_in.config_definitions {
-            options.insert(config_def.key.clone(), config_def.default_value.clone());
-        }
-        Self { options }
-    }
-
-    /// Create a new [`ConfigOptions`] wrapped in an RwLock and Arc
-    pub fn into_shareable(self) -> Arc<RwLock<Self>> {
-        Arc::new(RwLock::new(self))
-    }
-
-    /// Create new ConfigOptions struct, taking values from
-    /// environment variables where possible.
-    ///
-    /// For example, setting `DATAFUSION_EXECUTION_BATCH_SIZE` will
-    /// control `datafusion.execution.batch_size`.
-    pub fn from_env() -> Self {
-        let built_in = BuiltInConfigs::new();
-        let mut options = HashMap::with_capacity(built_in.config_definitions.len());
-        for config_def in &built_in.config_definitions {
-            let config_value = {
-                let mut env_key = config_def.key.replace('.', "_");
-                env_key.make_ascii_uppercase();
-                match env::var(&env_key) {
-                    Ok(value) => match ScalarValue::try_from_string(
-                        value.clone(),
-                        &config_def.data_type,
-                    ) {
-                        Ok(parsed) => parsed,
-                        Err(_) => {
-                            warn!("Warning: could not parse environment variable {}={} to type {}.", env_key, value, config_def.data_type);
-                            config_def.default_value.clone()
-                        }
-                    },
-                    Err(_) => config_def.default_value.clone(),
-                }
-            };
-            options.insert(config_def.key.clone(), config_value);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_positive_settings() {
+        let config = Config::builder()
+            .set(EXECUTOR_HEARTBEAT_INTERVAL, "1")
+            .build()
+            .unwrap();
+        assert_eq!(config.executor_heartbeat_interval(), 1);
+        for name in [
+            EXECUTOR_HEARTBEAT_INTERVAL,
+            SPECULATION_INTERVAL,
+            MAX_CONCURRENT_JOBS,
+        ] {
+            assert!(
+                Config::builder().set(name, "0").build().is_err(),
+                "{}",
+                name
+            );
         }
-        Self { options }
-    }
-
-    /// set a configuration option
-    pub fn set(&mut self, key: &str, value: ScalarValue) {
-        self.options.insert(key.to_string(), value);
-    }
-
-    /// set a boolean configuration option
-    pub fn set_bool(&mut self, key: &str, value: bool) {
-        self.set(key, ScalarValue::Boolean(Some(value)))
-    }
-
-    /// set a `u64` configuration option
-    pub fn set_u64(&mut self, key: &str, value: u64) {
-        self.set(key, ScalarValue::UInt64(Some(value)))
-    }
-
-    /// set a `String` configuration option
-    pub fn set_string(&mut self, key: &str, value: impl Into<String>) {
-        self.set(key, ScalarValue::Utf8(Some(value.into())))
-    }
-
-    /// get a configuration option
-    pub fn get(&self, key: &str) -> Option<ScalarValue> {
-        self.options.get(key).cloned()
-    }
-
-    /// get a boolean configuration option
-    pub fn get_bool(&self, key: &str) -> Option<bool> {
-        get_conf_value!(self, Boolean, key, "bool")
-    }
-
-    /// get a u64 configuration option
-    pub fn get_u64(&self, key: &str) -> Option<u64> {
-        get_conf_value!(self, UInt64, key, "u64")
-    }
-
-    /// get a string configuration option
-    pub fn get_string(&self, key: &str) -> Option<String> {
-        get_conf_value!(self, Utf8, key, "string")
-    }
-
-    /// Access the underlying hashmap
-    pub fn options(&self) -> &HashMap<String, ScalarValue> {
-        &self.options
-    }
-
-    /// Tests if the key exists in the configuration
-    pub fn exists(&self, key: &str) -> bool {
-        self.options().contains_key(key)
     }
 }
@@ -31,3 +31,31 @@ pub struct Context {
     state: Arc<Mutex<ContextState>>,
     context: Arc<SessionContext>,
 }
+
+impl Context {
+    /// Context of a client of the scheduler at `scheduler_host:scheduler_port`
+    pub fn new(scheduler_host: String, scheduler_port: u16, config: &Config) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(ContextState::new(
+                scheduler_host,
+                scheduler_port,
+                config,
+            ))),
+            context: Arc::new(SessionContext::new()),
+        }
+    }
+
+    pub fn config(&self) -> Config {
+        self.state.lock().unwrap().config().clone()
+    }
+
+    /// Host and port of the scheduler
+    pub fn scheduler(&self) -> (String, u16) {
+        let state = self.state.lock().unwrap();
+        (state.scheduler_host.clone(), state.scheduler_port)
+    }
+
+    pub fn session(&self) -> Arc<SessionContext> {
+        self.context.clone()
+    }
+}
@@ -83,75 +83,75 @@ mod tests {
 
     #[test]
     fn test_shift_months() {
-        let base = NaiveDate::from_ymd(2020, 1, 31);
-
-        assert_eq!(shift_months(base, 0), NaiveDate::from_ymd(2020, 1, 31));
-        assert_eq!(shift_months(base, 1), NaiveDate::from_ymd(2020, 2, 29));
-        assert_eq!(shift_months(base, 2), NaiveDate::from_ymd(2020, 3, 31));
-        assert_eq!(shift_months(base, 3), NaiveDate::from_ymd(2020, 4, 30));
-        assert_eq!(shift_months(base, 4), NaiveDate::from_ymd(2020, 5, 31));
-        assert_eq!(shift_months(base, 5), NaiveDate::from_ymd(2020, 6, 30));
-        assert_eq!(shift_months(base, 6), NaiveDate::from_ymd(2020, 7, 31));
-        assert_eq!(shift_months(base, 7), NaiveDate::from_ymd(2020, 8, 31));
-        assert_eq!(shift_months(base, 8), NaiveDate::from_ymd(2020, 9, 30));
-        assert_eq!(shift_months(base, 9), NaiveDate::from_ymd(2020, 10, 31));
-        assert_eq!(shift_months(base, 10), NaiveDate::from_ymd(2020, 11, 30));
-        assert_eq!(shift_months(base, 11), NaiveDate::from_ymd(2020, 12, 31));
-        assert_eq!(shift_months(base, 12), NaiveDate::from_ymd(2021, 1, 31));
-        assert_eq!(shift_months(base, 13), NaiveDate::from_ymd(2021, 2, 28));
-
-        assert_eq!(shift_months(base, -1), NaiveDate::from_ymd(2019, 12, 31));
-        assert_eq!(shift_months(base, -2), NaiveDate::from_ymd(2019, 11, 30));
-        assert_eq!(shift_months(base, -3), NaiveDate::from_ymd(2019, 10, 31));
-        assert_eq!(shift_months(base, -4), NaiveDate::from_ymd(2019, 9, 30));
-        assert_eq!(shift_months(base, -5), NaiveDate::from_ymd(2019, 8, 31));
-        assert_eq!(shift_months(base, -6), NaiveDate::from_ymd(2019, 7, 31));
-        assert_eq!(shift_months(base, -7), NaiveDate::from_ymd(2019, 6, 30));
-        assert_eq!(shift_months(base, -8), NaiveDate::from_ymd(2019, 5, 31));
-        assert_eq!(shift_months(base, -9), NaiveDate::from_ymd(2019, 4, 30));
-        assert_eq!(shift_months(base, -10), NaiveDate::from_ymd(2019, 3, 31));
-        assert_eq!(shift_months(base, -11), NaiveDate::from_ymd(2019, 2, 28));
-        assert_eq!(shift_months(base, -12), NaiveDate::from_ymd(2019, 1, 31));
-        assert_eq!(shift_months(base, -13), NaiveDate::from_ymd(2018, 12, 31));
-
-        assert_eq!(shift_months(base, 1265), NaiveDate::from_ymd(2125, 6, 30));
+        let base = NaiveDate::from_ymd_opt(2020, 1, 31).unwrap();
+
+        assert_eq!(shift_months(base, 0), NaiveDate::from_ymd_opt(2020, 1, 31).unwrap());
+        assert_eq!(shift_months(base, 1), NaiveDate::from_ymd_opt(2020, 2, 29).unwrap());
+        assert_eq!(shift_months(base, 2), NaiveDate::from_ymd_opt(2020, 3, 31).unwrap());
+        assert_eq!(shift_months(base, 3), NaiveDate::from_ymd_opt(2020, 4, 30).unwrap());
+        assert_eq!(shift_months(base, 4), NaiveDate::from_ymd_opt(2020, 5, 31).unwrap());
+        assert_eq!(shift_months(base, 5), NaiveDate::from_ymd_opt(2020, 6, 30).unwrap());
+        assert_eq!(shift_months(base, 6), NaiveDate::from_ymd_opt(2020, 7, 31).unwrap());
+        assert_eq!(shift_months(base, 7), NaiveDate::from_ymd_opt(2020, 8, 31).unwrap());
+        assert_eq!(shift_months(base, 8), NaiveDate::from_ymd_opt(2020, 9, 30).unwrap());
+        assert_eq!(shift_months(base, 9), NaiveDate::from_ymd_opt(2020, 10, 31).unwrap());
+        assert_eq!(shift_months(base, 10), NaiveDate::from_ymd_opt(2020, 11, 30).unwrap());
+        assert_eq!(shift_months(base, 11), NaiveDate::from_ymd_opt(2020, 12, 31).unwrap());
+        assert_eq!(shift_months(base, 12), NaiveDate::from_ymd_opt(2021, 1, 31).unwrap());
+        assert_eq!(shift_months(base, 13), NaiveDate::from_ymd_opt(2021, 2, 28).unwrap());
+
+        assert_eq!(shift_months(base, -1), NaiveDate::from_ymd_opt(2019, 12, 31).unwrap());
+        assert_eq!(shift_months(base, -2), NaiveDate::from_ymd_opt(2019, 11, 30).unwrap());
+        assert_eq!(shift_months(base, -3), NaiveDate::from_ymd_opt(2019, 10, 31).unwrap());
+        assert_eq!(shift_months(base, -4), NaiveDate::from_ymd_opt(2019, 9, 30).unwrap());
+        assert_eq!(shift_months(base, -5), NaiveDate::from_ymd_opt(2019, 8, 31).unwrap());
+        assert_eq!(shift_months(base, -6), NaiveDate::from_ymd_opt(2019, 7, 31).unwrap());
+        assert_eq!(shift_months(base, -7), NaiveDate::from_ymd_opt(2019, 6, 30).unwrap());
+        assert_eq!(shift_months(base, -8), NaiveDate::from_ymd_opt(2019, 5, 31).unwrap());
+        assert_eq!(shift_months(base, -9), NaiveDate::from_ymd_opt(2019, 4, 30).unwrap());
+        assert_eq!(shift_months(base, -10), NaiveDate::from_ymd_opt(2019, 3, 31).unwrap());
+        assert_eq!(shift_months(base, -11), NaiveDate::from_ymd_opt(2019, 2, 28).unwrap());
+        assert_eq!(shift_months(base, -12), NaiveDate::from_ymd_opt(2019, 1, 31).unwrap());
+        assert_eq!(shift_months(base, -13), NaiveDate::from_ymd_opt(2018, 12, 31).unwrap());
+
+        assert_eq!(shift_months(base, 1265), NaiveDate::from_ymd_opt(2125, 6, 30).unwrap());
     }
 
     #[test]
     fn test_shift_months_with_overflow() {
-        let base = NaiveDate::from_ymd(2020, 12, 31);
+        let base = NaiveDate::from_ymd_opt(2020, 12, 31).unwrap();
 
         assert_eq!(shift_months(base, 0), base);
-        assert_eq!(shift_months(base, 1), NaiveDate::from_ymd(2021, 1, 31));
-        assert_eq!(shift_months(base, 2), NaiveDate::from_ymd(2021, 2, 28));
-        assert_eq!(shift_months(base, 12), NaiveDate::from_ymd(2021, 12, 31));
-        assert_eq!(shift_months(base, 18), NaiveDate::from_ymd(2022, 6, 30));
-
-        assert_eq!(shift_months(base, -1), NaiveDate::from_ymd(2020, 11, 30));
-        assert_eq!(shift_months(base, -2), NaiveDate::from_ymd(2020, 10, 31));
-        assert_eq!(shift_months(base, -10), NaiveDate::from_ymd(2020, 2, 29));
-        assert_eq!(shift_months(base, -12), NaiveDate::from_ymd(2019, 12, 31));
-        assert_eq!(shift_months(base, -18), NaiveDate::from_ymd(2019, 6, 30));
+        assert_eq!(shift_months(base, 1), NaiveDate::from_ymd_opt(2021, 1, 31).unwrap());
+        assert_eq!(shift_months(base, 2), NaiveDate::from_ymd_opt(2021, 2, 28).unwrap());
+        assert_eq!(shift_months(base, 12), NaiveDate::from_ymd_opt(2021, 12, 31).unwrap());
+        assert_eq!(shift_months(base, 18), NaiveDate::from_ymd_opt(2022, 6, 30).unwrap());
+
+        assert_eq!(shift_months(base, -1), NaiveDate::from_ymd_opt(2020, 11, 30).unwrap());
+        assert_eq!(shift_months(base, -2), NaiveDate::from_ymd_opt(2020, 10, 31).unwrap());
+        assert_eq!(shift_months(base, -10), NaiveDate::from_ymd_opt(2020, 2, 29).unwrap());
+        assert_eq!(shift_months(base, -12), NaiveDate::from_ymd_opt(2019, 12, 31).unwrap());
+        assert_eq!(shift_months(base, -18), NaiveDate::from_ymd_opt(2019, 6, 30).unwrap());
     }
 
     #[test]
     fn test_shift_months_datetime() {
-        let date = NaiveDate::from_ymd(2020, 1, 31);
-        let o_clock = NaiveTime::from_hms(1, 2, 3);
+        let date = NaiveDate::from_ymd_opt(2020, 1, 31).unwrap();
+        let o_clock = NaiveTime::from_hms_opt(1, 2, 3).unwrap();
 
         let base = NaiveDateTime::new(date, o_clock);
 
         assert_eq!(
             shift_months(base, 0).date(),
-            NaiveDate::from_ymd(2020, 1, 31)
+            NaiveDate::from_ymd_opt(2020, 1, 31).unwrap()
         );
         assert_eq!(
             shift_months(base, 1).date(),
-            NaiveDate::from_ymd(2020, 2, 29)
+            NaiveDate::from_ymd_opt(2020, 2, 29).unwrap()
         );
         assert_eq!(
             shift_months(base, 2).date(),
-            NaiveDate::from_ymd(2020, 3, 31)
+            NaiveDate::from_ymd_opt(2020, 3, 31).unwrap()
         );
         assert_eq!(shift_months(base, 0).time(), o_clock);
         assert_eq!(shift_months(base, 1).time(), o_clock);
@@ -1,5 +1,6 @@
 //! Rapidash error types
 use arrow::error::ArrowError;
+use datafusion::common::DFSchema;
 use parquet::errors::ParquetError;
 use sqlparser::parser::ParserError;
 use std::result as std_result;
@@ -54,6 +55,9 @@ pub enum RapidashError {
     External(GenericError),
     /// Error with additional context
     Context(String, Box<RapidashError>),
+    /// Error returned when a peer speaks another protocol version or lacks
+    /// a required feature, e.g. an older executor during a rolling upgrade.
+    Incompatible(String),
 }
 
 impl From<String> for RapidashError {
@@ -72,7 +76,6 @@ impl Display for RapidashError {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
         match self {
             RapidashError::General(e) => write!(f, "General error: {}", e),
-            RapidashError::IoError(e) => write!(f, "Io error: {}", e),
             RapidashError::GrpcConnectionError(e) => write!(f, "Grpc connection error: {}", e),
             RapidashError::GrpcActionError(e) => write!(f, "Grpc action error: {}", e),
             RapidashError::FetchFailed(e, i, j, s) => {
@@ -80,7 +83,6 @@ impl Display for RapidashError {
             }
             RapidashError::Cancelled => write!(f, "Task cancelled"),
             RapidashError::ArrowError(ref desc) => write!(f, "Arrow error: {}", desc),
-            #[cfg(feature = "parquet")]
             RapidashError::ParquetError(ref desc) => {
                 write!(f, "Parquet error: {}", desc)
             }
@@ -114,18 +116,15 @@ impl Display for RapidashError {
             RapidashError::Context(ref desc, ref err) => {
                 write!(f, "{}\ncaused by\n{}", desc, *err)
             }
+            RapidashError::Incompatible(ref desc) => {
+                write!(f, "Incompatible peer: {}", desc)
+            }
         }
     }
 }
 
 impl Error for RapidashError {}
 
-impl From<io::Error> for RapidashError {
-    fn from(e: io::Error) -> Self {
-        RapidashError::IoError(e)
-    }
-}
-
 impl From<ArrowError> for RapidashError {
     fn from(e: ArrowError) -> Self {
         RapidashError::ArrowError(e)
@@ -142,7 +141,6 @@ impl From<RapidashError> for ArrowError {
     }
 }
 
-#[cfg(feature = "parquet")]
 impl From<ParquetError> for RapidashError {
     fn from(e: ParquetError) -> Self {
         RapidashError::ParquetError(e)
@@ -4,7 +4,7 @@ use std::cmp::{max, Ordering};
 use std::convert::{Infallible, TryInto};
 use std::ops::{Add, Sub};
 use std::str::FromStr;
-use std::{convert::TryFrom, fmt, iter::repeat, sync::Arc};
+use std::{convert::TryFrom, fmt, sync::Arc};
 
 use arrow::{
     array::*,
@@ -21,7 +21,7 @@ use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime};
 use ordered_float::OrderedFloat;
 
 use crate::delta::shift_months;
-use crate::error::{DataFusionError, Result};
+use crate::error::{RapidashError, Result};
 
 /// Represents a dynamically typed, nullable single value.
 /// This is the single-valued counter-part of arrow's `Array`.
@@ -38,7 +38,7 @@ pub enum ScalarValue {
     /// 64bit float
     Float64(Option<f64>),
     /// 128bit decimal, using the i128 to represent the decimal, precision scale
-    Decimal128(Option<i128>, u8, u8),
+    Decimal128(Option<i128>, u8, i8),
     /// signed 8bit int
     Int8(Option<i8>),
     /// signed 16bit int
@@ -356,7 +356,7 @@ macro_rules! decimal_right {
         -$TERM
     };
     ($TERM:expr, /) => {
-        Err(DataFusionError::NotImplemented(format!(
+        Err(RapidashError::NotImplemented(format!(
             "Decimal reciprocation not yet supported",
         )))
     };
@@ -403,7 +403,7 @@ macro_rules! primitive_right {
         Ok(ScalarValue::$SCALAR(Some($TERM.recip())))
     };
     ($TERM:expr, /, $SCALAR:ident) => {
-        Err(DataFusionError::Internal(format!(
+        Err(RapidashError::Internal(format!(
             "Can not divide an uninitialized value to a non-floating point value",
         )))
     };
@@ -415,7 +415,7 @@ macro_rules! unsigned_subtraction_error {
             "Can not subtract a {} value from an uninitialized value",
             $SCALAR
         );
-        Err(DataFusionError::Internal(msg))
+        Err(RapidashError::Internal(msg))
     }};
 }
 
@@ -481,7 +481,7 @@ macro_rules! impl_op {
                 let value = nanoseconds_add(*ts_ns, $RHS, get_sign!($OPERATION))?;
                 Ok(ScalarValue::TimestampNanosecond(Some(value), zone.clone()))
             }
-            _ => Err(DataFusionError::Internal(format!(
+            _ => Err(RapidashError::Internal(format!(
                 "Operator {} is not implemented for types {:?} and {:?}",
                 stringify!($OPERATION),
                 $LHS,
@@ -502,7 +502,7 @@ macro_rules! get_sign {
 
 #[inline]
 pub fn date32_add(days: i32, scalar: &ScalarValue, sign: i32) -> Result<i32> {
-    let epoch = NaiveDate::from_ymd(1970, 1, 1);
+    let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
     let prior = epoch.add(Duration::days(days as i64));
     let posterior = do_date_math(prior, scalar, sign)?;
     Ok(posterior.sub(epoch).num_days() as i32)
@@ -510,7 +510,7 @@ pub fn date32_add(days: i32, scalar: &ScalarValue, sign: i32) -> Result<i32> {
 
 #[inline]
 pub fn date64_add(ms: i64, scalar: &ScalarValue, sign: i32) -> Result<i64> {
-    let epoch = NaiveDate::from_ymd(1970, 1, 1);
+    let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
     let prior = epoch.add(Duration::milliseconds(ms));
     let posterior = do_date_math(prior, scalar, sign)?;
     Ok(posterior.sub(epoch).num_milliseconds())
@@ -549,7 +549,7 @@ fn do_date_time_math(
     scalar: &ScalarValue,
     sign: i32,
 ) -> Result<NaiveDateTime> {
-    let prior = NaiveDateTime::from_timestamp(secs, nsecs);
+    let prior = NaiveDateTime::from_timestamp_opt(secs, nsecs).unwrap();
     do_date_math(prior, scalar, sign)
 }
 
@@ -561,7 +561,7 @@ where
         ScalarValue::IntervalDayTime(Some(i)) => add_day_time(prior, *i, sign),
         ScalarValue::IntervalYearMonth(Some(i)) => shift_months(prior, *i * sign),
         ScalarValue::IntervalMonthDayNano(Some(i)) => add_m_d_nano(prior, *i, sign),
-        other => Err(DataFusionError::Execution(format!(
+        other => Err(RapidashError::Execution(format!(
             "DateIntervalExpr does not support non-interval type {:?}",
             other
         )))?,
@@ -673,8 +673,7 @@ fn dict_from_scalar<K: ArrowDictionaryKeyType>(value: &ScalarValue, size: usize)
     let values_array = value.to_array_of_size(1);
 
     // Create a key array with `size` elements, each of 0
-    let key_array: PrimitiveArray<K> = std::iter::repeat(Some(K::default_value()))
-        .take(size)
+    let key_array: PrimitiveArray<K> = std::iter::repeat_n(Some(K::default_value()), size)
         .collect();
 
     // create a new DictionaryArray
@@ -697,7 +696,7 @@ fn dict_from_values<K: ArrowDictionaryKeyType>(values_array: &dyn Array) -> Resu
         .map(|index| {
             if values_array.is_valid(index) {
                 let native_index = K::Native::from_usize(index).ok_or_else(|| {
-                    DataFusionError::Internal(format!(
+                    RapidashError::Internal(format!(
                         "Can not create index of type {} from value {}",
                         K::DATA_TYPE,
                         index
@@ -900,12 +899,12 @@ macro_rules! eq_array_primitive {
 
 impl ScalarValue {
     /// Create a decimal Scalar from value/precision and scale.
-    pub fn try_new_decimal128(value: i128, precision: u8, scale: u8) -> Result<Self> {
+    pub fn try_new_decimal128(value: i128, precision: u8, scale: i8) -> Result<Self> {
         // make sure the precision and scale is valid
-        if precision <= DECIMAL128_MAX_PRECISION && scale <= precision {
+        if precision <= DECIMAL128_MAX_PRECISION && i16::from(scale) <= i16::from(precision) {
             return Ok(ScalarValue::Decimal128(Some(value), precision, scale));
         }
-        Err(DataFusionError::Internal(format!(
+        Err(RapidashError::Internal(format!(
             "Can not new a decimal type ScalarValue for precision {} and scale {}",
             precision, scale
         )))
@@ -1012,7 +1011,7 @@ impl ScalarValue {
             ScalarValue::Decimal128(Some(v), precision, scale) => {
                 Ok(ScalarValue::Decimal128(Some(-v), *precision, *scale))
             }
-            value => Err(DataFusionError::Internal(format!(
+            value => Err(RapidashError::Internal(format!(
                 "Can not run arithmetic negative on scalar value {:?}",
                 value
             ))),
@@ -1126,7 +1125,7 @@ impl ScalarValue {
     ///
     /// Example
     /// ```
-    /// use datafusion_common::ScalarValue;
+    /// use common::scalar::ScalarValue;
     /// use arrow::array::{ArrayRef, BooleanArray};
     ///
     /// let scalars = vec![
@@ -1155,7 +1154,7 @@ impl ScalarValue {
         // figure out the type based on the first element
         let data_type = match scalars.peek() {
             None => {
-                return Err(DataFusionError::Internal(
+                return Err(RapidashError::Internal(
                     "Empty iterator passed to ScalarValue::iter_to_array".to_string(),
                 ));
             }
@@ -1172,7 +1171,7 @@ impl ScalarValue {
                             if let ScalarValue::$SCALAR_TY(v) = sv {
                                 Ok(v)
                             } else {
-                                Err(DataFusionError::Internal(format!(
+                                Err(RapidashError::Internal(format!(
                                     "Inconsistent types in ScalarValue::iter_to_array. \
                                     Expected {:?}, got {:?}",
                                     data_type, sv
@@ -1193,7 +1192,7 @@ impl ScalarValue {
                             if let ScalarValue::$SCALAR_TY(v, _) = sv {
                                 Ok(v)
                             } else {
-                                Err(DataFusionError::Internal(format!(
+                                Err(RapidashError::Internal(format!(
                                     "Inconsistent types in ScalarValue::iter_to_array. \
                                     Expected {:?}, got {:?}",
                                     data_type, sv
@@ -1216,7 +1215,7 @@ impl ScalarValue {
                             if let ScalarValue::$SCALAR_TY(v) = sv {
                                 Ok(v)
                             } else {
-                                Err(DataFusionError::Internal(format!(
+                                Err(RapidashError::Internal(format!(
                                     "Inconsistent types in ScalarValue::iter_to_array. \
                                     Expected {:?}, got {:?}",
                                     data_type, sv
@@ -1269,7 +1268,7 @@ impl ScalarValue {
                                         builder.values().append_null();
                                     }
                                     sv => {
-                                        return Err(DataFusionError::Internal(format!(
+                                        return Err(RapidashError::Internal(format!(
                                             "Inconsistent types in ScalarValue::iter_to_array. \
                                                 Expected Utf8, got {:?}",
                                             sv
@@ -1283,7 +1282,7 @@ impl ScalarValue {
                             builder.append(false);
                         }
                         sv => {
-                            return Err(DataFusionError::Internal(format!(
+                            return Err(RapidashError::Internal(format!(
                                 "Inconsistent types in ScalarValue::iter_to_array. \
                                     Expected List, got {:?}",
                                 sv
@@ -1302,7 +1301,7 @@ impl ScalarValue {
                 Arc::new(decimal_array)
             }
             DataType::Decimal256(_, _) => {
-                return Err(DataFusionError::Internal(
+                return Err(RapidashError::Internal(
                     "Decimal256 is not supported for ScalarValue".to_string(),
                 ));
             }
@@ -1409,7 +1408,7 @@ impl ScalarValue {
                             }
                         };
                     } else {
-                        return Err(DataFusionError::Internal(format!(
+                        return Err(RapidashError::Internal(format!(
                             "Expected Struct but found: {}",
                             scalar
                         )));
@@ -1430,7 +1429,6 @@ impl ScalarValue {
             DataType::Dictionary(key_type, value_type) => {
                 // create the values array
                 let value_scalars = scalars
-                    .into_iter()
                     .map(|scalar| match scalar {
                         ScalarValue::Dictionary(inner_key_type, scalar) => {
                             if &inner_key_type == key_type {
@@ -1442,7 +1440,7 @@ impl ScalarValue {
                                 );
                             }
                         }
-                        _ => Err(DataFusionError::Internal(format!(
+                        _ => Err(RapidashError::Internal(format!(
                             "Expected scalar of type {} but found: {} {:?}",
                             value_type, scalar, scalar
                         ))),
@@ -1464,13 +1462,13 @@ impl ScalarValue {
                     _ => unreachable!("Invalid dictionary keys type: {:?}", key_type),
                 }
             }
-            DataType::FixedSizeBinary(_) => {
+            DataType::FixedSizeBinary(size) => {
                 let array = scalars
                     .map(|sv| {
                         if let ScalarValue::FixedSizeBinary(_, v) = sv {
                             Ok(v)
                         } else {
-                            Err(DataFusionError::Internal(format!(
+                            Err(RapidashError::Internal(format!(
                                 "Inconsistent types in ScalarValue::iter_to_array. \
                                 Expected {:?}, got {:?}",
                                 data_type, sv
@@ -1478,7 +1476,7 @@ impl ScalarValue {
                         }
                     })
                     .collect::<Result<Vec<_>>>()?;
-                let array = FixedSizeBinaryArray::try_from_sparse_iter(array.into_iter())?;
+                let array = FixedSizeBinaryArray::try_from_sparse_iter_with_size(array.into_iter(), *size)?;
                 Arc::new(array)
             }
             // explicitly enumerate unsupported types so newly added
@@ -1492,7 +1490,7 @@ impl ScalarValue {
             | DataType::LargeList(_)
             | DataType::Union(_, _, _)
             | DataType::Map(_, _) => {
-                return Err(DataFusionError::Internal(format!(
+                return Err(RapidashError::Internal(format!(
                     "Unsupported creation of {:?} array from ScalarValue {:?}",
                     data_type,
                     scalars.peek()
@@ -1516,7 +1514,7 @@ impl ScalarValue {
     fn iter_to_decimal_array(
         scalars: impl IntoIterator<Item = ScalarValue>,
         precision: u8,
-        scale: u8,
+        scale: i8,
     ) -> Result<Decimal128Array> {
         let array = scalars
             .into_iter()
@@ -1563,7 +1561,7 @@ impl ScalarValue {
                     }
                 }
             } else {
-                return Err(DataFusionError::Internal(format!(
+                return Err(RapidashError::Internal(format!(
                     "Expected ScalarValue::List element. Received {:?}",
                     scalar
                 )));
@@ -1574,7 +1572,7 @@ impl ScalarValue {
         let element_arrays: Vec<&dyn Array> = elements.iter().map(|a| a.as_ref()).collect();
         let flat_array = match arrow::compute::concat(&element_arrays) {
             Ok(flat_array) => flat_array,
-            Err(err) => return Err(DataFusionError::ArrowError(err)),
+            Err(err) => return Err(RapidashError::ArrowError(err)),
         };
 
         // Build ListArray using ArrayData so we can specify a flat inner array, and offset indices
@@ -1592,12 +1590,10 @@ impl ScalarValue {
     fn build_decimal_array(
         value: Option<i128>,
         precision: u8,
-        scale: u8,
+        scale: i8,
         size: usize,
     ) -> Decimal128Array {
-        std::iter::repeat(value)
-            .take(size)
-            .into_iter()
+        std::iter::repeat_n(value, size)
             .collect::<Decimal128Array>()
             .with_precision_and_scale(precision, scale)
             .unwrap()
@@ -1664,44 +1660,45 @@ impl ScalarValue {
                 size
             ),
             ScalarValue::Utf8(e) => match e {
-                Some(value) => Arc::new(StringArray::from_iter_values(repeat(value).take(size))),
+                Some(value) => Arc::new(StringArray::from_iter_values(std::iter::repeat_n(value, size))),
                 None => new_null_array(&DataType::Utf8, size),
             },
             ScalarValue::LargeUtf8(e) => match e {
                 Some(value) => {
-                    Arc::new(LargeStringArray::from_iter_values(repeat(value).take(size)))
+                    Arc::new(LargeStringArray::from_iter_values(std::iter::repeat_n(value, size)))
                 }
                 None => new_null_array(&DataType::LargeUtf8, size),
             },
             ScalarValue::Binary(e) => match e {
                 Some(value) => Arc::new(
-                    repeat(Some(value.as_slice()))
-                        .take(size)
+                    std::iter::repeat_n(Some(value.as_slice()), size)
                         .collect::<BinaryArray>(),
                 ),
-                None => Arc::new(repeat(None::<&str>).take(size).collect::<BinaryArray>()),
+                None => Arc::new(std::iter::repeat_n(None::<&str>, size).collect::<BinaryArray>()),
             },
-            ScalarValue::FixedSizeBinary(_, e) => match e {
+            ScalarValue::FixedSizeBinary(width, e) => match e {
                 Some(value) => Arc::new(
-                    FixedSizeBinaryArray::try_from_sparse_iter(
-                        repeat(Some(value.as_slice())).take(size),
+                    FixedSizeBinaryArray::try_from_sparse_iter_with_size(
+                        std::iter::repeat_n(Some(value.as_slice()), size),
+                        *width,
                     )
                     .unwrap(),
                 ),
                 None => Arc::new(
-                    FixedSizeBinaryArray::try_from_sparse_iter(repeat(None::<&[u8]>).take(size))
-                        .unwrap(),
+                    FixedSizeBinaryArray::try_from_sparse_iter_with_size(
+                        std::iter::repeat_n(None::<&[u8]>, size),
+                        *width,
+                    )
+                    .unwrap(),
                 ),
             },
             ScalarValue::LargeBinary(e) => match e {
                 Some(value) => Arc::new(
-                    repeat(Some(value.as_slice()))
-                        .take(size)
+                    std::iter::repeat_n(Some(value.as_slice()), size)
                         .collect::<LargeBinaryArray>(),
                 ),
                 None => Arc::new(
-                    repeat(None::<&str>)
-                        .take(size)
+                    std::iter::repeat_n(None::<&str>, size)
                         .collect::<LargeBinaryArray>(),
                 ),
             },
@@ -1725,7 +1722,7 @@ impl ScalarValue {
                     build_list!(LargeStringBuilder, LargeUtf8, values, size)
                 }
                 _ => ScalarValue::iter_to_array_list(
-                    repeat(self.clone()).take(size),
+                    std::iter::repeat_n(self.clone(), size),
                     &DataType::List(Box::new(Field::new(
                         "item",
                         field.data_type().clone(),
@@ -1816,7 +1813,7 @@ impl ScalarValue {
         array: &ArrayRef,
         index: usize,
         precision: u8,
-        scale: u8,
+        scale: i8,
     ) -> ScalarValue {
         let array = array.as_any().downcast_ref::<Decimal128Array>().unwrap();
         if array.is_null(index) {
@@ -1857,7 +1854,7 @@ impl ScalarValue {
             DataType::LargeUtf8 => typed_cast!(array, index, LargeStringArray, LargeUtf8),
             DataType::List(nested_type) => {
                 let list_array = array.as_any().downcast_ref::<ListArray>().ok_or_else(|| {
-                    DataFusionError::Internal("Failed to downcast ListArray".to_string())
+                    RapidashError::Internal("Failed to downcast ListArray".to_string())
                 })?;
                 let value = match list_array.is_null(index) {
                     true => None,
@@ -1936,7 +1933,7 @@ impl ScalarValue {
                     .as_any()
                     .downcast_ref::<StructArray>()
                     .ok_or_else(|| {
-                        DataFusionError::Internal(
+                        RapidashError::Internal(
                             "Failed to downcast ArrayRef to StructArray".to_string(),
                         )
                     })?;
@@ -1980,7 +1977,7 @@ impl ScalarValue {
                 )
             }
             other => {
-                return Err(DataFusionError::NotImplemented(format!(
+                return Err(RapidashError::NotImplemented(format!(
                     "Can't create a scalar from array of type \"{:?}\"",
                     other
                 )));
@@ -2001,7 +1998,7 @@ impl ScalarValue {
         index: usize,
         value: &Option<i128>,
         precision: u8,
-        scale: u8,
+        scale: i8,
     ) -> bool {
         let array = array.as_any().downcast_ref::<Decimal128Array>().unwrap();
         if array.precision() != precision || array.scale() != scale {
@@ -2189,12 +2186,12 @@ impl From<Vec<(&str, ScalarValue)>> for ScalarValue {
 macro_rules! impl_try_from {
     ($SCALAR:ident, $NATIVE:ident) => {
         impl TryFrom<ScalarValue> for $NATIVE {
-            type Error = DataFusionError;
+            type Error = RapidashError;
 
             fn try_from(value: ScalarValue) -> Result<Self> {
                 match value {
                     ScalarValue::$SCALAR(Some(inner_value)) => Ok(inner_value),
-                    _ => Err(DataFusionError::Internal(format!(
+                    _ => Err(RapidashError::Internal(format!(
                         "Cannot convert {:?} to {}",
                         value,
                         std::any::type_name::<Self>()
@@ -2210,14 +2207,14 @@ impl_try_from!(Int16, i16);
 
 // special implementation for i32 because of Date32
 impl TryFrom<ScalarValue> for i32 {
-    type Error = DataFusionError;
+    type Error = RapidashError;
 
     fn try_from(value: ScalarValue) -> Result<Self> {
         match value {
             ScalarValue::Int32(Some(inner_value)) | ScalarValue::Date32(Some(inner_value)) => {
                 Ok(inner_value)
             }
-            _ => Err(DataFusionError::Internal(format!(
+            _ => Err(RapidashError::Internal(format!(
                 "Cannot convert {:?} to {}",
                 value,
                 std::any::type_name::<Self>()
@@ -2228,7 +2225,7 @@ impl TryFrom<ScalarValue> for i32 {
 
 // special implementation for i64 because of TimeNanosecond
 impl TryFrom<ScalarValue> for i64 {
-    type Error = DataFusionError;
+    type Error = RapidashError;
 
     fn try_from(value: ScalarValue) -> Result<Self> {
         match value {
@@ -2239,7 +2236,7 @@ impl TryFrom<ScalarValue> for i64 {
             | ScalarValue::TimestampMicrosecond(Some(inner_value), _)
             | ScalarValue::TimestampMillisecond(Some(inner_value), _)
             | ScalarValue::TimestampSecond(Some(inner_value), _) => Ok(inner_value),
-            _ => Err(DataFusionError::Internal(format!(
+            _ => Err(RapidashError::Internal(format!(
                 "Cannot convert {:?} to {}",
                 value,
                 std::any::type_name::<Self>()
@@ -2250,12 +2247,12 @@ impl TryFrom<ScalarValue> for i64 {
 
 // special implementation for i128 because of Decimal128
 impl TryFrom<ScalarValue> for i128 {
-    type Error = DataFusionError;
+    type Error = RapidashError;
 
     fn try_from(value: ScalarValue) -> Result<Self> {
         match value {
             ScalarValue::Decimal128(Some(inner_value), _, _) => Ok(inner_value),
-            _ => Err(DataFusionError::Internal(format!(
+            _ => Err(RapidashError::Internal(format!(
                 "Cannot convert {:?} to {}",
                 value,
                 std::any::type_name::<Self>()
@@ -2273,7 +2270,7 @@ impl_try_from!(Float64, f64);
 impl_try_from!(Boolean, bool);
 
 impl TryFrom<DataType> for ScalarValue {
-    type Error = DataFusionError;
+    type Error = RapidashError;
 
     /// Create a Null instance of ScalarValue for this datatype
     fn try_from(datatype: DataType) -> Result<Self> {
@@ -2282,7 +2279,7 @@ impl TryFrom<DataType> for ScalarValue {
 }
 
 impl TryFrom<&DataType> for ScalarValue {
-    type Error = DataFusionError;
+    type Error = RapidashError;
 
     /// Create a Null instance of ScalarValue for this datatype
     fn try_from(datatype: &DataType) -> Result<Self> {
@@ -2336,7 +2333,7 @@ impl TryFrom<&DataType> for ScalarValue {
             DataType::Struct(fields) => ScalarValue::Struct(None, Box::new(fields.clone())),
             DataType::Null => ScalarValue::Null,
             _ => {
-                return Err(DataFusionError::NotImplemented(format!(
+                return Err(RapidashError::NotImplemented(format!(
                     "Can't create a scalar from data_type \"{:?}\"",
                     datatype
                 )));
@@ -2565,7 +2562,6 @@ mod tests {
     use arrow::compute::kernels;
     use arrow::datatypes::ArrowPrimitiveType;
 
-    use crate::from_slice::FromSlice;
 
     use super::*;
 
@@ -2663,7 +2659,7 @@ mod tests {
             ScalarValue::Decimal128(Some(3), 10, 2),
         ];
         // convert the vec to decimal array and check the result
-        let array = ScalarValue::iter_to_array(decimal_vec.into_iter()).unwrap();
+        let array = ScalarValue::iter_to_array(decimal_vec).unwrap();
         assert_eq!(3, array.len());
         assert_eq!(DataType::Decimal128(10, 2), array.data_type().clone());
 
@@ -2673,7 +2669,7 @@ mod tests {
             ScalarValue::Decimal128(Some(3), 10, 2),
             ScalarValue::Decimal128(None, 10, 2),
         ];
-        let array = ScalarValue::iter_to_array(decimal_vec.into_iter()).unwrap();
+        let array = ScalarValue::iter_to_array(decimal_vec).unwrap();
         assert_eq!(4, array.len());
         assert_eq!(DataType::Decimal128(10, 2), array.data_type().clone());
 
@@ -2878,11 +2874,11 @@ mod tests {
             LargeStringArray,
             vec![Some("foo"), None, Some("bar")]
         );
-        check_scalar_iter_binary!(Binary, BinaryArray, vec![Some(b"foo"), None, Some(b"bar")]);
+        check_scalar_iter_binary!(Binary, BinaryArray, [Some(b"foo"), None, Some(b"bar")]);
         check_scalar_iter_binary!(
             LargeBinary,
             LargeBinaryArray,
-            vec![Some(b"foo"), None, Some(b"bar")]
+            [Some(b"foo"), None, Some(b"bar")]
         );
     }
 
@@ -2890,7 +2886,7 @@ mod tests {
     fn scalar_iter_to_array_empty() {
         let scalars = vec![] as Vec<ScalarValue>;
 
-        let result = ScalarValue::iter_to_array(scalars.into_iter()).unwrap_err();
+        let result = ScalarValue::iter_to_array(scalars).unwrap_err();
         assert!(
             result
                 .to_string()
@@ -2914,7 +2910,7 @@ mod tests {
             make_val(Some("Bar".into())),
         ];
 
-        let array = ScalarValue::iter_to_array(scalars.into_iter()).unwrap();
+        let array = ScalarValue::iter_to_array(scalars).unwrap();
         let array = as_dictionary_array::<Int32Type>(&array);
         let values_array = as_string_array(array.values());
 
@@ -2938,7 +2934,7 @@ mod tests {
         // If the scalar values are not all the correct type, error here
         let scalars: Vec<ScalarValue> = vec![Boolean(Some(true)), Int32(Some(5))];
 
-        let result = ScalarValue::iter_to_array(scalars.into_iter()).unwrap_err();
+        let result = ScalarValue::iter_to_array(scalars).unwrap_err();
         assert!(
             result.to_string().contains(
                 "Inconsistent types in ScalarValue::iter_to_array. Expected Boolean, got Int32(5)"
@@ -2996,21 +2992,21 @@ mod tests {
             }};
         }
 
-        let bool_vals = vec![Some(true), None, Some(false)];
-        let f32_vals = vec![Some(-1.0), None, Some(1.0)];
+        let bool_vals = [Some(true), None, Some(false)];
+        let f32_vals = [Some(-1.0), None, Some(1.0)];
         let f64_vals = make_typed_vec!(f32_vals, f64);
 
-        let i8_vals = vec![Some(-1), None, Some(1)];
+        let i8_vals = [Some(-1), None, Some(1)];
         let i16_vals = make_typed_vec!(i8_vals, i16);
         let i32_vals = make_typed_vec!(i8_vals, i32);
         let i64_vals = make_typed_vec!(i8_vals, i64);
 
-        let u8_vals = vec![Some(0), None, Some(1)];
+        let u8_vals = [Some(0), None, Some(1)];
         let u16_vals = make_typed_vec!(u8_vals, u16);
         let u32_vals = make_typed_vec!(u8_vals, u32);
         let u64_vals = make_typed_vec!(u8_vals, u64);
 
-        let str_vals = vec![Some("foo"), None, Some("bar")];
+        let str_vals = [Some("foo"), None, Some("bar")];
 
         /// Test each value in `scalar` with the corresponding element
         /// at `array`. Assumes each element is unique (aka not equal
@@ -3342,26 +3338,26 @@ mod tests {
         let expected = Arc::new(StructArray::from(vec![
             (
                 field_a.clone(),
-                Arc::new(Int32Array::from_slice(&[23, 23])) as ArrayRef,
+                Arc::new(Int32Array::from(vec![23, 23])) as ArrayRef,
             ),
             (
                 field_b.clone(),
-                Arc::new(BooleanArray::from_slice(&[false, false])) as ArrayRef,
+                Arc::new(BooleanArray::from(vec![false, false])) as ArrayRef,
             ),
             (
                 field_c.clone(),
-                Arc::new(StringArray::from_slice(&["Hello", "Hello"])) as ArrayRef,
+                Arc::new(StringArray::from(vec!["Hello", "Hello"])) as ArrayRef,
             ),
             (
                 field_d.clone(),
                 Arc::new(StructArray::from(vec![
                     (
                         field_e.clone(),
-                        Arc::new(Int16Array::from_slice(&[2, 2])) as ArrayRef,
+                        Arc::new(Int16Array::from(vec![2, 2])) as ArrayRef,
                     ),
                     (
                         field_f.clone(),
-                        Arc::new(Int64Array::from_slice(&[3, 3])) as ArrayRef,
+                        Arc::new(Int64Array::from(vec![3, 3])) as ArrayRef,
                     ),
                 ])) as ArrayRef,
             ),
@@ -3437,26 +3433,26 @@ mod tests {
         let expected = Arc::new(StructArray::from(vec![
             (
                 field_a,
-                Arc::new(Int32Array::from_slice(&[23, 7, -1000])) as ArrayRef,
+                Arc::new(Int32Array::from(vec![23, 7, -1000])) as ArrayRef,
             ),
             (
                 field_b,
-                Arc::new(BooleanArray::from_slice(&[false, true, true])) as ArrayRef,
+                Arc::new(BooleanArray::from(vec![false, true, true])) as ArrayRef,
             ),
             (
                 field_c,
-                Arc::new(StringArray::from_slice(&["Hello", "World", "!!!!!"])) as ArrayRef,
+                Arc::new(StringArray::from(vec!["Hello", "World", "!!!!!"])) as ArrayRef,
             ),
             (
                 field_d,
                 Arc::new(StructArray::from(vec![
                     (
                         field_e,
-                        Arc::new(Int16Array::from_slice(&[2, 4, 6])) as ArrayRef,
+                        Arc::new(Int16Array::from(vec![2, 4, 6])) as ArrayRef,
                     ),
                     (
                         field_f,
-                        Arc::new(Int64Array::from_slice(&[3, 5, 7])) as ArrayRef,
+                        Arc::new(Int64Array::from(vec![3, 5, 7])) as ArrayRef,
                     ),
                 ])) as ArrayRef,
             ),
@@ -3517,7 +3513,7 @@ mod tests {
         let expected = StructArray::from(vec![
             (
                 field_a.clone(),
-                Arc::new(StringArray::from_slice(&["First", "Second", "Third"])) as ArrayRef,
+                Arc::new(StringArray::from(vec!["First", "Second", "Third"])) as ArrayRef,
             ),
             (
                 field_primitive_list.clone(),
@@ -5,7 +5,7 @@ use std::cmp::{max, Ordering};
 use std::convert::{Infallible, TryInto};
 use std::ops::{Add, Sub};
 use std::str::FromStr;
-use std::{convert::TryFrom, fmt, iter::repeat, sync::Arc};
+use std::{convert::TryFrom, fmt, sync::Arc};
 
 use arrow::{
     array::*,
@@ -22,7 +22,7 @@ use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime};
 use ordered_float::OrderedFloat;
 
 use crate::delta::shift_months;
-use error::{RapidashError, Result};
+use crate::error::{RapidashError, Result};
 
 /// Represents a dynamically typed, nullable single value.
 /// This is the single-valued counter-part of arrow's `Array`.
@@ -39,7 +39,7 @@ pub enum ScalarValue {
     /// 64bit float
     Float64(Option<f64>),
     /// 128bit decimal, using the i128 to represent the decimal, precision scale
-    Decimal128(Option<i128>, u8, u8),
+    Decimal128(Option<i128>, u8, i8),
     /// signed 8bit int
     Int8(Option<i8>),
     /// signed 16bit int
@@ -674,8 +674,7 @@ fn dict_from_scalar<K: ArrowDictionaryKeyType>(value: &ScalarValue, size: usize)
     let values_array = value.to_array_of_size(1);
 
     // Create a key array with `size` elements, each of 0
-    let key_array: PrimitiveArray<K> = std::iter::repeat(Some(K::default_value()))
-        .take(size)
+    let key_array: PrimitiveArray<K> = std::iter::repeat_n(Some(K::default_value()), size)
         .collect();
 
     // create a new DictionaryArray
@@ -901,9 +900,9 @@ macro_rules! eq_array_primitive {
 
 impl ScalarValue {
     /// Create a decimal Scalar from value/precision and scale.
-    pub fn try_new_decimal128(value: i128, precision: u8, scale: u8) -> Result<Self> {
+    pub fn try_new_decimal128(value: i128, precision: u8, scale: i8) -> Result<Self> {
         // make sure the precision and scale is valid
-        if precision <= DECIMAL128_MAX_PRECISION && scale <= precision {
+        if precision <= DECIMAL128_MAX_PRECISION && i16::from(scale) <= i16::from(precision) {
             return Ok(ScalarValue::Decimal128(Some(value), precision, scale));
         }
         Err(RapidashError::Internal(format!(
@@ -1127,7 +1126,7 @@ impl ScalarValue {
     ///
     /// Example
     /// ```
-    /// use datafusion_common::ScalarValue;
+    /// use common::scalar::ScalarValue;
     /// use arrow::array::{ArrayRef, BooleanArray};
     ///
     /// let scalars = vec![
@@ -1431,7 +1430,6 @@ impl ScalarValue {
             DataType::Dictionary(key_type, value_type) => {
                 // create the values array
                 let value_scalars = scalars
-                    .into_iter()
                     .map(|scalar| match scalar {
                         ScalarValue::Dictionary(inner_key_type, scalar) => {
                             if &inner_key_type == key_type {
@@ -1465,7 +1463,7 @@ impl ScalarValue {
                     _ => unreachable!("Invalid dictionary keys type: {:?}", key_type),
                 }
             }
-            DataType::FixedSizeBinary(_) => {
+            DataType::FixedSizeBinary(size) => {
                 let array = scalars
                     .map(|sv| {
                         if let ScalarValue::FixedSizeBinary(_, v) = sv {
@@ -1479,7 +1477,7 @@ impl ScalarValue {
                         }
                     })
                     .collect::<Result<Vec<_>>>()?;
-                let array = FixedSizeBinaryArray::try_from_sparse_iter(array.into_iter())?;
+                let array = FixedSizeBinaryArray::try_from_sparse_iter_with_size(array.into_iter(), *size)?;
                 Arc::new(array)
             }
             // explicitly enumerate unsupported types so newly added
@@ -1517,7 +1515,7 @@ impl ScalarValue {
     fn iter_to_decimal_array(
         scalars: impl IntoIterator<Item = ScalarValue>,
         precision: u8,
-        scale: u8,
+        scale: i8,
     ) -> Result<Decimal128Array> {
         let array = scalars
             .into_iter()
@@ -1593,12 +1591,10 @@ impl ScalarValue {
     fn build_decimal_array(
         value: Option<i128>,
         precision: u8,
-        scale: u8,
+        scale: i8,
         size: usize,
     ) -> Decimal128Array {
-        std::iter::repeat(value)
-            .take(size)
-            .into_iter()
+        std::iter::repeat_n(value, size)
             .collect::<Decimal128Array>()
             .with_precision_and_scale(precision, scale)
             .unwrap()
@@ -1665,44 +1661,45 @@ impl ScalarValue {
                 size
             ),
             ScalarValue::Utf8(e) => match e {
-                Some(value) => Arc::new(StringArray::from_iter_values(repeat(value).take(size))),
+                Some(value) => Arc::new(StringArray::from_iter_values(std::iter::repeat_n(value, size))),
                 None => new_null_array(&DataType::Utf8, size),
             },
             ScalarValue::LargeUtf8(e) => match e {
                 Some(value) => {
-                    Arc::new(LargeStringArray::from_iter_values(repeat(value).take(size)))
+                    Arc::new(LargeStringArray::from_iter_values(std::iter::repeat_n(value, size)))
                 }
                 None => new_null_array(&DataType::LargeUtf8, size),
             },
             ScalarValue::Binary(e) => match e {
                 Some(value) => Arc::new(
-                    repeat(Some(value.as_slice()))
-                        .take(size)
+                    std::iter::repeat_n(Some(value.as_slice()), size)
                         .collect::<BinaryArray>(),
                 ),
-                None => Arc::new(repeat(None::<&str>).take(size).collect::<BinaryArray>()),
+                None => Arc::new(std::iter::repeat_n(None::<&str>, size).collect::<BinaryArray>()),
             },
-            ScalarValue::FixedSizeBinary(_, e) => match e {
+            ScalarValue::FixedSizeBinary(width, e) => match e {
                 Some(value) => Arc::new(
-                    FixedSizeBinaryArray::try_from_sparse_iter(
-                        repeat(Some(value.as_slice())).take(size),
+                    FixedSizeBinaryArray::try_from_sparse_iter_with_size(
+                        std::iter::repeat_n(Some(value.as_slice()), size),
+                        *width,
                     )
                     .unwrap(),
                 ),
                 None => Arc::new(
-                    FixedSizeBinaryArray::try_from_sparse_iter(repeat(None::<&[u8]>).take(size))
-                        .unwrap(),
+                    FixedSizeBinaryArray::try_from_sparse_iter_with_size(
+                        std::iter::repeat_n(None::<&[u8]>, size),
+                        *width,
+                    )
+                    .unwrap(),
                 ),
             },
             ScalarValue::LargeBinary(e) => match e {
                 Some(value) => Arc::new(
-                    repeat(Some(value.as_slice()))
-                        .take(size)
+                    std::iter::repeat_n(Some(value.as_slice()), size)
                         .collect::<LargeBinaryArray>(),
                 ),
                 None => Arc::new(
-                    repeat(None::<&str>)
-                        .take(size)
+                    std::iter::repeat_n(None::<&str>, size)
                         .collect::<LargeBinaryArray>(),
                 ),
             },
@@ -1726,7 +1723,7 @@ impl ScalarValue {
                     build_list!(LargeStringBuilder, LargeUtf8, values, size)
                 }
                 _ => ScalarValue::iter_to_array_list(
-                    repeat(self.clone()).take(size),
+                    std::iter::repeat_n(self.clone(), size),
                     &DataType::List(Box::new(Field::new(
                         "item",
                         field.data_type().clone(),
@@ -1817,7 +1814,7 @@ impl ScalarValue {
         array: &ArrayRef,
         index: usize,
         precision: u8,
-        scale: u8,
+        scale: i8,
     ) -> ScalarValue {
         let array = array.as_any().downcast_ref::<Decimal128Array>().unwrap();
         if array.is_null(index) {
@@ -2002,7 +1999,7 @@ impl ScalarValue {
         index: usize,
         value: &Option<i128>,
         precision: u8,
-        scale: u8,
+        scale: i8,
     ) -> bool {
         let array = array.as_any().downcast_ref::<Decimal128Array>().unwrap();
         if array.precision() != precision || array.scale() != scale {
@@ -2566,7 +2563,6 @@ mod tests {
     use arrow::compute::kernels;
     use arrow::datatypes::ArrowPrimitiveType;
 
-    use crate::from_slice::FromSlice;
 
     use super::*;
 
@@ -2664,7 +2660,7 @@ mod tests {
             ScalarValue::Decimal128(Some(3), 10, 2),
         ];
         // convert the vec to decimal array and check the result
-        let array = ScalarValue::iter_to_array(decimal_vec.into_iter()).unwrap();
+        let array = ScalarValue::iter_to_array(decimal_vec).unwrap();
         assert_eq!(3, array.len());
         assert_eq!(DataType::Decimal128(10, 2), array.data_type().clone());
 
@@ -2674,7 +2670,7 @@ mod tests {
             ScalarValue::Decimal128(Some(3), 10, 2),
             ScalarValue::Decimal128(None, 10, 2),
         ];
-        let array = ScalarValue::iter_to_array(decimal_vec.into_iter()).unwrap();
+        let array = ScalarValue::iter_to_array(decimal_vec).unwrap();
         assert_eq!(4, array.len());
         assert_eq!(DataType::Decimal128(10, 2), array.data_type().clone());
 
@@ -2879,11 +2875,11 @@ mod tests {
             LargeStringArray,
             vec![Some("foo"), None, Some("bar")]
         );
-        check_scalar_iter_binary!(Binary, BinaryArray, vec![Some(b"foo"), None, Some(b"bar")]);
+        check_scalar_iter_binary!(Binary, BinaryArray, [Some(b"foo"), None, Some(b"bar")]);
         check_scalar_iter_binary!(
             LargeBinary,
             LargeBinaryArray,
-            vec![Some(b"foo"), None, Some(b"bar")]
+            [Some(b"foo"), None, Some(b"bar")]
         );
     }
 
@@ -2891,7 +2887,7 @@ mod tests {
     fn scalar_iter_to_array_empty() {
         let scalars = vec![] as Vec<ScalarValue>;
 
-        let result = ScalarValue::iter_to_array(scalars.into_iter()).unwrap_err();
+        let result = ScalarValue::iter_to_array(scalars).unwrap_err();
         assert!(
             result
                 .to_string()
@@ -2915,7 +2911,7 @@ mod tests {
             make_val(Some("Bar".into())),
         ];
 
-        let array = ScalarValue::iter_to_array(scalars.into_iter()).unwrap();
+        let array = ScalarValue::iter_to_array(scalars).unwrap();
         let array = as_dictionary_array::<Int32Type>(&array);
         let values_array = as_string_array(array.values());
 
@@ -2939,7 +2935,7 @@ mod tests {
         // If the scalar values are not all the correct type, error here
         let scalars: Vec<ScalarValue> = vec![Boolean(Some(true)), Int32(Some(5))];
 
-        let result = ScalarValue::iter_to_array(scalars.into_iter()).unwrap_err();
+        let result = ScalarValue::iter_to_array(scalars).unwrap_err();
         assert!(
             result.to_string().contains(
                 "Inconsistent types in ScalarValue::iter_to_array. Expected Boolean, got Int32(5)"
@@ -2997,21 +2993,21 @@ mod tests {
             }};
         }
 
-        let bool_vals = vec![Some(true), None, Some(false)];
-        let f32_vals = vec![Some(-1.0), None, Some(1.0)];
+        let bool_vals = [Some(true), None, Some(false)];
+        let f32_vals = [Some(-1.0), None, Some(1.0)];
         let f64_vals = make_typed_vec!(f32_vals, f64);
 
-        let i8_vals = vec![Some(-1), None, Some(1)];
+        let i8_vals = [Some(-1), None, Some(1)];
         let i16_vals = make_typed_vec!(i8_vals, i16);
         let i32_vals = make_typed_vec!(i8_vals, i32);
         let i64_vals = make_typed_vec!(i8_vals, i64);
 
-        let u8_vals = vec![Some(0), None, Some(1)];
+        let u8_vals = [Some(0), None, Some(1)];
         let u16_vals = make_typed_vec!(u8_vals, u16);
         let u32_vals = make_typed_vec!(u8_vals, u32);
         let u64_vals = make_typed_vec!(u8_vals, u64);
 
-        let str_vals = vec![Some("foo"), None, Some("bar")];
+        let str_vals = [Some("foo"), None, Some("bar")];
 
         /// Test each value in `scalar` with the corresponding element
         /// at `array`. Assumes each element is unique (aka not equal
@@ -3343,26 +3339,26 @@ mod tests {
         let expected = Arc::new(StructArray::from(vec![
             (
                 field_a.clone(),
-                Arc::new(Int32Array::from_slice(&[23, 23])) as ArrayRef,
+                Arc::new(Int32Array::from(vec![23, 23])) as ArrayRef,
             ),
             (
                 field_b.clone(),
-                Arc::new(BooleanArray::from_slice(&[false, false])) as ArrayRef,
+                Arc::new(BooleanArray::from(vec![false, false])) as ArrayRef,
             ),
             (
                 field_c.clone(),
-                Arc::new(StringArray::from_slice(&["Hello", "Hello"])) as ArrayRef,
+                Arc::new(StringArray::from(vec!["Hello", "Hello"])) as ArrayRef,
             ),
             (
                 field_d.clone(),
                 Arc::new(StructArray::from(vec![
                     (
                         field_e.clone(),
-                        Arc::new(Int16Array::from_slice(&[2, 2])) as ArrayRef,
+                        Arc::new(Int16Array::from(vec![2, 2])) as ArrayRef,
                     ),
                     (
                         field_f.clone(),
-                        Arc::new(Int64Array::from_slice(&[3, 3])) as ArrayRef,
+                        Arc::new(Int64Array::from(vec![3, 3])) as ArrayRef,
                     ),
                 ])) as ArrayRef,
             ),
@@ -3438,26 +3434,26 @@ mod tests {
         let expected = Arc::new(StructArray::from(vec![
             (
                 field_a,
-                Arc::new(Int32Array::from_slice(&[23, 7, -1000])) as ArrayRef,
+                Arc::new(Int32Array::from(vec![23, 7, -1000])) as ArrayRef,
             ),
             (
                 field_b,
-                Arc::new(BooleanArray::from_slice(&[false, true, true])) as ArrayRef,
+                Arc::new(BooleanArray::from(vec![false, true, true])) as ArrayRef,
             ),
             (
                 field_c,
-                Arc::new(StringArray::from_slice(&["Hello", "World", "!!!!!"])) as ArrayRef,
+                Arc::new(StringArray::from(vec!["Hello", "World", "!!!!!"])) as ArrayRef,
             ),
             (
                 field_d,
                 Arc::new(StructArray::from(vec![
                     (
                         field_e,
-                        Arc::new(Int16Array::from_slice(&[2, 4, 6])) as ArrayRef,
+                        Arc::new(Int16Array::from(vec![2, 4, 6])) as ArrayRef,
                     ),
                     (
                         field_f,
-                        Arc::new(Int64Array::from_slice(&[3, 5, 7])) as ArrayRef,
+                        Arc::new(Int64Array::from(vec![3, 5, 7])) as ArrayRef,
                     ),
                 ])) as ArrayRef,
             ),
@@ -3518,7 +3514,7 @@ mod tests {
         let expected = StructArray::from(vec![
             (
                 field_a.clone(),
-                Arc::new(StringArray::from_slice(&["First", "Second", "Third"])) as ArrayRef,
+                Arc::new(StringArray::from(vec!["First", "Second", "Third"])) as ArrayRef,
             ),
             (
                 field_primitive_list.clone(),
@@ -2,7 +2,9 @@
 use chrono::{DateTime, Utc};
 use std::fmt::Debug;
 use std::sync::{Arc, RwLock};
-use crate::config::ConfigOptions;
+use uuid::Uuid;
+
+use crate::config::Config;
 
 /// SessionContext is the main interface for executing queries with Rapidash.
 /// It stands for the connection between user and cluster.
@@ -23,6 +25,30 @@ impl Default for SessionContext {
     }
 }
 
+impl SessionContext {
+    /// Create a session with the default configuration
+    pub fn new() -> Self {
+        Self::with_config(SessionConfig::default())
+    }
+
+    /// Create a session with the given configuration
+    pub fn with_config(config: SessionConfig) -> Self {
+        let session_id = Uuid::new_v4().to_string();
+        Self {
+            session_id: session_id.clone(),
+            session_start_time: Utc::now(),
+            state: Arc::new(RwLock::new(SessionState {
+                session_id,
+                config,
+            })),
+        }
+    }
+
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+}
+
 /// Execution context for registering data sources and executing queries
 #[derive(Clone)]
 pub struct SessionState {
@@ -45,5 +71,14 @@ impl Debug for SessionState {
 #[derive(Clone)]
 pub struct SessionConfig {
     /// Configuration options
-    pub config_options: Arc<RwLock<ConfigOptions>>,
+    pub config: Config,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            // infallible because the default settings are valid
+            config: Config::new().unwrap(),
+        }
+    }
 }
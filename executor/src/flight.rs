@@ -0,0 +1,209 @@
+//! Arrow flight service serving shuffle files to other executors.
+
+use std::fs::File;
+use std::path::PathBuf;
+use std::pin::Pin;
+
+use arrow_flight::flight_service_server::FlightService;
+use arrow_flight::utils::flight_data_from_arrow_batch;
+use arrow_flight::{
+    Action, ActionType, Criteria, Empty, FlightData, FlightDescriptor, FlightInfo,
+    HandshakeRequest, HandshakeResponse, PutResult, SchemaAsIpc, SchemaResult, Ticket,
+};
+use datafusion::arrow::ipc::reader::FileReader;
+use datafusion::arrow::ipc::writer::IpcWriteOptions;
+use futures::Stream;
+use log::{debug, warn};
+use prost::Message;
+use tokio::sync::mpsc::{channel, Sender};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status, Streaming};
+use transmit::compression::CompressionConfig;
+use transmit::proto::FetchPartition;
+use transmit::shuffle::{check_job_id, partition_dir, partition_file};
+
+type FlightStream<T> = Pin<Box<dyn Stream<Item = Result<T, Status>> + Send + 'static>>;
+
+/// Serve shuffle files under the work dir through `DoGet`
+pub struct ExecutorFlightService {
+    work_dir: String,
+    /// Compression of the streamed shuffle data
+    compression: CompressionConfig,
+}
+
+impl ExecutorFlightService {
+    pub fn new(work_dir: String, compression: CompressionConfig) -> Self {
+        Self {
+            work_dir,
+            compression,
+        }
+    }
+
+    /// Path of the file a shuffle writer wrote for the partition, the job id
+    /// must not lead out of the work dir
+    fn shuffle_file(&self, fetch: &FetchPartition) -> Result<PathBuf, Status> {
+        check_job_id(&fetch.job_id).map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let dir = partition_dir(
+            &self.work_dir,
+            &fetch.job_id,
+            fetch.stage_id as usize,
+            fetch.partition_id as usize,
+        );
+        Ok(partition_file(&dir, fetch.map_partition_id as usize))
+    }
+}
+
+/// Send the schema and then every batch of a shuffle file
+fn stream_file(
+    reader: FileReader<File>,
+    options: IpcWriteOptions,
+    tx: Sender<Result<FlightData, Status>>,
+) {
+    let schema = reader.schema();
+    if tx
+        .blocking_send(Ok(SchemaAsIpc::new(&schema, &options).into()))
+        .is_err()
+    {
+        return;
+    }
+
+    for batch in reader {
+        let data = batch
+            .map_err(|e| Status::internal(format!("Failed to read shuffle file: {}", e)))
+            .and_then(|batch| {
+                let (dictionaries, data) = flight_data_from_arrow_batch(&batch, &options);
+                if dictionaries.is_empty() {
+                    Ok(data)
+                } else {
+                    Err(Status::unimplemented("Dictionary arrays in shuffle data"))
+                }
+            });
+        let failed = data.is_err();
+        // the receiver is gone when the reader stops early
+        if tx.blocking_send(data).is_err() || failed {
+            return;
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl FlightService for ExecutorFlightService {
+    type HandshakeStream = FlightStream<HandshakeResponse>;
+    type ListFlightsStream = FlightStream<FlightInfo>;
+    type DoGetStream = FlightStream<FlightData>;
+    type DoPutStream = FlightStream<PutResult>;
+    type DoActionStream = FlightStream<arrow_flight::Result>;
+    type ListActionsStream = FlightStream<ActionType>;
+    type DoExchangeStream = FlightStream<FlightData>;
+
+    async fn do_get(
+        &self,
+        request: Request<Ticket>,
+    ) -> Result<Response<Self::DoGetStream>, Status> {
+        let fetch = FetchPartition::decode(request.into_inner().ticket.as_slice())
+            .map_err(|e| Status::invalid_argument(format!("Invalid ticket: {}", e)))?;
+        debug!(
+            "Fetch partition {} of stage {} in job {}",
+            fetch.partition_id, fetch.stage_id, fetch.job_id
+        );
+
+        let path = self.shuffle_file(&fetch)?;
+        let file = File::open(&path).map_err(|e| {
+            warn!("Failed to open shuffle file {}: {}", path.display(), e);
+            Status::not_found(format!("Shuffle file {} not found", path.display()))
+        })?;
+        let reader = FileReader::try_new(file, None)
+            .map_err(|e| Status::internal(format!("Failed to read shuffle file: {}", e)))?;
+        let options = self
+            .compression
+            .ipc_write_options()
+            .map_err(|e| Status::internal(format!("Invalid shuffle compression: {}", e)))?;
+
+        let (tx, rx) = channel(2);
+        tokio::task::spawn_blocking(move || stream_file(reader, options, tx));
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+
+    async fn handshake(
+        &self,
+        _request: Request<Streaming<HandshakeRequest>>,
+    ) -> Result<Response<Self::HandshakeStream>, Status> {
+        Err(Status::unimplemented("handshake"))
+    }
+
+    async fn list_flights(
+        &self,
+        _request: Request<Criteria>,
+    ) -> Result<Response<Self::ListFlightsStream>, Status> {
+        Err(Status::unimplemented("list_flights"))
+    }
+
+    async fn get_flight_info(
+        &self,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        Err(Status::unimplemented("get_flight_info"))
+    }
+
+    async fn get_schema(
+        &self,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<SchemaResult>, Status> {
+        Err(Status::unimplemented("get_schema"))
+    }
+
+    async fn do_put(
+        &self,
+        _request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoPutStream>, Status> {
+        Err(Status::unimplemented("do_put"))
+    }
+
+    async fn do_action(
+        &self,
+        _request: Request<Action>,
+    ) -> Result<Response<Self::DoActionStream>, Status> {
+        Err(Status::unimplemented("do_action"))
+    }
+
+    async fn list_actions(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<Self::ListActionsStream>, Status> {
+        Err(Status::unimplemented("list_actions"))
+    }
+
+    async fn do_exchange(
+        &self,
+        _request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoExchangeStream>, Status> {
+        Err(Status::unimplemented("do_exchange"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shuffle_file() {
+        let service = ExecutorFlightService::new("/work".to_string(), CompressionConfig::default());
+        let mut fetch = FetchPartition {
+            job_id: "job".to_string(),
+            stage_id: 1,
+            partition_id: 2,
+            map_partition_id: 3,
+        };
+        assert_eq!(
+            service.shuffle_file(&fetch).unwrap(),
+            PathBuf::from("/work/job/1/2/data-3.arrow")
+        );
+
+        for job_id in ["..", "../other", "/etc", ""] {
+            fetch.job_id = job_id.to_string();
+            let status = service.shuffle_file(&fetch).unwrap_err();
+            assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        }
+    }
+}
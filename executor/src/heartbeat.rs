@@ -0,0 +1,87 @@
+//! Register to the scheduler and keep sending heartbeats.
+
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use common::error::{RapidashError, Result};
+use log::{info, warn};
+use transmit::auth::AuthenticatedChannel;
+use transmit::error::from_status;
+use transmit::handshake::{check_compatible, handshake_request};
+use transmit::proto::scheduler_proto_client::SchedulerProtoClient;
+use transmit::proto::{ExecutorRegistration, HeartbeatRequest, PeerRole};
+
+/// Retry interval before the scheduler tells the real one
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Register the executor and send heartbeats with its load forever.
+/// The executor registers again whenever the scheduler forgets it,
+/// e.g. after a scheduler restart or a missed heartbeat. Whether the
+/// scheduler wants the executor to poll for tasks is stored in `pull`.
+/// Returns only when the scheduler is incompatible with this executor.
+pub async fn heartbeat_loop(
+    mut client: SchedulerProtoClient<AuthenticatedChannel>,
+    registration: ExecutorRegistration,
+    running_tasks: Arc<AtomicU32>,
+    pull: Arc<AtomicBool>,
+) -> Result<()> {
+    let mut interval = DEFAULT_INTERVAL;
+    let mut registered = false;
+
+    loop {
+        if registered {
+            let running = running_tasks.load(Ordering::SeqCst);
+            let request = HeartbeatRequest {
+                executor_id: registration.id.clone(),
+                running_tasks: running,
+                available_slots: registration.task_slots.saturating_sub(running),
+            };
+            match client.heartbeat(request).await {
+                Ok(response) => registered = !response.into_inner().reregister,
+                Err(e) => warn!("Failed to send heartbeat: {}", e),
+            }
+        }
+
+        if !registered {
+            // a restarted scheduler may run another release
+            match handshake(&mut client, &registration.id).await {
+                Ok(()) => {}
+                Err(e @ RapidashError::Incompatible(_)) => return Err(e),
+                Err(e) => {
+                    warn!("Failed to handshake with scheduler: {}", e);
+                    tokio::time::sleep(interval).await;
+                    continue;
+                }
+            }
+            match client.register_executor(registration.clone()).await {
+                Ok(response) => {
+                    let response = response.into_inner();
+                    interval = Duration::from_secs(response.heartbeat_interval.max(1));
+                    pull.store(response.pull, Ordering::SeqCst);
+                    registered = true;
+                    info!("Executor {} registered", registration.id);
+                }
+                Err(e) => warn!("Failed to register executor: {}", e),
+            }
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Exchange capabilities with the scheduler, both sides check the other
+async fn handshake(
+    client: &mut SchedulerProtoClient<AuthenticatedChannel>,
+    executor_id: &str,
+) -> Result<()> {
+    let response = client
+        .handshake(handshake_request(PeerRole::Executor, executor_id))
+        .await
+        .map_err(|status| from_status(&status))?
+        .into_inner();
+    let capabilities = response
+        .capabilities
+        .ok_or_else(|| RapidashError::Incompatible("Scheduler sent no capabilities".to_string()))?;
+    check_compatible(PeerRole::Scheduler, &capabilities)
+}
@@ -0,0 +1,11 @@
+//! Library
+
+// rpc handlers and their helpers return tonic's `Status` as error
+#![allow(clippy::result_large_err)]
+
+pub mod flight;
+pub mod heartbeat;
+pub mod poll;
+pub mod server;
+pub mod service;
+pub mod task;
@@ -1,5 +1,41 @@
 //! main
 
-fn main() {
-    println!("Hello, this is executor!");
+use std::net::SocketAddr;
+
+use common::config::Config;
+use executor::server::serve;
+use log::info;
+use tokio::net::TcpListener;
+
+/// Default scheduler url, same as the cli defaults
+const DEFAULT_SCHEDULER: &str = "http://127.0.0.1:51008";
+/// Default listen address of the executor
+const DEFAULT_ADDR: &str = "127.0.0.1:51009";
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // `RUST_LOG` overrides the info level
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    // scheduler url, listen address and work dir can be overridden by the arguments,
+    // `key=value` arguments are configuration settings
+    let (args, settings) = Config::split_args(std::env::args().skip(1));
+    let config = Config::with_settings(settings)?;
+    let mut args = args.into_iter();
+    let scheduler = args.next().unwrap_or_else(|| DEFAULT_SCHEDULER.to_string());
+    let addr: SocketAddr = args
+        .next()
+        .unwrap_or_else(|| DEFAULT_ADDR.to_string())
+        .parse()?;
+    let work_dir = args.next().unwrap_or_else(|| {
+        std::env::temp_dir()
+            .join("rapidash")
+            .to_string_lossy()
+            .to_string()
+    });
+
+    info!(
+        "Rapidash executor listening on {}, connecting to scheduler {}",
+        addr, scheduler
+    );
+    serve(config, scheduler, TcpListener::bind(addr).await?, work_dir).await
 }
@@ -0,0 +1,58 @@
+//! Poll the scheduler for tasks when it runs the pull policy.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::warn;
+use transmit::auth::AuthenticatedChannel;
+use transmit::proto::scheduler_proto_client::SchedulerProtoClient;
+use transmit::proto::{ExecutorRegistration, PollWorkRequest};
+
+use crate::task::TaskManager;
+
+/// Time between two polls, when the previous one filled no slot
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Take tasks from the scheduler for the free slots of the executor forever.
+/// Polls only while the scheduler told at registration that it runs the
+/// pull policy, otherwise it launches the tasks itself.
+pub async fn poll_loop(
+    mut client: SchedulerProtoClient<AuthenticatedChannel>,
+    registration: ExecutorRegistration,
+    tasks: TaskManager,
+    pull: Arc<AtomicBool>,
+) {
+    loop {
+        let running = tasks.running_tasks().load(Ordering::SeqCst);
+        let available_slots = registration.task_slots.saturating_sub(running);
+        if !pull.load(Ordering::SeqCst) || available_slots == 0 {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            continue;
+        }
+
+        let request = PollWorkRequest {
+            executor_id: registration.id.clone(),
+            available_slots,
+        };
+        let launched = match client.poll_work(request).await {
+            Ok(response) => {
+                let polled = response.into_inner().tasks;
+                let launched = polled.len();
+                for task in polled {
+                    tasks.launch(task);
+                }
+                launched
+            }
+            // the heartbeat registers the executor again when the scheduler forgot it
+            Err(e) => {
+                warn!("Failed to poll for tasks: {}", e);
+                0
+            }
+        };
+        // poll again right away while the scheduler has work
+        if launched == 0 {
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+}
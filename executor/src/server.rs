@@ -0,0 +1,128 @@
+//! Run an executor: register to the scheduler, run the tasks it gets and
+//! serve the shuffle files they write.
+
+use std::error::Error;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Duration;
+
+use arrow_flight::flight_service_server::FlightServiceServer;
+use common::config::Config;
+use log::info;
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::service::interceptor::InterceptedService;
+use tonic::transport::Server;
+use transmit::auth::{AuthInterceptor, TokenInterceptor};
+use transmit::compressed;
+use transmit::compression::CompressionConfig;
+use transmit::proto::executor_proto_server::ExecutorProtoServer;
+use transmit::proto::scheduler_proto_client::SchedulerProtoClient;
+use transmit::proto::ExecutorRegistration;
+use transmit::rpc::ConnectionManager;
+use transmit::tls::TlsConfig;
+use uuid::Uuid;
+
+use crate::flight::ExecutorFlightService;
+use crate::heartbeat::heartbeat_loop;
+use crate::poll::poll_loop;
+use crate::service::ExecutorServer;
+use crate::task::TaskManager;
+
+/// Serve an executor on the listener until the scheduler turns out to be
+/// incompatible, shuffle data is written to `work_dir`
+pub async fn serve(
+    config: Config,
+    scheduler: String,
+    listener: TcpListener,
+    work_dir: String,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let tls = TlsConfig::from_config(&config);
+    let compression = CompressionConfig::from_config(&config);
+    let addr = listener.local_addr()?;
+    let task_slots = std::thread::available_parallelism()
+        .map(|n| n.get() as u32)
+        .unwrap_or(1);
+    let registration = ExecutorRegistration {
+        id: Uuid::new_v4().to_string(),
+        host: addr.ip().to_string(),
+        port: addr.port() as u32,
+        task_slots,
+    };
+    info!(
+        "Executor {} listening on {}, connecting to scheduler {}",
+        registration.id, addr, scheduler
+    );
+
+    // connect lazily, the scheduler may start after the executor
+    let endpoint = tls
+        .endpoint(scheduler.clone())?
+        .connect_timeout(Duration::from_secs(config.rpc_connect_timeout() as u64))
+        .timeout(Duration::from_secs(config.rpc_request_timeout() as u64))
+        .tcp_keepalive(
+            config
+                .rpc_tcp_keepalive()
+                .map(|secs| Duration::from_secs(secs as u64)),
+        )
+        .http2_keep_alive_interval(Duration::from_secs(config.rpc_keepalive_interval() as u64))
+        .keep_alive_timeout(Duration::from_secs(config.rpc_keepalive_timeout() as u64));
+    let client = compressed!(
+        SchedulerProtoClient::with_interceptor(
+            endpoint.connect_lazy(),
+            TokenInterceptor::from_config(&config)?,
+        ),
+        compression
+    );
+    // statuses of finished tasks are retried until the scheduler gets them
+    let tasks = TaskManager::new(
+        registration.clone(),
+        work_dir.clone(),
+        scheduler,
+        ConnectionManager::from_config(&config)?,
+        tls.clone(),
+        compression,
+    );
+    // set by the registration when the scheduler runs the pull policy
+    let pull = Arc::new(AtomicBool::new(false));
+    let heartbeat = tokio::spawn(heartbeat_loop(
+        client.clone(),
+        registration.clone(),
+        tasks.running_tasks(),
+        pull.clone(),
+    ));
+    tokio::spawn(poll_loop(client, registration, tasks.clone(), pull));
+
+    // the scheduler launches and cancels tasks and other executors and the
+    // scheduler fetch shuffle files with a token, once the executor
+    // authenticates calls
+    let auth = AuthInterceptor::from_config(&config)?;
+    // shuffle files are served by arrow flight on the same port
+    let mut server = Server::builder();
+    if let Some(tls) = tls.server_tls()? {
+        server = server.tls_config(tls)?;
+    }
+    let server = server
+        .add_service(InterceptedService::new(
+            compressed!(
+                ExecutorProtoServer::new(ExecutorServer::new(tasks, &config)),
+                compression
+            ),
+            auth.clone(),
+        ))
+        .add_service(InterceptedService::new(
+            compressed!(
+                FlightServiceServer::new(ExecutorFlightService::new(work_dir, compression)),
+                compression
+            ),
+            auth,
+        ))
+        .serve_with_incoming(TcpListenerStream::new(listener));
+
+    // stop when the scheduler turns out to be incompatible
+    tokio::select! {
+        result = server => result?,
+        result = heartbeat => result??,
+    }
+
+    Ok(())
+}
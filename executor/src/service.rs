@@ -0,0 +1,148 @@
+//! Executor side implementation of `ExecutorProto`.
+
+use common::config::Config;
+use tonic::{Request, Response, Status};
+use transmit::auth::{principal, AuthInterceptor};
+use transmit::proto::executor_proto_server::ExecutorProto;
+use transmit::proto::{
+    CancelTaskRequest, CancelTaskResponse, LaunchTaskRequest, LaunchTaskResponse,
+};
+use transmit::shuffle::check_job_id;
+
+use crate::task::TaskManager;
+
+pub struct ExecutorServer {
+    tasks: TaskManager,
+    /// Principals of `rapidash.scheduler.principals`
+    schedulers: Vec<String>,
+    /// Whether calls are authenticated
+    authenticated: bool,
+}
+
+impl ExecutorServer {
+    pub fn new(tasks: TaskManager, config: &Config) -> Self {
+        Self {
+            tasks,
+            schedulers: config.scheduler_principals(),
+            authenticated: AuthInterceptor::is_configured(config),
+        }
+    }
+
+    /// Tasks are launched and cancelled only by `rapidash.scheduler.principals`
+    /// once calls authenticate
+    fn check_scheduler<T>(&self, request: &Request<T>) -> Result<(), Status> {
+        match principal(request) {
+            Some(principal) if !self.schedulers.contains(&principal) => Err(
+                Status::permission_denied(format!("{} is not a scheduler", principal)),
+            ),
+            None if self.authenticated => Err(Status::unauthenticated(
+                "Task rpcs need an authenticated scheduler",
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl ExecutorProto for ExecutorServer {
+    async fn launch_task(
+        &self,
+        request: Request<LaunchTaskRequest>,
+    ) -> Result<Response<LaunchTaskResponse>, Status> {
+        self.check_scheduler(&request)?;
+        let tasks = request.into_inner().tasks;
+        // the job id names the directory of the shuffle data the task writes
+        for task in &tasks {
+            let partition = task.partition.as_ref();
+            check_job_id(partition.map(|p| p.job_id.as_str()).unwrap_or_default())
+                .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        }
+        for task in tasks {
+            self.tasks.launch(task);
+        }
+
+        Ok(Response::new(LaunchTaskResponse { success: true }))
+    }
+
+    async fn cancel_task(
+        &self,
+        request: Request<CancelTaskRequest>,
+    ) -> Result<Response<CancelTaskResponse>, Status> {
+        self.check_scheduler(&request)?;
+        let CancelTaskRequest {
+            task_ids,
+            clean_job_ids,
+        } = request.into_inner();
+        for job_id in &clean_job_ids {
+            check_job_id(job_id).map_err(|e| Status::invalid_argument(e.to_string()))?;
+        }
+
+        let mut cancelled = false;
+        for task_id in &task_ids {
+            cancelled |= self.tasks.cancel(task_id);
+        }
+        for job_id in &clean_job_ids {
+            self.tasks.clean_job(job_id);
+        }
+
+        Ok(Response::new(CancelTaskResponse { cancelled }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::config::{AUTH_TOKEN_FILE, SCHEDULER_PRINCIPALS};
+    use transmit::auth::Principal;
+    use transmit::compression::CompressionConfig;
+    use transmit::proto::ExecutorRegistration;
+    use transmit::rpc::ConnectionManager;
+    use transmit::tls::TlsConfig;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_scheduler_principals() {
+        let config = Config::builder()
+            .set(AUTH_TOKEN_FILE, "tokens")
+            .set(SCHEDULER_PRINCIPALS, "scheduler")
+            .build()
+            .unwrap();
+        let tasks = TaskManager::new(
+            ExecutorRegistration::default(),
+            std::env::temp_dir().to_string_lossy().to_string(),
+            "http://127.0.0.1:1".to_string(),
+            ConnectionManager::from_config(&Config::new().unwrap()).unwrap(),
+            TlsConfig::default(),
+            CompressionConfig::default(),
+        );
+        let server = ExecutorServer::new(tasks, &config);
+        let request = |principal: Option<&str>| {
+            let mut request = Request::new(CancelTaskRequest::default());
+            if let Some(principal) = principal {
+                request
+                    .extensions_mut()
+                    .insert(Principal(principal.to_string()));
+            }
+            request
+        };
+
+        let status = server.cancel_task(request(None)).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::Unauthenticated);
+        let status = server
+            .cancel_task(request(Some("alice")))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+        server
+            .cancel_task(request(Some("scheduler")))
+            .await
+            .unwrap();
+
+        let mut request = Request::new(LaunchTaskRequest::default());
+        request
+            .extensions_mut()
+            .insert(Principal("alice".to_string()));
+        let status = server.launch_task(request).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+    }
+}
@@ -0,0 +1,246 @@
+//! Run tasks launched by the scheduler.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use datafusion::error::{DataFusionError, Result};
+use datafusion::physical_plan::ExecutionPlan;
+use datafusion::prelude::{SessionConfig, SessionContext};
+use log::{error, info, warn};
+use tokio::task::JoinHandle;
+use transmit::codec::decode_plan;
+use transmit::compressed;
+use transmit::compression::CompressionConfig;
+use transmit::proto::scheduler_proto_client::SchedulerProtoClient;
+use transmit::proto::{
+    task_status, ExecutorRegistration, FailedTask, PartitionId, ShuffleLocation, SuccessfulTask,
+    TaskDefinition, TaskMetrics, TaskStatus, UpdateTaskStatusRequest,
+};
+use transmit::rpc::ConnectionManager;
+use transmit::shuffle::{
+    fetch_partition_error, remove_job_output, remove_task_output, ShuffleWriterExec,
+};
+use transmit::tls::TlsConfig;
+
+/// A task being run
+struct RunningTask {
+    partition: PartitionId,
+    handle: JoinHandle<()>,
+}
+
+/// Run tasks and report their status to the scheduler
+#[derive(Clone)]
+pub struct TaskManager {
+    executor: ExecutorRegistration,
+    /// Directory of the shuffle data
+    work_dir: String,
+    ctx: SessionContext,
+    /// Url of the scheduler the statuses are reported to
+    scheduler: String,
+    connections: ConnectionManager,
+    tasks: Arc<Mutex<HashMap<String, RunningTask>>>,
+    running_tasks: Arc<AtomicU32>,
+}
+
+impl TaskManager {
+    pub fn new(
+        executor: ExecutorRegistration,
+        work_dir: String,
+        scheduler: String,
+        connections: ConnectionManager,
+        tls: TlsConfig,
+        compression: CompressionConfig,
+    ) -> Self {
+        // shuffle readers fetch partitions from other executors with the same TLS
+        // and token, shuffle writers compress partitions as configured
+        let config = SessionConfig::new()
+            .with_extension(Arc::new(tls))
+            .with_extension(Arc::new(compression))
+            .with_extension(Arc::new(connections.token()));
+        Self {
+            executor,
+            work_dir,
+            ctx: SessionContext::with_config(config),
+            scheduler,
+            connections,
+            tasks: Arc::new(Mutex::new(HashMap::new())),
+            running_tasks: Arc::new(AtomicU32::new(0)),
+        }
+    }
+
+    /// Number of running tasks, shared with the heartbeat
+    pub fn running_tasks(&self) -> Arc<AtomicU32> {
+        self.running_tasks.clone()
+    }
+
+    /// Start running a task in background
+    pub fn launch(&self, task: TaskDefinition) {
+        info!(
+            "Launch task {} attempt {} of {:?}",
+            task.task_id, task.attempt, task.partition
+        );
+        // hold the lock while spawning, so the task can't finish before it is added
+        let mut tasks = self.tasks.lock().unwrap();
+        self.running_tasks.fetch_add(1, Ordering::SeqCst);
+        let manager = self.clone();
+        let task_id = task.task_id.clone();
+        let partition = task.partition.clone().unwrap_or_default();
+        let handle = tokio::spawn(async move { manager.run(task).await });
+        tasks.insert(task_id, RunningTask { partition, handle });
+    }
+
+    /// Stop a running task and remove its partial output, return false if it is not running
+    pub fn cancel(&self, task_id: &str) -> bool {
+        let task = match self.tasks.lock().unwrap().remove(task_id) {
+            Some(task) => task,
+            None => return false,
+        };
+        task.handle.abort();
+        self.running_tasks.fetch_sub(1, Ordering::SeqCst);
+
+        let partition = task.partition;
+        if let Err(e) = remove_task_output(
+            &self.work_dir,
+            &partition.job_id,
+            partition.stage_id as usize,
+            partition.partition_id as usize,
+        ) {
+            warn!("Failed to remove output of task {}: {}", task_id, e);
+        }
+        info!("Task {} cancelled", task_id);
+        true
+    }
+
+    /// Remove all shuffle data of a job
+    pub fn clean_job(&self, job_id: &str) {
+        if let Err(e) = remove_job_output(&self.work_dir, job_id) {
+            warn!("Failed to remove shuffle data of job {}: {}", job_id, e);
+        }
+    }
+
+    async fn run(&self, task: TaskDefinition) {
+        let start_time = now();
+        let result = self.execute(&task).await;
+        let end_time = now();
+
+        // a cancelled task has been removed already, nothing to report
+        if self.tasks.lock().unwrap().remove(&task.task_id).is_none() {
+            return;
+        }
+        self.running_tasks.fetch_sub(1, Ordering::SeqCst);
+
+        let status = match result {
+            Ok(locations) => {
+                info!("Task {} succeeded", task.task_id);
+                let output_rows = locations
+                    .iter()
+                    .filter_map(|l| l.stats.as_ref())
+                    .map(|s| s.num_rows)
+                    .sum();
+                task_status::Status::Successful(SuccessfulTask {
+                    locations,
+                    metrics: Some(TaskMetrics {
+                        start_time,
+                        end_time,
+                        output_rows,
+                    }),
+                })
+            }
+            Err(e) => {
+                error!("Task {} failed: {}", task.task_id, e);
+                task_status::Status::Failed(FailedTask {
+                    error: e.to_string(),
+                    fetch_failed: fetch_partition_error(&e),
+                })
+            }
+        };
+        let request = UpdateTaskStatusRequest {
+            executor_id: self.executor.id.clone(),
+            statuses: vec![TaskStatus {
+                task_id: task.task_id.clone(),
+                partition: task.partition.clone(),
+                attempt: task.attempt,
+                executor_id: self.executor.id.clone(),
+                status: Some(status),
+            }],
+        };
+        // the scheduler ignores a status reported twice, so a report that may
+        // have been received is sent again too
+        let connections = &self.connections;
+        let result = connections
+            .call(&self.scheduler, true, |channel| {
+                let mut client = compressed!(
+                    SchedulerProtoClient::with_interceptor(channel, connections.token()),
+                    connections.compression()
+                );
+                let request = request.clone();
+                async move { client.update_task_status(request).await }
+            })
+            .await;
+        if let Err(e) = result {
+            error!("Failed to report status of task {}: {}", task.task_id, e);
+        }
+    }
+
+    /// Write the input partition of the task to shuffle files
+    async fn execute(&self, task: &TaskDefinition) -> Result<Vec<ShuffleLocation>> {
+        let partition = task
+            .partition
+            .clone()
+            .ok_or_else(|| DataFusionError::Internal("Task without partition".to_string()))?;
+        let plan = decode_plan(&task.plan)?;
+        let writer = plan
+            .as_any()
+            .downcast_ref::<ShuffleWriterExec>()
+            .ok_or_else(|| {
+                DataFusionError::Internal("Task plan must be a ShuffleWriterExec".to_string())
+            })?;
+        // the job id of the partition was checked when the task was launched
+        if writer.job_id() != partition.job_id {
+            return Err(DataFusionError::Internal(format!(
+                "Task of job {} writes shuffle data of job {}",
+                partition.job_id,
+                writer.job_id()
+            )));
+        }
+        // shuffle data goes to the work dir of this executor
+        let writer = ShuffleWriterExec::try_new(
+            writer.job_id().to_string(),
+            writer.stage_id(),
+            writer.children()[0].clone(),
+            self.work_dir.clone(),
+            writer.shuffle_output_partitioning().cloned(),
+        )?;
+
+        let partitions = writer
+            .write_partition(partition.partition_id as usize, self.ctx.task_ctx())
+            .await?;
+
+        Ok(partitions
+            .into_iter()
+            .map(|p| ShuffleLocation {
+                partition: Some(PartitionId {
+                    job_id: partition.job_id.clone(),
+                    stage_id: partition.stage_id,
+                    partition_id: p.partition_id as u32,
+                }),
+                executor_id: self.executor.id.clone(),
+                host: self.executor.host.clone(),
+                port: self.executor.port,
+                path: p.path,
+                stats: Some(p.stats.into()),
+                map_partition_id: partition.partition_id,
+            })
+            .collect())
+    }
+}
+
+/// Milliseconds since unix epoch
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
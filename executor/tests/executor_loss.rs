@@ -0,0 +1,149 @@
+//! Executor loss tests against a local scheduler and executor processes
+use std::collections::HashMap;
+use std::fs;
+use std::process::{Child, Command};
+use std::time::Duration;
+
+use common::config::{Config, EXECUTOR_HEARTBEAT_INTERVAL, EXECUTOR_TIMEOUT};
+use datafusion::arrow::array::Int64Array;
+use scheduler::job::{Job, JobState};
+use scheduler::query::Query;
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::transport::Server;
+use transmit::proto::scheduler_proto_server::SchedulerProtoServer;
+use uuid::Uuid;
+
+/// An executor process, killed when dropped
+struct ExecutorProcess(Child);
+
+impl ExecutorProcess {
+    /// Start an executor on a free port connecting to the scheduler
+    fn start(scheduler: &str) -> Self {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let work_dir = std::env::temp_dir().join(format!("rapidash-executor-{}", Uuid::new_v4()));
+        let child = Command::new(env!("CARGO_BIN_EXE_executor"))
+            .arg(scheduler)
+            .arg(format!("127.0.0.1:{}", port))
+            .arg(work_dir)
+            .spawn()
+            .unwrap();
+        Self(child)
+    }
+
+    fn kill(&mut self) {
+        self.0.kill().unwrap();
+        self.0.wait().unwrap();
+    }
+}
+
+impl Drop for ExecutorProcess {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+    }
+}
+
+/// Start a scheduler dropping executors after 2 seconds without heartbeat,
+/// return it and its url
+async fn serve() -> (Query, String) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let settings = HashMap::from([
+        (EXECUTOR_HEARTBEAT_INTERVAL.to_string(), "1".to_string()),
+        (EXECUTOR_TIMEOUT.to_string(), "2".to_string()),
+    ]);
+    let query = Query::new(Config::with_settings(settings).unwrap()).unwrap();
+    query.start_expiry_check(Duration::from_millis(200));
+    tokio::spawn(
+        Server::builder()
+            .add_service(SchedulerProtoServer::new(query.clone()))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+    (query, format!("http://{}", addr))
+}
+
+/// Ids of the registered executors, once there are `count` of them
+async fn registered(query: &Query, count: usize) -> Vec<String> {
+    for _ in 0..300 {
+        let executors = query.executors().executors();
+        if executors.len() == count {
+            return executors.into_iter().map(|info| info.meta.id).collect();
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("{} executors did not register", count);
+}
+
+/// Wait until the scheduler marked the executor dead
+async fn dead(query: &Query, executor_id: &str) {
+    for _ in 0..300 {
+        if query.executors().is_dead(executor_id) {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("Executor {} was not marked dead", executor_id);
+}
+
+/// Wait until the stages of the job were submitted to the executors
+async fn submitted(query: &Query, job_id: &str) {
+    for _ in 0..300 {
+        if query.executions().state(job_id).is_some() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("Job {} was not submitted", job_id);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_job_survives_executor_loss() {
+    let dir = std::env::temp_dir().join(format!("rapidash-loss-{}", Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("t.csv");
+    fs::write(&path, "a,b\n1,2\n1,3\n2,4\n3,5\n").unwrap();
+
+    let (query, url) = serve().await;
+    let mut lost = ExecutorProcess::start(&url);
+    let lost_id = registered(&query, 1).await.remove(0);
+    let sql = format!(
+        "create external table t (a bigint, b bigint) stored as csv with header row location '{}'",
+        path.display()
+    );
+    let id = query.jobs().submit(Job::new(sql, None)).await.unwrap();
+    query.jobs().wait(&id).await.unwrap();
+
+    // the scan is launched on the only executor, which is killed mid-job
+    let sql = "select a, sum(b) as s from t group by a order by a".to_string();
+    let id = query.jobs().submit(Job::new(sql, None)).await.unwrap();
+    submitted(&query, &id).await;
+    lost.kill();
+
+    // the tasks of the dead executor run again on a healthy one
+    dead(&query, &lost_id).await;
+    let _healthy = ExecutorProcess::start(&url);
+    let job = query.jobs().wait(&id).await.unwrap();
+    assert_eq!(job.state(), JobState::Succeeded, "{:?}", job.error);
+    let sums: Vec<i64> = query
+        .jobs()
+        .result(&id)
+        .unwrap()
+        .batches
+        .iter()
+        .flat_map(|batch| {
+            let sums = batch
+                .column(1)
+                .as_any()
+                .downcast_ref::<Int64Array>()
+                .unwrap();
+            sums.iter().flatten().collect::<Vec<_>>()
+        })
+        .collect();
+    assert_eq!(sums, vec![5, 4, 5]);
+    // the graph of the finished job is gone
+    assert_eq!(query.executions().state(&id), None);
+}
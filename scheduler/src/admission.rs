@@ -0,0 +1,312 @@
+//! Admission of jobs against the resource quotas.
+//!
+//! A job is estimated when it is submitted: its tasks are the partitions of
+//! its stages and its memory the bytes its scans read. A job over a quota on
+//! its own is rejected with `ResourcesExhausted`. A job only over a quota
+//! together with the running jobs waits in the queue until enough of them
+//! finish, or is rejected as well with `rapidash.quota.exceeded=reject`.
+//! Admins change the quotas at runtime with the `UpdateQuotas` rpc.
+
+use std::sync::{Arc, RwLock};
+
+use common::config::Config;
+use common::error::{RapidashError, Result};
+use datafusion::physical_plan::ExecutionPlan;
+use transmit::proto;
+
+use crate::job::Job;
+use crate::planner::{file_scan_config, QueryStage};
+
+const MIB: usize = 1024 * 1024;
+
+/// Resources a job is estimated to use while it runs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceEstimate {
+    /// Bytes read by the scans of the job
+    pub memory: usize,
+    pub tasks: usize,
+}
+
+impl ResourceEstimate {
+    pub fn of_stages(stages: &[QueryStage]) -> Self {
+        let mut estimate = Self::default();
+        for stage in stages {
+            let plan: Arc<dyn ExecutionPlan> = stage.plan.clone();
+            estimate.memory += scan_bytes(&plan);
+            estimate.tasks += stage.partition_count();
+        }
+        estimate
+    }
+}
+
+/// Bytes of the files scanned by the plan, or of the statistics of its other leaves
+fn scan_bytes(plan: &Arc<dyn ExecutionPlan>) -> usize {
+    if let Some(config) = file_scan_config(plan.as_ref()) {
+        return config
+            .file_groups
+            .iter()
+            .flatten()
+            .map(|file| file.object_meta.size)
+            .sum();
+    }
+    let children = plan.children();
+    if children.is_empty() {
+        return plan.statistics().total_byte_size.unwrap_or_default();
+    }
+    children.iter().map(scan_bytes).sum()
+}
+
+/// Limits of the resources of running jobs, 0 for no limit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Quotas {
+    /// Estimated bytes of all running jobs
+    pub cluster_memory: usize,
+    pub cluster_tasks: usize,
+    /// Estimated bytes of the running jobs of a user
+    pub user_memory: usize,
+    pub user_tasks: usize,
+    /// Jobs over the quotas are rejected instead of waiting in the queue
+    pub reject: bool,
+}
+
+impl Quotas {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            cluster_memory: config.quota_cluster_memory().saturating_mul(MIB),
+            cluster_tasks: config.quota_cluster_tasks(),
+            user_memory: config.quota_user_memory().saturating_mul(MIB),
+            user_tasks: config.quota_user_tasks(),
+            // the value is validated by the configuration
+            reject: config.quota_exceeded() == "reject",
+        }
+    }
+}
+
+/// Memory quotas are in MiB in the rpcs, like in the configuration
+impl From<Quotas> for proto::Quotas {
+    fn from(quotas: Quotas) -> Self {
+        Self {
+            cluster_memory: (quotas.cluster_memory / MIB) as u64,
+            cluster_tasks: quotas.cluster_tasks as u32,
+            user_memory: (quotas.user_memory / MIB) as u64,
+            user_tasks: quotas.user_tasks as u32,
+            reject: quotas.reject,
+        }
+    }
+}
+
+impl From<proto::Quotas> for Quotas {
+    fn from(quotas: proto::Quotas) -> Self {
+        Self {
+            cluster_memory: (quotas.cluster_memory as usize).saturating_mul(MIB),
+            cluster_tasks: quotas.cluster_tasks as usize,
+            user_memory: (quotas.user_memory as usize).saturating_mul(MIB),
+            user_tasks: quotas.user_tasks as usize,
+            reject: quotas.reject,
+        }
+    }
+}
+
+/// Whether a job may start next to the running jobs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Admission {
+    Admit,
+    /// Over a quota with the running jobs, fits once some of them finish
+    Wait(String),
+    /// Over a quota on its own, it never fits
+    Reject(String),
+}
+
+/// Check jobs against quotas shared by every clone, so they change at runtime
+#[derive(Debug, Clone, Default)]
+pub struct AdmissionControl {
+    quotas: Arc<RwLock<Quotas>>,
+}
+
+impl AdmissionControl {
+    pub fn new(quotas: Quotas) -> Self {
+        Self {
+            quotas: Arc::new(RwLock::new(quotas)),
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(Quotas::from_config(config))
+    }
+
+    pub fn quotas(&self) -> Quotas {
+        *self.quotas.read().unwrap()
+    }
+
+    pub fn set_quotas(&self, quotas: Quotas) {
+        *self.quotas.write().unwrap() = quotas;
+    }
+
+    /// Whether the job fits in every quota next to the running jobs, the
+    /// reason of a wait is the first quota it goes over
+    pub fn check(&self, job: &Job, running: &[&Job]) -> Admission {
+        let quotas = self.quotas();
+        let user = job.principal.as_deref().unwrap_or("anonymous");
+        let of_user: Vec<&Job> = running
+            .iter()
+            .copied()
+            .filter(|other| other.principal == job.principal)
+            .collect();
+        let cluster = ("cluster quota".to_string(), "running jobs".to_string());
+        let per_user = (
+            format!("quota of user {}", user),
+            format!("running jobs of user {}", user),
+        );
+        let limits = [
+            (&cluster, Resource::Memory, quotas.cluster_memory, running),
+            (&cluster, Resource::Tasks, quotas.cluster_tasks, running),
+            (
+                &per_user,
+                Resource::Memory,
+                quotas.user_memory,
+                &of_user[..],
+            ),
+            (&per_user, Resource::Tasks, quotas.user_tasks, &of_user[..]),
+        ];
+
+        let mut admission = Admission::Admit;
+        for ((quota, holders), resource, limit, jobs) in limits {
+            let needed = resource.of(&job.estimate);
+            let used: usize = jobs.iter().map(|job| resource.of(&job.estimate)).sum();
+            if limit == 0 || used + needed <= limit {
+                continue;
+            }
+            if needed > limit {
+                return Admission::Reject(format!(
+                    "Job {} needs an estimated {}, over the {} of {}",
+                    job.id,
+                    resource.format(needed),
+                    quota,
+                    resource.format(limit)
+                ));
+            }
+            if admission == Admission::Admit {
+                admission = Admission::Wait(format!(
+                    "Job {} needs an estimated {}, but the {} already use {} of the {} of {}",
+                    job.id,
+                    resource.format(needed),
+                    holders,
+                    resource.format(used),
+                    quota,
+                    resource.format(limit)
+                ));
+            }
+        }
+        admission
+    }
+
+    /// Check a submitted job, a job that has to wait is only an error when
+    /// the quotas reject instead of queueing
+    pub fn admit(&self, job: &Job, running: &[&Job]) -> Result<()> {
+        match self.check(job, running) {
+            Admission::Admit => Ok(()),
+            Admission::Wait(_) if !self.quotas().reject => Ok(()),
+            Admission::Wait(reason) | Admission::Reject(reason) => {
+                Err(RapidashError::ResourcesExhausted(reason))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Resource {
+    Memory,
+    Tasks,
+}
+
+impl Resource {
+    fn of(&self, estimate: &ResourceEstimate) -> usize {
+        match self {
+            Resource::Memory => estimate.memory,
+            Resource::Tasks => estimate.tasks,
+        }
+    }
+
+    fn format(&self, amount: usize) -> String {
+        match self {
+            Resource::Memory => format!("{:.1} MiB of memory", amount as f64 / MIB as f64),
+            Resource::Tasks => format!("{} tasks", amount),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(principal: &str, memory_mib: usize, tasks: usize) -> Job {
+        let mut job = Job::new("select 1".to_string(), Some(principal.to_string()));
+        job.estimate = ResourceEstimate {
+            memory: memory_mib * MIB,
+            tasks,
+        };
+        job
+    }
+
+    fn control(reject: bool) -> AdmissionControl {
+        AdmissionControl::new(Quotas {
+            cluster_memory: 100 * MIB,
+            cluster_tasks: 0,
+            user_memory: 0,
+            user_tasks: 8,
+            reject,
+        })
+    }
+
+    #[test]
+    fn test_reject_job_over_quota() {
+        let big = job("alice", 200, 1);
+        let admission = control(false).check(&big, &[]);
+        assert_eq!(
+            admission,
+            Admission::Reject(format!(
+                "Job {} needs an estimated 200.0 MiB of memory, over the cluster quota of 100.0 MiB of memory",
+                big.id
+            ))
+        );
+        assert!(matches!(
+            control(false).admit(&big, &[]),
+            Err(RapidashError::ResourcesExhausted(_))
+        ));
+    }
+
+    #[test]
+    fn test_wait_for_running_jobs() {
+        let running = job("alice", 10, 6);
+        let other_user = job("bob", 10, 4);
+        assert_eq!(
+            control(false).check(&other_user, &[&running]),
+            Admission::Admit
+        );
+
+        let queued = job("alice", 10, 4);
+        let admission = control(false).check(&queued, &[&running]);
+        assert_eq!(
+            admission,
+            Admission::Wait(format!(
+                "Job {} needs an estimated 4 tasks, but the running jobs of user alice already use 6 tasks of the quota of user alice of 8 tasks",
+                queued.id
+            ))
+        );
+        // queued by default, rejected when the quotas say so
+        assert!(control(false).admit(&queued, &[&running]).is_ok());
+        assert!(control(true).admit(&queued, &[&running]).is_err());
+    }
+
+    #[test]
+    fn test_change_quotas() {
+        let control = control(false);
+        let shared = control.clone();
+        let big = job("alice", 200, 1);
+        shared.set_quotas(Quotas {
+            cluster_memory: 500 * MIB,
+            ..control.quotas()
+        });
+        assert_eq!(control.check(&big, &[]), Admission::Admit);
+    }
+}
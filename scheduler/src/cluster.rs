@@ -0,0 +1,282 @@
+//! Executors registered to the scheduler.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use common::error::Result;
+use log::{info, warn};
+
+use crate::state::SchedulerState;
+
+/// Static description of an executor, sent when it registers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutorMeta {
+    pub id: String,
+    pub host: String,
+    pub port: u16,
+    /// Number of tasks the executor can run at the same time
+    pub task_slots: u32,
+}
+
+/// A registered executor and its latest load
+#[derive(Debug, Clone)]
+pub struct ExecutorInfo {
+    pub meta: ExecutorMeta,
+    pub running_tasks: u32,
+    pub available_slots: u32,
+    /// Tasks assigned to the executor that have not finished, each holds a slot
+    pub reserved: HashSet<String>,
+    /// Time of the latest registration or heartbeat
+    pub last_seen: Instant,
+}
+
+impl ExecutorInfo {
+    /// Slots tasks can be assigned to. Heartbeats lag behind the tasks the
+    /// scheduler assigns, so only the reservations count.
+    pub fn free_slots(&self) -> u32 {
+        self.meta
+            .task_slots
+            .saturating_sub(self.reserved.len() as u32)
+    }
+}
+
+/// Live registry of executors, an executor is marked dead when it misses heartbeats
+#[derive(Clone)]
+pub struct ExecutorManager {
+    executors: Arc<RwLock<HashMap<String, ExecutorInfo>>>,
+    /// Ids of executors with a compatible handshake
+    handshaken: Arc<RwLock<HashSet<String>>>,
+    /// Ids of executors dropped after missing heartbeats, until they register again
+    dead: Arc<RwLock<HashSet<String>>>,
+    /// Time without heartbeat before an executor is dropped
+    timeout: Duration,
+    /// Registrations are written there until the executor is dropped
+    state: SchedulerState,
+}
+
+impl ExecutorManager {
+    pub fn new(timeout: Duration) -> Self {
+        Self::with_state(timeout, SchedulerState::default())
+    }
+
+    pub fn with_state(timeout: Duration, state: SchedulerState) -> Self {
+        Self {
+            executors: Arc::new(RwLock::new(HashMap::new())),
+            handshaken: Arc::new(RwLock::new(HashSet::new())),
+            dead: Arc::new(RwLock::new(HashSet::new())),
+            timeout,
+            state,
+        }
+    }
+
+    /// Register the executors of the state again after a restart, return
+    /// their number. They passed the handshake before, and are dropped if
+    /// they don't heartbeat within the timeout.
+    pub fn recover(&self) -> Result<usize> {
+        let executors = self.state.executors()?;
+        let count = executors.len();
+        for meta in executors {
+            self.accept(&meta.id);
+            self.insert(meta);
+        }
+        Ok(count)
+    }
+
+    /// Record an executor passed the handshake, only those may register
+    pub fn accept(&self, id: &str) {
+        self.handshaken.write().unwrap().insert(id.to_string());
+    }
+
+    pub fn is_accepted(&self, id: &str) -> bool {
+        self.handshaken.read().unwrap().contains(id)
+    }
+
+    /// Register an executor, a known executor is registered again from scratch.
+    /// Return true if the executor was known, dead or live, its tasks are lost.
+    pub fn register(&self, meta: ExecutorMeta) -> bool {
+        let recovered = self.dead.write().unwrap().remove(&meta.id);
+        let restarted = self.executors.read().unwrap().contains_key(&meta.id);
+        info!(
+            "Executor {} {} at {}:{} with {} task slots",
+            meta.id,
+            if recovered {
+                "recovered"
+            } else if restarted {
+                "restarted"
+            } else {
+                "registered"
+            },
+            meta.host,
+            meta.port,
+            meta.task_slots
+        );
+        if let Err(e) = self.state.save_executor(&meta) {
+            warn!("Failed to save executor {}: {}", meta.id, e);
+        }
+        self.insert(meta);
+        recovered || restarted
+    }
+
+    fn insert(&self, meta: ExecutorMeta) {
+        let info = ExecutorInfo {
+            running_tasks: 0,
+            available_slots: meta.task_slots,
+            reserved: HashSet::new(),
+            last_seen: Instant::now(),
+            meta,
+        };
+        self.executors
+            .write()
+            .unwrap()
+            .insert(info.meta.id.clone(), info);
+    }
+
+    /// Record a heartbeat, return false if the executor is not registered
+    pub fn heartbeat(&self, id: &str, running_tasks: u32, available_slots: u32) -> bool {
+        match self.executors.write().unwrap().get_mut(id) {
+            Some(info) => {
+                info.running_tasks = running_tasks;
+                info.available_slots = available_slots;
+                info.last_seen = Instant::now();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Option<ExecutorInfo> {
+        self.executors.read().unwrap().get(id).cloned()
+    }
+
+    /// All registered executors
+    pub fn executors(&self) -> Vec<ExecutorInfo> {
+        self.executors.read().unwrap().values().cloned().collect()
+    }
+
+    /// Hold a slot of the executor for each task until it finishes
+    pub fn reserve(&self, id: &str, task_ids: &[String]) {
+        if let Some(info) = self.executors.write().unwrap().get_mut(id) {
+            info.reserved.extend(task_ids.iter().cloned());
+        }
+    }
+
+    /// Free the slots of finished or cancelled tasks, releasing a task twice
+    /// is a no-op
+    pub fn release(&self, task_ids: &[String]) {
+        for info in self.executors.write().unwrap().values_mut() {
+            for task_id in task_ids {
+                info.reserved.remove(task_id);
+            }
+        }
+    }
+
+    pub fn is_dead(&self, id: &str) -> bool {
+        self.dead.read().unwrap().contains(id)
+    }
+
+    /// Mark dead and drop the executors that missed heartbeats for longer
+    /// than the timeout, return their ids
+    pub fn remove_expired(&self) -> Vec<String> {
+        let mut executors = self.executors.write().unwrap();
+        let expired: Vec<String> = executors
+            .values()
+            .filter(|info| info.last_seen.elapsed() > self.timeout)
+            .map(|info| info.meta.id.clone())
+            .collect();
+        let mut dead = self.dead.write().unwrap();
+        for id in &expired {
+            warn!("Executor {} missed heartbeats, marked dead", id);
+            executors.remove(id);
+            dead.insert(id.clone());
+            if let Err(e) = self.state.remove_executor(id) {
+                warn!("Failed to remove executor {}: {}", id, e);
+            }
+        }
+        expired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta(id: &str) -> ExecutorMeta {
+        ExecutorMeta {
+            id: id.to_string(),
+            host: "localhost".to_string(),
+            port: 51009,
+            task_slots: 4,
+        }
+    }
+
+    #[test]
+    fn test_register_and_heartbeat() {
+        let manager = ExecutorManager::new(Duration::from_secs(30));
+        manager.register(meta("a"));
+
+        assert!(manager.heartbeat("a", 3, 1));
+        assert!(!manager.heartbeat("b", 0, 4));
+
+        let info = manager.get("a").unwrap();
+        assert_eq!(info.running_tasks, 3);
+        assert_eq!(info.available_slots, 1);
+        assert_eq!(manager.executors().len(), 1);
+    }
+
+    #[test]
+    fn test_reserve_slots() {
+        let manager = ExecutorManager::new(Duration::from_secs(30));
+        manager.register(meta("a"));
+        let tasks = vec!["1".to_string(), "2".to_string()];
+        manager.reserve("a", &tasks);
+        assert_eq!(manager.get("a").unwrap().free_slots(), 2);
+
+        manager.release(&tasks[..1]);
+        manager.release(&tasks[..1]);
+        assert_eq!(manager.get("a").unwrap().free_slots(), 3);
+        // a registration starts from scratch, its tasks are lost
+        manager.register(meta("a"));
+        assert_eq!(manager.get("a").unwrap().free_slots(), 4);
+    }
+
+    #[test]
+    fn test_recover_executors() {
+        let state = SchedulerState::default();
+        let manager = ExecutorManager::with_state(Duration::from_millis(20), state.clone());
+        manager.register(meta("a"));
+        manager.register(meta("b"));
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(manager.heartbeat("b", 0, 4));
+        manager.remove_expired();
+
+        let manager = ExecutorManager::with_state(Duration::from_secs(30), state);
+        assert_eq!(manager.recover().unwrap(), 1);
+        assert_eq!(manager.get("b").unwrap().meta, meta("b"));
+        assert!(manager.is_accepted("b"));
+    }
+
+    #[test]
+    fn test_remove_expired() {
+        let manager = ExecutorManager::new(Duration::from_millis(20));
+        manager.register(meta("a"));
+        manager.register(meta("b"));
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(manager.heartbeat("b", 0, 4));
+
+        assert_eq!(manager.remove_expired(), vec!["a".to_string()]);
+        assert!(manager.get("a").is_none());
+        assert!(manager.get("b").is_some());
+        assert!(manager.is_dead("a"));
+
+        // a dead executor registers again cleanly
+        assert!(!manager.heartbeat("a", 0, 4));
+        assert!(manager.register(meta("a")));
+        assert!(!manager.is_dead("a"));
+        assert!(manager.heartbeat("a", 0, 4));
+        // so does a live executor that restarted
+        assert!(manager.register(meta("a")));
+        assert!(!manager.register(meta("c")));
+    }
+}
@@ -0,0 +1,786 @@
+//! Arrow flight sql service, running statements as jobs of the scheduler.
+//!
+//! `GetFlightInfo` of a statement submits a job and returns a ticket holding
+//! the job id, `DoGet` of the ticket waits for the job and streams its result.
+
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use arrow_flight::flight_service_server::FlightService;
+use arrow_flight::sql::server::FlightSqlService;
+use arrow_flight::sql::{
+    ActionClosePreparedStatementRequest, ActionCreatePreparedStatementRequest,
+    ActionCreatePreparedStatementResult, CommandGetCatalogs, CommandGetCrossReference,
+    CommandGetDbSchemas, CommandGetExportedKeys, CommandGetImportedKeys, CommandGetPrimaryKeys,
+    CommandGetSqlInfo, CommandGetTableTypes, CommandGetTables, CommandPreparedStatementQuery,
+    CommandPreparedStatementUpdate, CommandStatementQuery, CommandStatementUpdate, ProstMessageExt,
+    SqlInfo, TicketStatementQuery,
+};
+use arrow_flight::utils::flight_data_from_arrow_batch;
+use arrow_flight::{
+    Action, FlightData, FlightDescriptor, FlightEndpoint, FlightInfo, HandshakeRequest,
+    HandshakeResponse, IpcMessage, SchemaAsIpc, Ticket,
+};
+use common::error::RapidashError;
+use datafusion::arrow::array::{ArrayRef, BinaryArray, StringArray};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion::arrow::ipc::writer::IpcWriteOptions;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::datasource::TableType;
+use futures::{stream, Stream, StreamExt};
+use log::info;
+use prost::Message;
+use tonic::{Request, Response, Status, Streaming};
+use transmit::auth::principal;
+use transmit::error::to_status;
+use uuid::Uuid;
+
+use crate::job::{Job, JobManager, JobState};
+use crate::query::rebatch;
+
+type DoGetStream = <FlightSqlServer as FlightService>::DoGetStream;
+
+/// A prepared statement, only usable by the principal that created it
+struct PreparedStatement {
+    sql: String,
+    principal: Option<String>,
+    /// Last time it was created or used
+    used: Instant,
+}
+
+/// Flight sql service sharing the jobs and catalogs of `Query`
+#[derive(Clone)]
+pub struct FlightSqlServer {
+    jobs: JobManager,
+    /// Max rows of a streamed batch
+    batch_size: usize,
+    /// Prepared statements by handle
+    statements: Arc<Mutex<HashMap<String, PreparedStatement>>>,
+}
+
+impl FlightSqlServer {
+    pub fn new(jobs: JobManager, batch_size: usize) -> Self {
+        Self {
+            jobs,
+            // step_by panics on zero, so never slice by less than one row
+            batch_size: batch_size.max(1),
+            statements: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Submit a job for the sql, rejected when it goes over the quotas
+    async fn submit_job(&self, sql: String, principal: Option<String>) -> Result<String, Status> {
+        self.jobs
+            .submit(Job::new(sql, principal))
+            .await
+            .map_err(|e| to_status(&e))
+    }
+
+    /// Submit a job for the sql and describe where its result is fetched
+    async fn submit(
+        &self,
+        sql: String,
+        request: Request<FlightDescriptor>,
+    ) -> Result<FlightInfo, Status> {
+        if sql.trim().is_empty() {
+            return Err(Status::invalid_argument("Empty sql"));
+        }
+        let schema = self
+            .jobs
+            .plan_schema(&sql)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let id = self.submit_job(sql, principal(&request)).await?;
+        let descriptor = request.into_inner();
+        info!("Flight sql statement submitted as job {}", id);
+
+        let ticket = TicketStatementQuery {
+            statement_handle: id.into_bytes(),
+        };
+        flight_info(&schema, ticket.as_any().encode_to_vec(), descriptor)
+    }
+
+    /// Sql of a prepared statement of the principal, the statements of
+    /// other principals are not found
+    fn statement(&self, handle: &[u8], principal: &Option<String>) -> Result<String, Status> {
+        let handle = String::from_utf8_lossy(handle);
+        let mut statements = self.statements.lock().unwrap();
+        match statements.get_mut(handle.as_ref()) {
+            Some(statement) if &statement.principal == principal => {
+                statement.used = Instant::now();
+                Ok(statement.sql.clone())
+            }
+            _ => Err(Status::not_found(format!(
+                "Prepared statement {} not found",
+                handle
+            ))),
+        }
+    }
+
+    /// Remove the prepared statements unused for `retention`, returns how
+    /// many were removed
+    pub fn expire_statements(&self, retention: Duration) -> usize {
+        let mut statements = self.statements.lock().unwrap();
+        let before = statements.len();
+        statements.retain(|handle, statement| {
+            let expired = statement.used.elapsed() >= retention;
+            if expired {
+                info!("Prepared statement {} expired", handle);
+            }
+            !expired
+        });
+        before - statements.len()
+    }
+
+    /// Remove expired prepared statements in background every `interval`
+    pub fn start_statement_expiry(&self, interval: Duration, retention: Duration) {
+        let server = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                server.expire_statements(retention);
+            }
+        });
+    }
+
+    /// Run ddl as an update, it changes the catalog but no rows. Queries
+    /// return rows rather than an update count and there is no dml.
+    async fn update(&self, sql: String, principal: Option<String>) -> Result<i64, Status> {
+        if sql.trim().is_empty() {
+            return Err(Status::invalid_argument("Empty sql"));
+        }
+        let is_ddl = self
+            .jobs
+            .is_ddl(&sql)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        if !is_ddl {
+            return Err(Status::unimplemented(
+                "Update counts of queries, only ddl runs as an update",
+            ));
+        }
+        let id = self.submit_job(sql, principal.clone()).await?;
+        self.result(&id, &principal).await?;
+        Ok(0)
+    }
+
+    /// Wait for a job of the principal and take its result
+    async fn result(
+        &self,
+        id: &str,
+        principal: &Option<String>,
+    ) -> Result<(SchemaRef, Vec<RecordBatch>), Status> {
+        match self.jobs.get(id) {
+            Some(job) if &job.principal == principal => {}
+            Some(_) => {
+                return Err(Status::permission_denied(format!(
+                    "Job {} belongs to another user",
+                    id
+                )))
+            }
+            None => return Err(Status::not_found(format!("Job {} not found", id))),
+        }
+        let job = self
+            .jobs
+            .wait(id)
+            .await
+            .ok_or_else(|| Status::not_found(format!("Job {} not found", id)))?;
+        match job.state() {
+            JobState::Succeeded => {
+                let result = self.jobs.result(id).ok_or_else(|| {
+                    Status::failed_precondition(format!("Result of job {} expired", id))
+                })?;
+                Ok((result.schema, result.batches))
+            }
+            JobState::Cancelled => Err(Status::cancelled(RapidashError::Cancelled.to_string())),
+            _ => Err(Status::internal(format!(
+                "Job {} failed: {}",
+                job.id,
+                job.error.unwrap_or_default()
+            ))),
+        }
+    }
+}
+
+/// Flight info with a single endpoint on this server
+fn flight_info(
+    schema: &Schema,
+    ticket: Vec<u8>,
+    descriptor: FlightDescriptor,
+) -> Result<FlightInfo, Status> {
+    Ok(FlightInfo {
+        schema: encode_schema(schema)?,
+        flight_descriptor: Some(descriptor),
+        endpoint: vec![FlightEndpoint {
+            ticket: Some(Ticket { ticket }),
+            location: vec![],
+        }],
+        total_records: -1,
+        total_bytes: -1,
+    })
+}
+
+fn encode_schema(schema: &Schema) -> Result<Vec<u8>, Status> {
+    let IpcMessage(schema) = SchemaAsIpc::new(schema, &IpcWriteOptions::default())
+        .try_into()
+        .map_err(|e| Status::internal(format!("Failed to encode schema: {}", e)))?;
+    Ok(schema)
+}
+
+/// Stream the schema and then every batch with its dictionaries
+fn flight_data_stream(schema: SchemaRef, batches: Vec<RecordBatch>) -> DoGetStream {
+    let options = IpcWriteOptions::default();
+    let schema = FlightData::from(SchemaAsIpc::new(&schema, &options));
+    let batches = stream::iter(batches).flat_map(move |batch| {
+        let (dictionaries, data) = flight_data_from_arrow_batch(&batch, &options);
+        stream::iter(dictionaries.into_iter().chain(std::iter::once(data)))
+    });
+    Box::pin(stream::once(async { schema }).chain(batches).map(Ok))
+}
+
+fn single_batch(schema: SchemaRef, columns: Vec<ArrayRef>) -> Result<DoGetStream, Status> {
+    let batch = RecordBatch::try_new(schema.clone(), columns)
+        .map_err(|e| Status::internal(e.to_string()))?;
+    Ok(flight_data_stream(schema, vec![batch]))
+}
+
+/// Match a sql `LIKE` pattern, `%` is any string and `_` is any character
+fn like(pattern: &str, value: &str) -> bool {
+    fn matches(pattern: &[char], value: &[char]) -> bool {
+        match pattern.split_first() {
+            None => value.is_empty(),
+            Some(('%', rest)) => (0..=value.len()).any(|i| matches(rest, &value[i..])),
+            Some(('_', rest)) => !value.is_empty() && matches(rest, &value[1..]),
+            Some((c, rest)) => value.first() == Some(c) && matches(rest, &value[1..]),
+        }
+    }
+    let pattern: Vec<char> = pattern.chars().collect();
+    let value: Vec<char> = value.chars().collect();
+    matches(&pattern, &value)
+}
+
+/// A missing filter pattern matches everything
+fn filter(pattern: &Option<String>, value: &str) -> bool {
+    pattern.as_ref().is_none_or(|pattern| like(pattern, value))
+}
+
+fn table_type_name(table_type: TableType) -> &'static str {
+    match table_type {
+        TableType::Base => "TABLE",
+        TableType::View => "VIEW",
+        TableType::Temporary => "LOCAL TEMPORARY",
+    }
+}
+
+fn catalogs_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![Field::new(
+        "catalog_name",
+        DataType::Utf8,
+        false,
+    )]))
+}
+
+fn db_schemas_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("catalog_name", DataType::Utf8, true),
+        Field::new("db_schema_name", DataType::Utf8, false),
+    ]))
+}
+
+fn tables_schema(include_schema: bool) -> SchemaRef {
+    let mut fields = vec![
+        Field::new("catalog_name", DataType::Utf8, true),
+        Field::new("db_schema_name", DataType::Utf8, true),
+        Field::new("table_name", DataType::Utf8, false),
+        Field::new("table_type", DataType::Utf8, false),
+    ];
+    if include_schema {
+        fields.push(Field::new("table_schema", DataType::Binary, false));
+    }
+    Arc::new(Schema::new(fields))
+}
+
+fn table_types_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![Field::new(
+        "table_type",
+        DataType::Utf8,
+        false,
+    )]))
+}
+
+/// Ticket of a catalog command is the command itself
+fn command_info<T: ProstMessageExt>(
+    command: T,
+    schema: SchemaRef,
+    request: Request<FlightDescriptor>,
+) -> Result<Response<FlightInfo>, Status> {
+    flight_info(
+        &schema,
+        command.as_any().encode_to_vec(),
+        request.into_inner(),
+    )
+    .map(Response::new)
+}
+
+#[tonic::async_trait]
+impl FlightSqlService for FlightSqlServer {
+    type FlightService = FlightSqlServer;
+
+    async fn do_handshake(
+        &self,
+        _request: Request<Streaming<HandshakeRequest>>,
+    ) -> Result<
+        Response<Pin<Box<dyn Stream<Item = Result<HandshakeResponse, Status>> + Send>>>,
+        Status,
+    > {
+        let response = HandshakeResponse {
+            protocol_version: 0,
+            payload: vec![],
+        };
+        Ok(Response::new(Box::pin(stream::once(async {
+            Ok(response)
+        }))))
+    }
+
+    async fn get_flight_info_statement(
+        &self,
+        query: CommandStatementQuery,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        self.submit(query.query, request).await.map(Response::new)
+    }
+
+    async fn get_flight_info_prepared_statement(
+        &self,
+        query: CommandPreparedStatementQuery,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let sql = self.statement(&query.prepared_statement_handle, &principal(&request))?;
+        self.submit(sql, request).await.map(Response::new)
+    }
+
+    async fn get_flight_info_catalogs(
+        &self,
+        query: CommandGetCatalogs,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        command_info(query, catalogs_schema(), request)
+    }
+
+    async fn get_flight_info_schemas(
+        &self,
+        query: CommandGetDbSchemas,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        command_info(query, db_schemas_schema(), request)
+    }
+
+    async fn get_flight_info_tables(
+        &self,
+        query: CommandGetTables,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let schema = tables_schema(query.include_schema);
+        command_info(query, schema, request)
+    }
+
+    async fn get_flight_info_table_types(
+        &self,
+        query: CommandGetTableTypes,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        command_info(query, table_types_schema(), request)
+    }
+
+    async fn get_flight_info_sql_info(
+        &self,
+        _query: CommandGetSqlInfo,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        Err(Status::unimplemented("get_flight_info_sql_info"))
+    }
+
+    async fn get_flight_info_primary_keys(
+        &self,
+        _query: CommandGetPrimaryKeys,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        Err(Status::unimplemented("get_flight_info_primary_keys"))
+    }
+
+    async fn get_flight_info_exported_keys(
+        &self,
+        _query: CommandGetExportedKeys,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        Err(Status::unimplemented("get_flight_info_exported_keys"))
+    }
+
+    async fn get_flight_info_imported_keys(
+        &self,
+        _query: CommandGetImportedKeys,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        Err(Status::unimplemented("get_flight_info_imported_keys"))
+    }
+
+    async fn get_flight_info_cross_reference(
+        &self,
+        _query: CommandGetCrossReference,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        Err(Status::unimplemented("get_flight_info_cross_reference"))
+    }
+
+    async fn do_get_statement(
+        &self,
+        ticket: TicketStatementQuery,
+        request: Request<Ticket>,
+    ) -> Result<Response<DoGetStream>, Status> {
+        let id = String::from_utf8_lossy(&ticket.statement_handle).to_string();
+        let (schema, batches) = self.result(&id, &principal(&request)).await?;
+        let batches = rebatch(batches, self.batch_size);
+        Ok(Response::new(flight_data_stream(schema, batches)))
+    }
+
+    async fn do_get_prepared_statement(
+        &self,
+        _query: CommandPreparedStatementQuery,
+        _request: Request<Ticket>,
+    ) -> Result<Response<DoGetStream>, Status> {
+        // the flight info of a prepared statement hands out statement tickets
+        Err(Status::unimplemented("do_get_prepared_statement"))
+    }
+
+    async fn do_get_catalogs(
+        &self,
+        _query: CommandGetCatalogs,
+        _request: Request<Ticket>,
+    ) -> Result<Response<DoGetStream>, Status> {
+        let mut catalogs = self.jobs.context().catalog_names();
+        catalogs.sort();
+        single_batch(
+            catalogs_schema(),
+            vec![Arc::new(StringArray::from(catalogs))],
+        )
+        .map(Response::new)
+    }
+
+    async fn do_get_schemas(
+        &self,
+        query: CommandGetDbSchemas,
+        _request: Request<Ticket>,
+    ) -> Result<Response<DoGetStream>, Status> {
+        let ctx = self.jobs.context();
+        let mut rows = vec![];
+        for catalog_name in ctx.catalog_names() {
+            if matches!(&query.catalog, Some(catalog) if *catalog != catalog_name) {
+                continue;
+            }
+            let catalog = match ctx.catalog(&catalog_name) {
+                Some(catalog) => catalog,
+                None => continue,
+            };
+            for schema_name in catalog.schema_names() {
+                if !filter(&query.db_schema_filter_pattern, &schema_name) {
+                    continue;
+                }
+                rows.push((catalog_name.clone(), schema_name));
+            }
+        }
+        rows.sort();
+
+        let (catalogs, schemas): (Vec<_>, Vec<_>) = rows.into_iter().unzip();
+        single_batch(
+            db_schemas_schema(),
+            vec![
+                Arc::new(StringArray::from(catalogs)),
+                Arc::new(StringArray::from(schemas)),
+            ],
+        )
+        .map(Response::new)
+    }
+
+    async fn do_get_tables(
+        &self,
+        query: CommandGetTables,
+        _request: Request<Ticket>,
+    ) -> Result<Response<DoGetStream>, Status> {
+        let ctx = self.jobs.context();
+        let mut rows = vec![];
+        for catalog_name in ctx.catalog_names() {
+            if matches!(&query.catalog, Some(catalog) if *catalog != catalog_name) {
+                continue;
+            }
+            let catalog = match ctx.catalog(&catalog_name) {
+                Some(catalog) => catalog,
+                None => continue,
+            };
+            for schema_name in catalog.schema_names() {
+                if !filter(&query.db_schema_filter_pattern, &schema_name) {
+                    continue;
+                }
+                let schema = match catalog.schema(&schema_name) {
+                    Some(schema) => schema,
+                    None => continue,
+                };
+                for table_name in schema.table_names() {
+                    if !filter(&query.table_name_filter_pattern, &table_name) {
+                        continue;
+                    }
+                    let table = match schema.table(&table_name) {
+                        Some(table) => table,
+                        None => continue,
+                    };
+                    let table_type = table_type_name(table.table_type());
+                    if !query.table_types.is_empty()
+                        && !query.table_types.iter().any(|t| t == table_type)
+                    {
+                        continue;
+                    }
+                    rows.push((
+                        catalog_name.clone(),
+                        schema_name.clone(),
+                        table_name,
+                        table_type,
+                        table.schema(),
+                    ));
+                }
+            }
+        }
+        rows.sort_by(|a, b| (&a.0, &a.1, &a.2).cmp(&(&b.0, &b.1, &b.2)));
+
+        let mut columns: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from_iter_values(rows.iter().map(|r| &r.0))),
+            Arc::new(StringArray::from_iter_values(rows.iter().map(|r| &r.1))),
+            Arc::new(StringArray::from_iter_values(rows.iter().map(|r| &r.2))),
+            Arc::new(StringArray::from_iter_values(rows.iter().map(|r| r.3))),
+        ];
+        if query.include_schema {
+            let schemas = rows
+                .iter()
+                .map(|r| encode_schema(&r.4))
+                .collect::<Result<Vec<_>, Status>>()?;
+            columns.push(Arc::new(BinaryArray::from_iter_values(schemas)));
+        }
+        single_batch(tables_schema(query.include_schema), columns).map(Response::new)
+    }
+
+    async fn do_get_table_types(
+        &self,
+        _query: CommandGetTableTypes,
+        _request: Request<Ticket>,
+    ) -> Result<Response<DoGetStream>, Status> {
+        let types = [TableType::Base, TableType::Temporary, TableType::View]
+            .into_iter()
+            .map(table_type_name)
+            .collect::<Vec<_>>();
+        single_batch(
+            table_types_schema(),
+            vec![Arc::new(StringArray::from(types))],
+        )
+        .map(Response::new)
+    }
+
+    async fn do_get_sql_info(
+        &self,
+        _query: CommandGetSqlInfo,
+        _request: Request<Ticket>,
+    ) -> Result<Response<DoGetStream>, Status> {
+        Err(Status::unimplemented("do_get_sql_info"))
+    }
+
+    async fn do_get_primary_keys(
+        &self,
+        _query: CommandGetPrimaryKeys,
+        _request: Request<Ticket>,
+    ) -> Result<Response<DoGetStream>, Status> {
+        Err(Status::unimplemented("do_get_primary_keys"))
+    }
+
+    async fn do_get_exported_keys(
+        &self,
+        _query: CommandGetExportedKeys,
+        _request: Request<Ticket>,
+    ) -> Result<Response<DoGetStream>, Status> {
+        Err(Status::unimplemented("do_get_exported_keys"))
+    }
+
+    async fn do_get_imported_keys(
+        &self,
+        _query: CommandGetImportedKeys,
+        _request: Request<Ticket>,
+    ) -> Result<Response<DoGetStream>, Status> {
+        Err(Status::unimplemented("do_get_imported_keys"))
+    }
+
+    async fn do_get_cross_reference(
+        &self,
+        _query: CommandGetCrossReference,
+        _request: Request<Ticket>,
+    ) -> Result<Response<DoGetStream>, Status> {
+        Err(Status::unimplemented("do_get_cross_reference"))
+    }
+
+    async fn do_put_statement_update(
+        &self,
+        ticket: CommandStatementUpdate,
+        request: Request<Streaming<FlightData>>,
+    ) -> Result<i64, Status> {
+        self.update(ticket.query, principal(&request)).await
+    }
+
+    async fn do_put_prepared_statement_query(
+        &self,
+        _query: CommandPreparedStatementQuery,
+        _request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<<Self as FlightService>::DoPutStream>, Status> {
+        Err(Status::unimplemented("Prepared statement parameters"))
+    }
+
+    async fn do_put_prepared_statement_update(
+        &self,
+        query: CommandPreparedStatementUpdate,
+        request: Request<Streaming<FlightData>>,
+    ) -> Result<i64, Status> {
+        let principal = principal(&request);
+        let sql = self.statement(&query.prepared_statement_handle, &principal)?;
+        self.update(sql, principal).await
+    }
+
+    async fn do_action_create_prepared_statement(
+        &self,
+        query: ActionCreatePreparedStatementRequest,
+        request: Request<Action>,
+    ) -> Result<ActionCreatePreparedStatementResult, Status> {
+        let schema = self
+            .jobs
+            .plan_schema(&query.query)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let handle = Uuid::new_v4().to_string();
+        let statement = PreparedStatement {
+            sql: query.query,
+            principal: principal(&request),
+            used: Instant::now(),
+        };
+        self.statements
+            .lock()
+            .unwrap()
+            .insert(handle.clone(), statement);
+
+        Ok(ActionCreatePreparedStatementResult {
+            prepared_statement_handle: handle.into_bytes(),
+            dataset_schema: encode_schema(&schema)?,
+            // parameters are not supported yet
+            parameter_schema: vec![],
+        })
+    }
+
+    async fn do_action_close_prepared_statement(
+        &self,
+        query: ActionClosePreparedStatementRequest,
+        request: Request<Action>,
+    ) {
+        // a principal can't close the statements of others
+        let principal = principal(&request);
+        let handle = String::from_utf8_lossy(&query.prepared_statement_handle);
+        let mut statements = self.statements.lock().unwrap();
+        if matches!(statements.get(handle.as_ref()), Some(s) if s.principal == principal) {
+            statements.remove(handle.as_ref());
+        }
+    }
+
+    async fn register_sql_info(&self, _id: i32, _result: &SqlInfo) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use transmit::auth::Principal;
+
+    #[test]
+    fn test_like() {
+        assert!(like("%", ""));
+        assert!(like("pub%", "public"));
+        assert!(like("p_blic", "public"));
+        assert!(like("%lic", "public"));
+        assert!(!like("pub", "public"));
+        assert!(!like("_", ""));
+    }
+
+    #[tokio::test]
+    async fn test_prepared_statement_of_principal() {
+        let query = crate::query::Query::new(common::config::Config::new().unwrap()).unwrap();
+        let server = FlightSqlServer::new(query.jobs().clone(), 1024);
+        let request = |principal: &str| {
+            let mut request = Request::new(Action::default());
+            request
+                .extensions_mut()
+                .insert(Principal(principal.to_string()));
+            request
+        };
+        let create = ActionCreatePreparedStatementRequest {
+            query: "select 1".to_string(),
+        };
+        let handle = server
+            .do_action_create_prepared_statement(create, request("alice"))
+            .await
+            .unwrap()
+            .prepared_statement_handle;
+
+        let alice = Some("alice".to_string());
+        let bob = Some("bob".to_string());
+        assert_eq!(server.statement(&handle, &alice).unwrap(), "select 1");
+        let status = server.statement(&handle, &bob).unwrap_err();
+        assert_eq!(status.code(), tonic::Code::NotFound);
+
+        // bob can't close it either
+        let close = ActionClosePreparedStatementRequest {
+            prepared_statement_handle: handle.clone(),
+        };
+        server
+            .do_action_close_prepared_statement(close, request("bob"))
+            .await;
+        assert!(server.statement(&handle, &alice).is_ok());
+
+        assert_eq!(server.expire_statements(Duration::from_secs(60)), 0);
+        assert_eq!(server.expire_statements(Duration::ZERO), 1);
+        assert!(server.statement(&handle, &alice).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_result_of_principal() {
+        let query = crate::query::Query::new(common::config::Config::new().unwrap()).unwrap();
+        let server = FlightSqlServer::new(query.jobs().clone(), 1024);
+        let alice = Some("alice".to_string());
+        let id = server
+            .submit_job("create view v as select 1".to_string(), alice.clone())
+            .await
+            .unwrap();
+
+        let status = server
+            .result(&id, &Some("bob".to_string()))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+        server.result(&id, &alice).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_update() {
+        let query = crate::query::Query::new(common::config::Config::new().unwrap()).unwrap();
+        let server = FlightSqlServer::new(query.jobs().clone(), 1024);
+
+        let status = server
+            .update("select 1".to_string(), None)
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::Unimplemented);
+        let count = server
+            .update("create view v as select 1".to_string(), None)
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+}
@@ -0,0 +1,946 @@
+//! Distributed execution of the stages of a job.
+//!
+//! A stage runs once the stages it reads have written all their partitions.
+//! A failed task runs again until it has failed `rapidash.task.max.attempts`
+//! times. A task failing to fetch a shuffle partition (`FetchFailed`) did not
+//! fail by itself, its input was lost with the executor that wrote it: the
+//! outputs of the map stage on that executor are dropped, only the map tasks
+//! that wrote them run again, and the failed task runs once its input is
+//! complete again. A stage losing its input `rapidash.task.max.attempts`
+//! times fails the job.
+//!
+//! An executor marked dead loses the outputs it wrote and the tasks it ran,
+//! they are computed again on the other executors without counting as
+//! failures.
+//!
+//! With `rapidash.speculation` a task running much longer than the median of
+//! its stage gets a copy on another executor, once most of the stage has
+//! finished. The first copy to succeed computes the partition, the other one
+//! is cancelled.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use common::config::Config;
+use datafusion::error::{DataFusionError, Result};
+use log::{info, warn};
+use tokio::sync::Notify;
+use transmit::proto::{
+    task_status, FetchPartitionError, ShuffleLocation, TaskDefinition, TaskStatus,
+};
+
+use crate::job::ExecutionState;
+use crate::planner::QueryStage;
+use crate::policy::PendingTask;
+
+/// When slow tasks get a copy, from `rapidash.speculation.*`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Speculation {
+    /// Fraction of the tasks of a stage that finish before its slow tasks are copied
+    pub quantile: f64,
+    /// A task is slow once it runs longer than the median runtime of its
+    /// stage times the multiplier
+    pub multiplier: f64,
+}
+
+impl Speculation {
+    /// None when speculation is disabled
+    pub fn from_config(config: &Config) -> Option<Self> {
+        config.speculation().then(|| Self {
+            quantile: config.speculation_quantile() as f64 / 100.0,
+            multiplier: config.speculation_multiplier() as f64 / 100.0,
+        })
+    }
+}
+
+/// A copy of the task of a partition
+#[derive(Debug, Clone)]
+struct TaskAttempt {
+    task_id: String,
+    /// Executor running the copy and when it was launched, None while it
+    /// waits for an executor
+    launched: Option<(String, Instant)>,
+}
+
+impl TaskAttempt {
+    fn new(task_id: String) -> Self {
+        Self {
+            task_id,
+            launched: None,
+        }
+    }
+}
+
+/// Execution of one stage of a graph
+#[derive(Debug)]
+struct StageExecution {
+    stage: QueryStage,
+    /// Locations written by the task of every partition, None until it succeeds
+    outputs: Vec<Option<Vec<ShuffleLocation>>>,
+    /// Running copies of the task of a partition, more than one when speculated
+    running: HashMap<usize, Vec<TaskAttempt>>,
+    /// Tasks launched for every partition, which is the attempt of the next one
+    attempts: Vec<u32>,
+    /// Failures of the task of every partition, lost inputs excluded
+    failures: Vec<u32>,
+    /// Times the stage lost its input
+    retries: u32,
+    /// Runtimes of the succeeded tasks
+    runtimes: Vec<Duration>,
+}
+
+impl StageExecution {
+    fn new(stage: QueryStage) -> Self {
+        let partitions = stage.partition_count();
+        Self {
+            stage,
+            outputs: vec![None; partitions],
+            running: HashMap::new(),
+            attempts: vec![0; partitions],
+            failures: vec![0; partitions],
+            retries: 0,
+            runtimes: vec![],
+        }
+    }
+
+    fn is_complete(&self) -> bool {
+        self.outputs.iter().all(Option::is_some)
+    }
+
+    fn locations(&self) -> Vec<ShuffleLocation> {
+        self.outputs.iter().flatten().flatten().cloned().collect()
+    }
+
+    /// Whether every stage read by this one is complete
+    fn is_ready(&self, complete: &HashMap<usize, Vec<ShuffleLocation>>) -> bool {
+        self.stage
+            .inputs
+            .iter()
+            .all(|input| complete.contains_key(input))
+    }
+
+    /// Partitions to compute that have no running task
+    fn waiting(&self) -> Vec<(usize, u32)> {
+        (0..self.outputs.len())
+            .filter(|partition| {
+                self.outputs[*partition].is_none() && !self.running.contains_key(partition)
+            })
+            .map(|partition| (partition, self.attempts[partition]))
+            .collect()
+    }
+
+    fn attempt_mut(&mut self, partition: usize, task_id: &str) -> Option<&mut TaskAttempt> {
+        self.running
+            .get_mut(&partition)?
+            .iter_mut()
+            .find(|attempt| attempt.task_id == task_id)
+    }
+
+    /// Remove a finished copy of the task of a partition
+    fn finish(&mut self, partition: usize, task_id: &str) {
+        if let Some(copies) = self.running.get_mut(&partition) {
+            copies.retain(|attempt| attempt.task_id != task_id);
+            if copies.is_empty() {
+                self.running.remove(&partition);
+            }
+        }
+    }
+
+    /// Partitions whose only copy runs much longer than the median runtime,
+    /// with the executor running it
+    fn slow(&self, speculation: &Speculation, now: Instant) -> Vec<(usize, String)> {
+        let finished = self
+            .outputs
+            .iter()
+            .filter(|output| output.is_some())
+            .count();
+        if finished == 0 || (finished as f64) < speculation.quantile * self.outputs.len() as f64 {
+            return vec![];
+        }
+        let mut runtimes = self.runtimes.clone();
+        runtimes.sort_unstable();
+        let threshold = match runtimes.get(runtimes.len() / 2) {
+            Some(median) => median.mul_f64(speculation.multiplier),
+            None => return vec![],
+        };
+
+        let mut slow: Vec<(usize, String)> = self
+            .running
+            .iter()
+            .filter_map(|(partition, copies)| match copies.as_slice() {
+                [TaskAttempt {
+                    launched: Some((executor_id, start)),
+                    ..
+                }] if now.saturating_duration_since(*start) > threshold => {
+                    Some((*partition, executor_id.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+        slow.sort();
+        slow
+    }
+
+    /// Drop the outputs written by an executor, return their partitions
+    fn invalidate(&mut self, executor_id: &str) -> Vec<usize> {
+        let mut lost = vec![];
+        for (partition, output) in self.outputs.iter_mut().enumerate() {
+            let written = output.as_ref().is_some_and(|locations| {
+                locations
+                    .iter()
+                    .any(|location| location.executor_id == executor_id)
+            });
+            if written {
+                *output = None;
+                lost.push(partition);
+            }
+        }
+        lost
+    }
+}
+
+/// Stages of a job run by executors
+#[derive(Debug)]
+pub struct ExecutionGraph {
+    job_id: String,
+    stages: BTreeMap<usize, StageExecution>,
+    /// Stage writing the result of the job
+    final_stage: usize,
+    max_attempts: u32,
+    state: ExecutionState,
+    /// Why the graph failed
+    error: Option<String>,
+    /// Running tasks not needed anymore, to cancel
+    cancelled: Vec<String>,
+}
+
+impl ExecutionGraph {
+    /// Graph of the stages of a job, the last one writes the result
+    pub fn new(job_id: &str, stages: Vec<QueryStage>, max_attempts: usize) -> Result<Self> {
+        let final_stage = stages.last().map(|stage| stage.id).ok_or_else(|| {
+            DataFusionError::Internal(format!("Job {} has no stage to run", job_id))
+        })?;
+        Ok(Self {
+            job_id: job_id.to_string(),
+            stages: stages
+                .into_iter()
+                .map(|stage| (stage.id, StageExecution::new(stage)))
+                .collect(),
+            final_stage,
+            // a task always runs at least once
+            max_attempts: max_attempts.max(1) as u32,
+            state: ExecutionState::Running,
+            error: None,
+            cancelled: vec![],
+        })
+    }
+
+    pub fn job_id(&self) -> &str {
+        &self.job_id
+    }
+
+    pub fn state(&self) -> ExecutionState {
+        self.state
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Locations of the result, once every stage succeeded
+    pub fn result(&self) -> Option<Vec<ShuffleLocation>> {
+        (self.state == ExecutionState::Succeeded)
+            .then(|| self.stages[&self.final_stage].locations())
+    }
+
+    /// Running tasks to cancel since the last call
+    pub fn take_cancelled(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.cancelled)
+    }
+
+    /// Outputs of the complete stages by stage id
+    fn complete_locations(&self) -> HashMap<usize, Vec<ShuffleLocation>> {
+        self.stages
+            .iter()
+            .filter(|(_, execution)| execution.is_complete())
+            .map(|(id, execution)| (*id, execution.locations()))
+            .collect()
+    }
+
+    /// Tasks of the partitions to compute whose input is complete, they are
+    /// recorded as running
+    pub fn runnable_tasks(&mut self) -> Result<Vec<PendingTask>> {
+        if self.state != ExecutionState::Running {
+            return Ok(vec![]);
+        }
+        let complete = self.complete_locations();
+
+        let mut tasks = vec![];
+        for execution in self.stages.values_mut() {
+            let waiting = execution.waiting();
+            if !execution.is_ready(&complete) || waiting.is_empty() {
+                continue;
+            }
+            let stage_tasks = execution.stage.partition_tasks(&waiting, &complete)?;
+            for (task, (partition, _)) in stage_tasks.iter().zip(waiting) {
+                let attempt = TaskAttempt::new(task.definition.task_id.clone());
+                execution.running.insert(partition, vec![attempt]);
+                execution.attempts[partition] += 1;
+            }
+            tasks.extend(stage_tasks);
+        }
+        Ok(tasks)
+    }
+
+    /// Copies of the slow tasks, each excluded from the executor running
+    /// the original
+    pub fn speculative_tasks(
+        &mut self,
+        speculation: &Speculation,
+        now: Instant,
+    ) -> Result<Vec<PendingTask>> {
+        if self.state != ExecutionState::Running {
+            return Ok(vec![]);
+        }
+        let complete = self.complete_locations();
+
+        let mut tasks = vec![];
+        for execution in self.stages.values_mut() {
+            let slow = execution.slow(speculation, now);
+            // a stage whose input was lost waits for it to be computed again
+            if !execution.is_ready(&complete) || slow.is_empty() {
+                continue;
+            }
+            let partitions: Vec<(usize, u32)> = slow
+                .iter()
+                .map(|(partition, _)| (*partition, execution.attempts[*partition]))
+                .collect();
+            let copies = execution.stage.partition_tasks(&partitions, &complete)?;
+            for (mut copy, (partition, executor_id)) in copies.into_iter().zip(slow) {
+                info!(
+                    "Task of partition {} of stage {} of job {} is slow on executor {}, copied",
+                    partition, execution.stage.id, self.job_id, executor_id
+                );
+                copy.excluded = Some(executor_id);
+                let attempt = TaskAttempt::new(copy.definition.task_id.clone());
+                execution
+                    .running
+                    .entry(partition)
+                    .or_default()
+                    .push(attempt);
+                execution.attempts[partition] += 1;
+                tasks.push(copy);
+            }
+        }
+        Ok(tasks)
+    }
+
+    /// Record that a task was launched on an executor
+    pub fn launched(&mut self, executor_id: &str, task: &TaskDefinition) {
+        let (stage_id, partition) = match &task.partition {
+            Some(id) => (id.stage_id as usize, id.partition_id as usize),
+            None => return,
+        };
+        let attempt = self
+            .stages
+            .get_mut(&stage_id)
+            .and_then(|execution| execution.attempt_mut(partition, &task.task_id));
+        if let Some(attempt) = attempt {
+            attempt.launched = Some((executor_id.to_string(), Instant::now()));
+        }
+    }
+
+    /// Record the status of a task, statuses of older attempts and of
+    /// cancelled copies are ignored
+    pub fn update(&mut self, status: &TaskStatus) {
+        if self.state != ExecutionState::Running {
+            return;
+        }
+        let (stage_id, partition) = match &status.partition {
+            Some(id) => (id.stage_id as usize, id.partition_id as usize),
+            None => return,
+        };
+        let execution = match self.stages.get_mut(&stage_id) {
+            Some(execution) => execution,
+            None => {
+                warn!(
+                    "Status of unknown stage {} of job {}",
+                    stage_id, self.job_id
+                );
+                return;
+            }
+        };
+        if execution.attempt_mut(partition, &status.task_id).is_none() {
+            return;
+        }
+
+        match &status.status {
+            Some(task_status::Status::Successful(successful)) => {
+                // the first copy to succeed computes the partition
+                for copy in execution.running.remove(&partition).unwrap_or_default() {
+                    if copy.task_id != status.task_id {
+                        self.cancelled.push(copy.task_id);
+                    } else if let Some((_, start)) = copy.launched {
+                        execution.runtimes.push(start.elapsed());
+                    }
+                }
+                execution.outputs[partition] = Some(successful.locations.clone());
+                if stage_id == self.final_stage && execution.is_complete() {
+                    info!("Job {} ran all its stages", self.job_id);
+                    self.state = ExecutionState::Succeeded;
+                }
+            }
+            Some(task_status::Status::Failed(failed)) => {
+                execution.finish(partition, &status.task_id);
+                if let Some(fetch_failed) = &failed.fetch_failed {
+                    self.input_lost(stage_id, fetch_failed, &failed.error);
+                    return;
+                }
+                execution.failures[partition] += 1;
+                let failures = execution.failures[partition];
+                // another copy still running may succeed
+                if execution.running.contains_key(&partition) {
+                    warn!(
+                        "Task {} of job {} failed, another copy of partition {} of stage {} runs: {}",
+                        status.task_id, self.job_id, partition, stage_id, failed.error
+                    );
+                } else if failures >= self.max_attempts {
+                    self.fail(format!(
+                        "Task of partition {} of stage {} failed {} times: {}",
+                        partition, stage_id, failures, failed.error
+                    ));
+                } else {
+                    warn!(
+                        "Task {} of job {} failed, partition {} of stage {} runs again: {}",
+                        status.task_id, self.job_id, partition, stage_id, failed.error
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// A task of the stage could not fetch an output of a map stage, the
+    /// outputs of the map stage on that executor are computed again
+    fn input_lost(&mut self, stage_id: usize, fetch_failed: &FetchPartitionError, error: &str) {
+        let map_stage_id = fetch_failed.map_stage_id as usize;
+        let lost = match self.stages.get_mut(&map_stage_id) {
+            Some(map_stage) => map_stage.invalidate(&fetch_failed.executor_id),
+            None => vec![],
+        };
+        // the task read outputs already known lost, it runs again with the new ones
+        if lost.is_empty() {
+            return;
+        }
+        warn!(
+            "Job {} lost partitions {:?} of stage {} with executor {}, they are computed again",
+            self.job_id, lost, map_stage_id, fetch_failed.executor_id
+        );
+
+        // infallible because the status came from a known stage
+        let execution = self.stages.get_mut(&stage_id).unwrap();
+        execution.retries += 1;
+        let retries = execution.retries;
+        if retries >= self.max_attempts {
+            self.fail(format!(
+                "Stage {} lost its input {} times: {}",
+                stage_id, retries, error
+            ));
+        }
+    }
+
+    /// An executor died: the outputs it wrote that are still to be read are
+    /// computed again and its running tasks run again elsewhere
+    pub fn executor_lost(&mut self, executor_id: &str) {
+        if self.state != ExecutionState::Running {
+            return;
+        }
+        // stages are numbered after the stages they read, so the readers of a
+        // stage are visited before it and already lost their own outputs
+        let stage_ids: Vec<usize> = self.stages.keys().rev().copied().collect();
+        for stage_id in stage_ids {
+            let needed = stage_id == self.final_stage
+                || self.stages.values().any(|execution| {
+                    execution.stage.inputs.contains(&stage_id) && !execution.is_complete()
+                });
+            let execution = self.stages.get_mut(&stage_id).unwrap();
+            if needed {
+                let lost = execution.invalidate(executor_id);
+                if !lost.is_empty() {
+                    warn!(
+                        "Job {} lost partitions {:?} of stage {} with executor {}, they are computed again",
+                        self.job_id, lost, stage_id, executor_id
+                    );
+                }
+            }
+            execution.running.retain(|_, copies| {
+                copies.retain(
+                    |attempt| !matches!(&attempt.launched, Some((id, _)) if id == executor_id),
+                );
+                !copies.is_empty()
+            });
+        }
+    }
+
+    fn fail(&mut self, error: String) {
+        warn!("Execution of job {} failed: {}", self.job_id, error);
+        self.state = ExecutionState::Failed;
+        self.error = Some(error);
+        for execution in self.stages.values_mut() {
+            for (_, copies) in execution.running.drain() {
+                self.cancelled
+                    .extend(copies.into_iter().map(|attempt| attempt.task_id));
+            }
+        }
+    }
+}
+
+/// Tasks to run and to cancel after task statuses were recorded
+#[derive(Debug, Default)]
+pub struct GraphUpdate {
+    pub tasks: Vec<PendingTask>,
+    /// Running tasks not needed anymore, e.g. the slower copy of a speculated task
+    pub cancelled: Vec<String>,
+}
+
+/// Execution graphs of the jobs run by executors
+#[derive(Clone)]
+pub struct ExecutionManager {
+    graphs: Arc<Mutex<HashMap<String, ExecutionGraph>>>,
+    /// Notified every time a graph finishes or is removed
+    finished: Arc<Notify>,
+    max_attempts: usize,
+    /// None when slow tasks are not copied
+    speculation: Option<Speculation>,
+}
+
+impl Default for ExecutionManager {
+    fn default() -> Self {
+        // infallible because the default settings are valid
+        Self::from_config(&Config::new().unwrap())
+    }
+}
+
+impl ExecutionManager {
+    pub fn new(max_attempts: usize) -> Self {
+        Self {
+            graphs: Arc::new(Mutex::new(HashMap::new())),
+            finished: Arc::new(Notify::new()),
+            max_attempts,
+            speculation: None,
+        }
+    }
+
+    pub fn with_speculation(mut self, speculation: Speculation) -> Self {
+        self.speculation = Some(speculation);
+        self
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        let manager = Self::new(config.task_max_attempts());
+        match Speculation::from_config(config) {
+            Some(speculation) => manager.with_speculation(speculation),
+            None => manager,
+        }
+    }
+
+    /// Start running the stages of a job, return the tasks of the stages
+    /// that read no other stage
+    pub fn submit(&self, job_id: &str, stages: Vec<QueryStage>) -> Result<Vec<PendingTask>> {
+        let mut graph = ExecutionGraph::new(job_id, stages, self.max_attempts)?;
+        let tasks = graph.runnable_tasks()?;
+        self.graphs
+            .lock()
+            .unwrap()
+            .insert(job_id.to_string(), graph);
+        Ok(tasks)
+    }
+
+    /// Record that tasks were launched on an executor
+    pub fn launched(&self, executor_id: &str, tasks: &[TaskDefinition]) {
+        let mut graphs = self.graphs.lock().unwrap();
+        for task in tasks {
+            let job_id = task.partition.as_ref().map(|id| id.job_id.as_str());
+            if let Some(graph) = job_id.and_then(|job_id| graphs.get_mut(job_id)) {
+                graph.launched(executor_id, task);
+            }
+        }
+    }
+
+    /// Record the statuses reported by an executor, return the tasks that
+    /// can run next and the ones to cancel
+    pub fn update(&self, statuses: &[TaskStatus]) -> GraphUpdate {
+        let mut graphs = self.graphs.lock().unwrap();
+        let mut updated = HashSet::new();
+        for status in statuses {
+            let job_id = match &status.partition {
+                Some(id) => &id.job_id,
+                None => continue,
+            };
+            if let Some(graph) = graphs.get_mut(job_id) {
+                graph.update(status);
+                updated.insert(job_id.clone());
+            }
+        }
+
+        let mut update = GraphUpdate::default();
+        for job_id in updated {
+            // infallible because the graph was updated above
+            let graph = graphs.get_mut(&job_id).unwrap();
+            self.next_tasks(graph, &mut update);
+        }
+        update
+    }
+
+    /// Add the tasks that can run next and the ones to cancel to the update,
+    /// waiters learn when the graph finished
+    fn next_tasks(&self, graph: &mut ExecutionGraph, update: &mut GraphUpdate) {
+        match graph.runnable_tasks() {
+            Ok(runnable) => update.tasks.extend(runnable),
+            Err(e) => graph.fail(e.to_string()),
+        }
+        update.cancelled.extend(graph.take_cancelled());
+        if graph.state() != ExecutionState::Running {
+            self.finished.notify_waiters();
+        }
+    }
+
+    /// Forget an executor that died, return the tasks running again elsewhere
+    pub fn executor_lost(&self, executor_id: &str) -> GraphUpdate {
+        let mut graphs = self.graphs.lock().unwrap();
+        let mut update = GraphUpdate::default();
+        for graph in graphs.values_mut() {
+            graph.executor_lost(executor_id);
+            self.next_tasks(graph, &mut update);
+        }
+        update
+    }
+
+    /// Copies of the slow tasks of every job, none without speculation
+    pub fn speculative_tasks(&self) -> Vec<PendingTask> {
+        let speculation = match &self.speculation {
+            Some(speculation) => speculation,
+            None => return vec![],
+        };
+        let now = Instant::now();
+        let mut tasks = vec![];
+        for graph in self.graphs.lock().unwrap().values_mut() {
+            match graph.speculative_tasks(speculation, now) {
+                Ok(copies) => tasks.extend(copies),
+                Err(e) => warn!("Failed to copy slow tasks of job {}: {}", graph.job_id(), e),
+            }
+        }
+        tasks
+    }
+
+    pub fn state(&self, job_id: &str) -> Option<ExecutionState> {
+        self.graphs
+            .lock()
+            .unwrap()
+            .get(job_id)
+            .map(|graph| graph.state())
+    }
+
+    pub fn error(&self, job_id: &str) -> Option<String> {
+        let graphs = self.graphs.lock().unwrap();
+        graphs.get(job_id)?.error().map(str::to_string)
+    }
+
+    /// Locations of the result of a job whose stages all succeeded
+    pub fn result(&self, job_id: &str) -> Option<Vec<ShuffleLocation>> {
+        self.graphs.lock().unwrap().get(job_id)?.result()
+    }
+
+    /// Wait until the graph of a job finishes and return its state, None
+    /// once it has no graph, e.g. because the job was cancelled
+    pub async fn wait(&self, job_id: &str) -> Option<ExecutionState> {
+        loop {
+            // created before the check, so a graph finishing in between still wakes us up
+            let finished = self.finished.notified();
+            match self.state(job_id)? {
+                ExecutionState::Running => finished.await,
+                state => return Some(state),
+            }
+        }
+    }
+
+    /// Stop scheduling the tasks of a job, return false if it has no graph
+    pub fn remove(&self, job_id: &str) -> bool {
+        let removed = self.graphs.lock().unwrap().remove(job_id).is_some();
+        self.finished.notify_waiters();
+        removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use datafusion::prelude::{CsvReadOptions, SessionConfig, SessionContext};
+    use transmit::proto::{FailedTask, PartitionId, SuccessfulTask};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::planner::plan_job;
+
+    /// Stages of an aggregate: stage 0 scans one partition and writes 4
+    /// hash partitions, read by the 4 tasks of stage 1
+    async fn stages() -> Vec<QueryStage> {
+        plan("select a, sum(b) from t group by a").await
+    }
+
+    async fn plan(sql: &str) -> Vec<QueryStage> {
+        let dir = std::env::temp_dir().join(format!("rapidash-graph-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("t.csv");
+        fs::write(&path, "a,b\n1,2\n1,3\n2,4\n").unwrap();
+
+        let ctx = SessionContext::with_config(SessionConfig::new().with_target_partitions(4));
+        ctx.register_csv("t", path.to_str().unwrap(), CsvReadOptions::new())
+            .await
+            .unwrap();
+        plan_job(&ctx, "job", sql).await.unwrap()
+    }
+
+    fn succeeded(task: &PendingTask, executor_id: &str, output_partitions: u32) -> TaskStatus {
+        let id = task.definition.partition.clone().unwrap();
+        let locations = (0..output_partitions)
+            .map(|partition_id| ShuffleLocation {
+                partition: Some(PartitionId {
+                    partition_id,
+                    ..id.clone()
+                }),
+                executor_id: executor_id.to_string(),
+                map_partition_id: id.partition_id,
+                ..Default::default()
+            })
+            .collect();
+        status(
+            task,
+            task_status::Status::Successful(SuccessfulTask {
+                locations,
+                metrics: None,
+            }),
+        )
+    }
+
+    fn failed(task: &PendingTask, fetch_failed: Option<FetchPartitionError>) -> TaskStatus {
+        status(
+            task,
+            task_status::Status::Failed(FailedTask {
+                error: "boom".to_string(),
+                fetch_failed,
+            }),
+        )
+    }
+
+    fn status(task: &PendingTask, status: task_status::Status) -> TaskStatus {
+        TaskStatus {
+            task_id: task.definition.task_id.clone(),
+            partition: task.definition.partition.clone(),
+            attempt: task.definition.attempt,
+            executor_id: String::new(),
+            status: Some(status),
+        }
+    }
+
+    fn stage_of(task: &PendingTask) -> u32 {
+        task.definition.partition.as_ref().unwrap().stage_id
+    }
+
+    #[tokio::test]
+    async fn test_recompute_lost_input() {
+        let executions = ExecutionManager::new(4);
+        let tasks = executions.submit("job", stages().await).unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(stage_of(&tasks[0]), 0);
+
+        // the map output is on executor a, the final stage starts
+        let tasks = executions.update(&[succeeded(&tasks[0], "a", 4)]).tasks;
+        assert_eq!(tasks.len(), 4);
+        assert!(tasks.iter().all(|task| stage_of(task) == 1));
+
+        // executor a died: one reduce task fails fetching, the others succeed
+        let fetch_failed = FetchPartitionError {
+            executor_id: "a".to_string(),
+            map_stage_id: 0,
+            map_partition_id: 0,
+        };
+        let mut statuses = vec![failed(&tasks[0], Some(fetch_failed))];
+        statuses.extend(tasks[1..].iter().map(|task| succeeded(task, "b", 1)));
+        let retried = executions.update(&statuses).tasks;
+
+        // only the lost map task runs again, the reduce task waits for it
+        assert_eq!(retried.len(), 1);
+        assert_eq!(stage_of(&retried[0]), 0);
+        assert_eq!(retried[0].definition.attempt, 1);
+
+        let tasks = executions.update(&[succeeded(&retried[0], "b", 4)]).tasks;
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(stage_of(&tasks[0]), 1);
+        assert_eq!(tasks[0].definition.attempt, 1);
+        // the new attempt reads the output on executor b
+        assert_eq!(
+            tasks[0].locality.executors.keys().collect::<Vec<_>>(),
+            ["b"]
+        );
+
+        assert!(executions
+            .update(&[succeeded(&tasks[0], "b", 1)])
+            .tasks
+            .is_empty());
+        assert_eq!(executions.state("job"), Some(ExecutionState::Succeeded));
+        assert_eq!(executions.result("job").unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_max_attempts() {
+        let executions = ExecutionManager::new(2);
+        let tasks = executions.submit("job", stages().await).unwrap();
+
+        let retried = executions.update(&[failed(&tasks[0], None)]).tasks;
+        assert_eq!(retried.len(), 1);
+        // the status of the first attempt is stale now
+        assert!(executions
+            .update(&[succeeded(&tasks[0], "a", 4)])
+            .tasks
+            .is_empty());
+        assert_eq!(executions.state("job"), Some(ExecutionState::Running));
+
+        assert!(executions
+            .update(&[failed(&retried[0], None)])
+            .tasks
+            .is_empty());
+        assert_eq!(executions.state("job"), Some(ExecutionState::Failed));
+        assert_eq!(
+            executions.error("job").unwrap(),
+            "Task of partition 0 of stage 0 failed 2 times: boom"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_speculate_slow_task() {
+        let speculation = Speculation {
+            quantile: 0.75,
+            multiplier: 1.5,
+        };
+        let mut graph = ExecutionGraph::new("job", stages().await, 4).unwrap();
+        let tasks = graph.runnable_tasks().unwrap();
+        graph.launched("a", &tasks[0].definition);
+        graph.update(&succeeded(&tasks[0], "a", 4));
+
+        let tasks = graph.runnable_tasks().unwrap();
+        for (i, task) in tasks.iter().enumerate() {
+            graph.launched(if i == 3 { "b" } else { "a" }, &task.definition);
+        }
+        // nothing to copy before most of the stage finished
+        let later = Instant::now() + Duration::from_secs(1);
+        assert!(graph
+            .speculative_tasks(&speculation, later)
+            .unwrap()
+            .is_empty());
+        for task in &tasks[..3] {
+            graph.update(&succeeded(task, "a", 1));
+        }
+
+        // the last task runs much longer than the others, it is copied
+        // away from executor b
+        let copies = graph.speculative_tasks(&speculation, later).unwrap();
+        assert_eq!(copies.len(), 1);
+        assert_eq!(copies[0].excluded.as_deref(), Some("b"));
+        assert_eq!(
+            copies[0].definition.partition,
+            tasks[3].definition.partition
+        );
+        assert_eq!(copies[0].definition.attempt, 1);
+        // a task already copied is not copied again
+        assert!(graph
+            .speculative_tasks(&speculation, later)
+            .unwrap()
+            .is_empty());
+
+        // the copy wins, the original is cancelled and its late status ignored
+        graph.launched("a", &copies[0].definition);
+        graph.update(&succeeded(&copies[0], "a", 1));
+        assert_eq!(
+            graph.take_cancelled(),
+            vec![tasks[3].definition.task_id.clone()]
+        );
+        graph.update(&failed(&tasks[3], None));
+        assert_eq!(graph.state(), ExecutionState::Succeeded);
+        assert!(graph
+            .result()
+            .unwrap()
+            .iter()
+            .all(|location| location.executor_id == "a"));
+    }
+
+    #[tokio::test]
+    async fn test_executor_lost() {
+        let executions = ExecutionManager::new(1);
+        let tasks = executions.submit("job", stages().await).unwrap();
+        executions.launched("a", &[tasks[0].definition.clone()]);
+        let reduce = executions.update(&[succeeded(&tasks[0], "a", 4)]).tasks;
+        for (i, task) in reduce.iter().enumerate() {
+            let executor_id = if i == 0 { "a" } else { "b" };
+            executions.launched(executor_id, std::slice::from_ref(&task.definition));
+        }
+
+        // the map output and the reduce task on executor a run again,
+        // the reduce tasks on executor b keep running
+        let lost = executions.executor_lost("a").tasks;
+        assert_eq!(lost.len(), 1);
+        assert_eq!(stage_of(&lost[0]), 0);
+        assert_eq!(lost[0].definition.attempt, 1);
+        // the status of the task on the dead executor is stale
+        assert!(executions
+            .update(&[succeeded(&reduce[0], "a", 1)])
+            .tasks
+            .is_empty());
+
+        let tasks = executions.update(&[succeeded(&lost[0], "b", 4)]).tasks;
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(stage_of(&tasks[0]), 1);
+        assert_eq!(tasks[0].definition.attempt, 1);
+
+        // lost tasks do not count as failures, even with a single attempt
+        let statuses: Vec<TaskStatus> = tasks
+            .iter()
+            .chain(&reduce[1..])
+            .map(|task| succeeded(task, "b", 1))
+            .collect();
+        executions.update(&statuses);
+        assert_eq!(executions.state("job"), Some(ExecutionState::Succeeded));
+        assert!(executions
+            .result("job")
+            .unwrap()
+            .iter()
+            .all(|location| location.executor_id == "b"));
+    }
+
+    #[tokio::test]
+    async fn test_lost_output_already_read() {
+        // the inner aggregate reads stage 0, the outer one reads stage 1
+        let stages =
+            plan("select s, count(*) from (select a, sum(b) as s from t group by a) group by s")
+                .await;
+        assert_eq!(stages.len(), 3);
+        let executions = ExecutionManager::new(1);
+        let tasks = executions.submit("job", stages).unwrap();
+        executions.launched("a", &[tasks[0].definition.clone()]);
+        let inner = executions.update(&[succeeded(&tasks[0], "a", 4)]).tasks;
+        let statuses: Vec<TaskStatus> = inner.iter().map(|task| succeeded(task, "b", 4)).collect();
+        let outer = executions.update(&statuses).tasks;
+        assert!(outer.iter().all(|task| stage_of(task) == 2));
+        for task in &outer {
+            executions.launched("b", std::slice::from_ref(&task.definition));
+        }
+
+        // stage 1 is complete, so nothing reads the output of stage 0 on executor a anymore
+        assert!(executions.executor_lost("a").tasks.is_empty());
+        let statuses: Vec<TaskStatus> = outer.iter().map(|task| succeeded(task, "b", 1)).collect();
+        executions.update(&statuses);
+        assert_eq!(executions.state("job"), Some(ExecutionState::Succeeded));
+    }
+}
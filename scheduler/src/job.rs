@@ -0,0 +1,1137 @@
+//! Jobs submitted by clients and their execution.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use common::config::Config;
+use common::error::{RapidashError, Result};
+use datafusion::arrow::datatypes::{Schema, SchemaRef};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::datasource::MemTable;
+use datafusion::error::{DataFusionError, Result as DFResult};
+use datafusion::logical_expr::{
+    CreateExternalTable, CreateMemoryTable, CreateView, DropTable, LogicalPlan,
+};
+use datafusion::physical_plan::collect;
+use datafusion::prelude::SessionContext;
+use log::{error, info, warn};
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+use transmit::proto::{
+    ExecutionStateRecord, JobRecord, JobStateRecord, QueryState, ShuffleLocation, StageRecord,
+};
+use transmit::shuffle::ShuffleReaderExec;
+use uuid::Uuid;
+
+use crate::admission::{Admission, AdmissionControl, Quotas, ResourceEstimate};
+use crate::planner::{plan_stages, QueryStage};
+use crate::queue::JobQueue;
+use crate::session::Session;
+use crate::state::{optional, time, SchedulerState};
+
+/// State of a job, see `can_transition_to` for the valid transitions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobState {
+    Queued,
+    /// The sql is being planned into stages
+    Planning,
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+impl JobState {
+    /// A finished job never changes its state again
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            JobState::Succeeded | JobState::Failed | JobState::Cancelled
+        )
+    }
+
+    pub fn can_transition_to(&self, next: JobState) -> bool {
+        use JobState::*;
+        matches!(
+            (self, next),
+            (Queued, Planning)
+                | (Planning, Running)
+                | (Running, Succeeded)
+                | (Queued | Planning | Running, Failed | Cancelled)
+        )
+    }
+}
+
+impl From<JobState> for QueryState {
+    fn from(state: JobState) -> Self {
+        match state {
+            JobState::Queued => QueryState::Queued,
+            // clients only tell queued jobs from the ones being worked on
+            JobState::Planning | JobState::Running => QueryState::Running,
+            JobState::Succeeded => QueryState::Succeeded,
+            JobState::Failed => QueryState::Failed,
+            JobState::Cancelled => QueryState::Cancelled,
+        }
+    }
+}
+
+impl From<JobState> for JobStateRecord {
+    fn from(state: JobState) -> Self {
+        match state {
+            JobState::Queued => JobStateRecord::Queued,
+            JobState::Planning => JobStateRecord::Planning,
+            JobState::Running => JobStateRecord::Running,
+            JobState::Succeeded => JobStateRecord::Succeeded,
+            JobState::Failed => JobStateRecord::Failed,
+            JobState::Cancelled => JobStateRecord::Cancelled,
+        }
+    }
+}
+
+impl From<JobStateRecord> for JobState {
+    fn from(state: JobStateRecord) -> Self {
+        match state {
+            JobStateRecord::Queued => JobState::Queued,
+            JobStateRecord::Planning => JobState::Planning,
+            JobStateRecord::Running => JobState::Running,
+            JobStateRecord::Succeeded => JobState::Succeeded,
+            JobStateRecord::Failed => JobState::Failed,
+            JobStateRecord::Cancelled => JobState::Cancelled,
+        }
+    }
+}
+
+/// State of a stage of a job, or of the task computing one partition of a stage
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionState {
+    Pending,
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+impl ExecutionState {
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            ExecutionState::Succeeded | ExecutionState::Failed | ExecutionState::Cancelled
+        )
+    }
+
+    pub fn can_transition_to(&self, next: ExecutionState) -> bool {
+        use ExecutionState::*;
+        matches!(
+            (self, next),
+            (Pending, Running) | (Running, Succeeded) | (Pending | Running, Failed | Cancelled)
+        )
+    }
+}
+
+impl From<ExecutionState> for ExecutionStateRecord {
+    fn from(state: ExecutionState) -> Self {
+        match state {
+            ExecutionState::Pending => ExecutionStateRecord::Pending,
+            ExecutionState::Running => ExecutionStateRecord::Running,
+            ExecutionState::Succeeded => ExecutionStateRecord::Succeeded,
+            ExecutionState::Failed => ExecutionStateRecord::Failed,
+            ExecutionState::Cancelled => ExecutionStateRecord::Cancelled,
+        }
+    }
+}
+
+impl From<ExecutionStateRecord> for ExecutionState {
+    fn from(state: ExecutionStateRecord) -> Self {
+        match state {
+            ExecutionStateRecord::Pending => ExecutionState::Pending,
+            ExecutionStateRecord::Running => ExecutionState::Running,
+            ExecutionStateRecord::Succeeded => ExecutionState::Succeeded,
+            ExecutionStateRecord::Failed => ExecutionState::Failed,
+            ExecutionStateRecord::Cancelled => ExecutionState::Cancelled,
+        }
+    }
+}
+
+/// A stage of a job, with a task per partition
+#[derive(Debug, Clone)]
+pub struct Stage {
+    pub id: usize,
+    pub state: ExecutionState,
+    /// State of the task of every partition
+    pub tasks: Vec<ExecutionState>,
+}
+
+/// A query submitted by a client
+#[derive(Debug, Clone)]
+pub struct Job {
+    /// Uuid for the job
+    pub id: String,
+    /// Name of the job from `rapidash.job.name`
+    pub name: Option<String>,
+    /// Session the job was submitted in, None for flight sql statements
+    pub session_id: Option<String>,
+    /// Sql text of the query
+    pub sql: String,
+    /// Authenticated user that submitted the job, None without authentication
+    pub principal: Option<String>,
+    /// Queued jobs of higher priority start first, from `rapidash.job.priority`
+    pub priority: usize,
+    /// Weight of the fair share of the job, from `rapidash.job.weight`
+    pub weight: usize,
+    /// Resources checked against the quotas before the job starts
+    pub estimate: ResourceEstimate,
+    /// Settings of the session, None for jobs run with the scheduler settings
+    pub config: Option<Config>,
+    /// Only changed through `transition`
+    state: JobState,
+    pub submit_time: DateTime<Utc>,
+    /// Time planning started
+    pub start_time: Option<DateTime<Utc>>,
+    /// Time the job finished
+    pub end_time: Option<DateTime<Utc>>,
+    pub stages: Vec<Stage>,
+    /// Error message when the job failed
+    pub error: Option<String>,
+}
+
+impl Job {
+    pub fn new(sql: String, principal: Option<String>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name: None,
+            session_id: None,
+            sql,
+            principal,
+            priority: 0,
+            weight: 1,
+            estimate: ResourceEstimate::default(),
+            config: None,
+            state: JobState::Queued,
+            submit_time: Utc::now(),
+            start_time: None,
+            end_time: None,
+            stages: vec![],
+            error: None,
+        }
+    }
+
+    /// Job submitted in the session, named by its settings
+    pub fn with_session(mut self, session: &Session) -> Self {
+        self.session_id = Some(session.id.clone());
+        self.name = session.config.job_name();
+        self.priority = session.config.job_priority();
+        self.weight = session.config.job_weight();
+        self.config = Some(session.config.clone());
+        self
+    }
+
+    pub fn state(&self) -> JobState {
+        self.state
+    }
+
+    /// Move the job to the next state, unfinished stages and tasks of a
+    /// finished job are cancelled
+    pub fn transition(&mut self, next: JobState) -> Result<()> {
+        if !self.state.can_transition_to(next) {
+            return Err(RapidashError::Internal(format!(
+                "Job {} can't go from {:?} to {:?}",
+                self.id, self.state, next
+            )));
+        }
+
+        self.state = next;
+        if next == JobState::Planning {
+            self.start_time = Some(Utc::now());
+        }
+        if next.is_finished() {
+            self.end_time = Some(Utc::now());
+            for stage in &mut self.stages {
+                for state in stage.tasks.iter_mut().chain([&mut stage.state]) {
+                    if !state.is_finished() {
+                        *state = ExecutionState::Cancelled;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Add a pending stage of the partitions, return its id
+    pub fn add_stage(&mut self, partitions: usize) -> usize {
+        let id = self.stages.len();
+        self.stages.push(Stage {
+            id,
+            state: ExecutionState::Pending,
+            tasks: vec![ExecutionState::Pending; partitions],
+        });
+        id
+    }
+
+    pub fn transition_stage(&mut self, stage_id: usize, next: ExecutionState) -> Result<()> {
+        let job_id = self.id.clone();
+        let stage = self.stage_mut(stage_id)?;
+        if !stage.state.can_transition_to(next) {
+            return Err(RapidashError::Internal(format!(
+                "Stage {} of job {} can't go from {:?} to {:?}",
+                stage_id, job_id, stage.state, next
+            )));
+        }
+        stage.state = next;
+        Ok(())
+    }
+
+    /// Move the task of a partition to the next state, the stage starts with
+    /// its first task and succeeds with its last one
+    pub fn transition_task(
+        &mut self,
+        stage_id: usize,
+        partition: usize,
+        next: ExecutionState,
+    ) -> Result<()> {
+        let job_id = self.id.clone();
+        let stage = self.stage_mut(stage_id)?;
+        let task = stage.tasks.get_mut(partition).ok_or_else(|| {
+            RapidashError::Internal(format!(
+                "Stage {} of job {} has no partition {}",
+                stage_id, job_id, partition
+            ))
+        })?;
+        if !task.can_transition_to(next) {
+            return Err(RapidashError::Internal(format!(
+                "Task {} of stage {} of job {} can't go from {:?} to {:?}",
+                partition, stage_id, job_id, task, next
+            )));
+        }
+        *task = next;
+
+        let stage_state = stage.state;
+        let all_succeeded = stage
+            .tasks
+            .iter()
+            .all(|task| *task == ExecutionState::Succeeded);
+        match stage_state {
+            ExecutionState::Pending if next == ExecutionState::Running => {
+                self.transition_stage(stage_id, ExecutionState::Running)
+            }
+            ExecutionState::Running if all_succeeded => {
+                self.transition_stage(stage_id, ExecutionState::Succeeded)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Finished fraction of the job, from 0 to 1, counting succeeded tasks
+    pub fn progress(&self) -> f32 {
+        if self.state == JobState::Succeeded {
+            return 1.0;
+        }
+        let tasks = self.stages.iter().flat_map(|stage| &stage.tasks);
+        let (total, succeeded) = tasks.fold((0, 0), |(total, succeeded), task| {
+            (
+                total + 1,
+                succeeded + (*task == ExecutionState::Succeeded) as usize,
+            )
+        });
+        if total == 0 {
+            0.0
+        } else {
+            succeeded as f32 / total as f32
+        }
+    }
+
+    /// Record of the job kept by the state store, its result is only kept
+    /// in memory
+    pub fn to_record(&self) -> JobRecord {
+        let millis = |time: Option<DateTime<Utc>>| time.map_or(0, |time| time.timestamp_millis());
+        let stages = self
+            .stages
+            .iter()
+            .map(|stage| StageRecord {
+                id: stage.id as u32,
+                state: ExecutionStateRecord::from(stage.state) as i32,
+                tasks: stage
+                    .tasks
+                    .iter()
+                    .map(|task| ExecutionStateRecord::from(*task) as i32)
+                    .collect(),
+            })
+            .collect();
+
+        JobRecord {
+            id: self.id.clone(),
+            name: self.name.clone().unwrap_or_default(),
+            session_id: self.session_id.clone().unwrap_or_default(),
+            sql: self.sql.clone(),
+            principal: self.principal.clone().unwrap_or_default(),
+            priority: self.priority as u32,
+            weight: self.weight as u32,
+            estimated_memory: self.estimate.memory as u64,
+            estimated_tasks: self.estimate.tasks as u32,
+            settings: self
+                .config
+                .as_ref()
+                .map(|config| config.settings().clone())
+                .unwrap_or_default(),
+            state: JobStateRecord::from(self.state) as i32,
+            submit_time: self.submit_time.timestamp_millis(),
+            start_time: millis(self.start_time),
+            end_time: millis(self.end_time),
+            stages,
+            error: self.error.clone().unwrap_or_default(),
+        }
+    }
+
+    pub fn from_record(record: JobRecord) -> Result<Self> {
+        let invalid = |what: &str| {
+            RapidashError::Internal(format!("Invalid {} in record of job {}", what, record.id))
+        };
+        let execution_state = |state: i32| {
+            ExecutionStateRecord::from_i32(state)
+                .map(ExecutionState::from)
+                .ok_or_else(|| invalid("stage state"))
+        };
+        let state = JobStateRecord::from_i32(record.state)
+            .map(JobState::from)
+            .ok_or_else(|| invalid("state"))?;
+        let stages = record
+            .stages
+            .iter()
+            .map(|stage| {
+                Ok(Stage {
+                    id: stage.id as usize,
+                    state: execution_state(stage.state)?,
+                    tasks: stage
+                        .tasks
+                        .iter()
+                        .map(|task| execution_state(*task))
+                        .collect::<Result<_>>()?,
+                })
+            })
+            .collect::<Result<_>>()?;
+        // jobs of a session keep its settings, even when they are all defaults
+        let config = match record.session_id.is_empty() {
+            true => None,
+            false => Some(Config::with_settings(record.settings)?),
+        };
+
+        Ok(Self {
+            id: record.id,
+            name: optional(record.name),
+            session_id: optional(record.session_id),
+            sql: record.sql,
+            principal: optional(record.principal),
+            priority: record.priority as usize,
+            weight: record.weight as usize,
+            estimate: ResourceEstimate {
+                memory: record.estimated_memory as usize,
+                tasks: record.estimated_tasks as usize,
+            },
+            config,
+            state,
+            submit_time: time(record.submit_time).unwrap_or_else(Utc::now),
+            start_time: time(record.start_time),
+            end_time: time(record.end_time),
+            stages,
+            error: optional(record.error),
+        })
+    }
+
+    fn stage_mut(&mut self, stage_id: usize) -> Result<&mut Stage> {
+        let job_id = &self.id;
+        self.stages.get_mut(stage_id).ok_or_else(|| {
+            RapidashError::Internal(format!("Job {} has no stage {}", job_id, stage_id))
+        })
+    }
+}
+
+/// Runs the stages of jobs, e.g. on the executors of a cluster
+#[tonic::async_trait]
+pub trait StageRunner: Send + Sync {
+    /// Run the stages of a job, the last one writes the result. Return the
+    /// locations of the result once every stage succeeded.
+    async fn run_stages(
+        &self,
+        job_id: &str,
+        stages: Vec<QueryStage>,
+    ) -> Result<Vec<ShuffleLocation>>;
+}
+
+/// Result of a succeeded job, kept in memory for its client to fetch
+#[derive(Debug, Clone)]
+pub struct JobResult {
+    pub schema: SchemaRef,
+    pub batches: Vec<RecordBatch>,
+    /// Time the job succeeded
+    time: Instant,
+}
+
+/// Keep track of all jobs and run them
+#[derive(Clone)]
+pub struct JobManager {
+    jobs: Arc<RwLock<HashMap<String, Job>>>,
+    /// Handles of running jobs, used to abort them
+    tasks: Arc<Mutex<HashMap<String, JoinHandle<()>>>>,
+    /// Shared context, so tables created by one job are visible to later jobs
+    ctx: SessionContext,
+    /// Notified every time a job finishes
+    finished: Arc<Notify>,
+    /// Every change of a job, and the ddl of the catalog, is written there
+    state: SchedulerState,
+    /// Decides which queued job starts when a slot frees
+    queue: JobQueue,
+    /// Keeps the running jobs within the resource quotas
+    admission: AdmissionControl,
+    /// Runs the stages of the queries, None when jobs can only change the catalog
+    runner: Option<Arc<dyn StageRunner>>,
+    /// Results of succeeded jobs, until they expire
+    results: Arc<Mutex<HashMap<String, JobResult>>>,
+}
+
+impl Default for JobManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JobManager {
+    pub fn new() -> Self {
+        Self::with_state(SchedulerState::default())
+    }
+
+    pub fn with_state(state: SchedulerState) -> Self {
+        Self {
+            jobs: Arc::new(RwLock::new(HashMap::new())),
+            tasks: Arc::new(Mutex::new(HashMap::new())),
+            ctx: SessionContext::new(),
+            finished: Arc::new(Notify::new()),
+            state,
+            queue: JobQueue::default(),
+            admission: AdmissionControl::default(),
+            runner: None,
+            results: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Jobs run in the context, e.g. with the TLS used to fetch their result
+    pub fn with_context(mut self, ctx: SessionContext) -> Self {
+        self.ctx = ctx;
+        self
+    }
+
+    pub fn with_queue(mut self, queue: JobQueue) -> Self {
+        self.queue = queue;
+        self
+    }
+
+    pub fn with_admission(mut self, admission: AdmissionControl) -> Self {
+        self.admission = admission;
+        self
+    }
+
+    pub fn with_runner(mut self, runner: Arc<dyn StageRunner>) -> Self {
+        self.runner = Some(runner);
+        self
+    }
+
+    pub fn quotas(&self) -> Quotas {
+        self.admission.quotas()
+    }
+
+    /// Change the quotas, queued jobs that fit the new ones start
+    pub fn set_quotas(&self, quotas: Quotas) {
+        self.admission.set_quotas(quotas);
+        self.dispatch();
+    }
+
+    /// Session context the jobs run in, with the catalogs of all created tables
+    pub fn context(&self) -> &SessionContext {
+        &self.ctx
+    }
+
+    /// Schema of the result of the sql, only planned and not executed
+    pub fn plan_schema(&self, sql: &str) -> DFResult<SchemaRef> {
+        let plan = self.ctx.create_logical_plan(sql)?;
+        Ok(Arc::new(plan.schema().as_ref().into()))
+    }
+
+    /// Whether the sql only changes the catalog, so it returns no rows
+    pub fn is_ddl(&self, sql: &str) -> DFResult<bool> {
+        Ok(matches!(
+            self.ctx.create_logical_plan(sql)?,
+            LogicalPlan::CreateMemoryTable(_)
+                | LogicalPlan::CreateExternalTable(_)
+                | LogicalPlan::CreateView(_)
+                | LogicalPlan::DropTable(_)
+                | LogicalPlan::DropView(_)
+                | LogicalPlan::SetVariable(_)
+                | LogicalPlan::CreateCatalogSchema(_)
+                | LogicalPlan::CreateCatalog(_)
+        ))
+    }
+
+    /// Resources the sql is estimated to use, planned but not executed
+    pub async fn estimate(&self, sql: &str) -> DFResult<ResourceEstimate> {
+        let plan = match self.ctx.create_logical_plan(sql)? {
+            // the query of a create table as select runs, other ddl only changes the catalog
+            LogicalPlan::CreateMemoryTable(CreateMemoryTable { input, .. }) => {
+                input.as_ref().clone()
+            }
+            LogicalPlan::CreateExternalTable(_)
+            | LogicalPlan::CreateView(_)
+            | LogicalPlan::DropTable(_)
+            | LogicalPlan::DropView(_)
+            | LogicalPlan::SetVariable(_)
+            | LogicalPlan::CreateCatalogSchema(_)
+            | LogicalPlan::CreateCatalog(_) => return Ok(ResourceEstimate::default()),
+            plan => plan,
+        };
+        // the job id of the stages doesn't matter, they are never run
+        let stages = plan_stages(&self.ctx, "", &plan).await?;
+        Ok(ResourceEstimate::of_stages(&stages))
+    }
+
+    /// Recover the catalog and the jobs of the state after a restart, return
+    /// the number of recovered jobs. Queued jobs run again, jobs that were
+    /// planning or running fail because their partitions were computed by
+    /// the previous process.
+    pub async fn recover(&self) -> Result<usize> {
+        self.recover_catalog().await?;
+
+        let mut jobs = self.state.jobs()?;
+        let recovered = jobs.len();
+        jobs.sort_by_key(|job| job.submit_time);
+        for mut job in jobs {
+            match job.state() {
+                JobState::Queued => {
+                    info!("Job {} queued again", job.id);
+                    self.jobs.write().unwrap().insert(job.id.clone(), job);
+                }
+                JobState::Planning | JobState::Running => {
+                    job.transition(JobState::Failed)?;
+                    job.error = Some("Scheduler restarted while the job was running".to_string());
+                    warn!("Job {} failed by the restart", job.id);
+                    self.state.save_job(&job)?;
+                    self.jobs.write().unwrap().insert(job.id.clone(), job);
+                }
+                _ => {
+                    self.jobs.write().unwrap().insert(job.id.clone(), job);
+                }
+            }
+        }
+        self.dispatch();
+        Ok(recovered)
+    }
+
+    /// Run the ddl of the catalog again. Tables may depend on each other, so
+    /// failed statements are retried as long as others succeed.
+    async fn recover_catalog(&self) -> Result<()> {
+        let mut pending: Vec<(String, String)> = self.state.tables()?.into_iter().collect();
+        loop {
+            let mut failed = vec![];
+            for (name, sql) in pending.iter().cloned() {
+                if let Err(e) = self.run_ddl(&sql).await {
+                    failed.push((name, sql, e));
+                }
+            }
+            if failed.is_empty() || failed.len() == pending.len() {
+                for (name, _, e) in &failed {
+                    warn!("Table {} could not be recovered: {}", name, e);
+                }
+                return Ok(());
+            }
+            pending = failed
+                .into_iter()
+                .map(|(name, sql, _)| (name, sql))
+                .collect();
+        }
+    }
+
+    async fn run_ddl(&self, sql: &str) -> DFResult<()> {
+        self.ctx.sql(sql).await?.collect().await?;
+        Ok(())
+    }
+
+    /// Queue a job, it starts running when the queue gives it a slot and
+    /// it fits the quotas, return the job id. Jobs over the quotas fail
+    /// with `ResourcesExhausted`.
+    pub async fn submit(&self, mut job: Job) -> Result<String> {
+        job.estimate = match self.estimate(&job.sql).await {
+            Ok(estimate) => estimate,
+            // the job fails with the same error once it runs
+            Err(e) => {
+                warn!("Failed to estimate job {}: {}", job.id, e);
+                ResourceEstimate::default()
+            }
+        };
+        {
+            let tasks = self.tasks.lock().unwrap();
+            let jobs = self.jobs.read().unwrap();
+            let running: Vec<&Job> = tasks.keys().filter_map(|id| jobs.get(id)).collect();
+            if let Err(e) = self.admission.admit(&job, &running) {
+                warn!("Job {} rejected: {}", job.id, e);
+                return Err(e);
+            }
+        }
+
+        // audit trail of who ran what
+        info!(
+            "Job {} submitted by {} with priority {}: {}",
+            job.id,
+            job.principal.as_deref().unwrap_or("anonymous"),
+            job.priority,
+            job.sql
+        );
+        let id = job.id.clone();
+        if let Err(e) = self.state.save_job(&job) {
+            warn!("Failed to save job {}: {}", id, e);
+        }
+        self.jobs.write().unwrap().insert(id.clone(), job);
+        self.dispatch();
+        Ok(id)
+    }
+
+    /// Start queued jobs as long as the queue has free slots and they fit the quotas
+    fn dispatch(&self) {
+        // hold the lock while spawning, so a job can't remove its handle before it is added
+        let mut tasks = self.tasks.lock().unwrap();
+        loop {
+            let mut rejected = vec![];
+            let next = {
+                let jobs = self.jobs.read().unwrap();
+                let (running, queued): (Vec<&Job>, Vec<&Job>) = jobs
+                    .values()
+                    .filter(|job| tasks.contains_key(&job.id) || job.state() == JobState::Queued)
+                    .partition(|job| tasks.contains_key(&job.id));
+                let admitted: Vec<&Job> = queued
+                    .into_iter()
+                    .filter(|job| match self.admission.check(job, &running) {
+                        Admission::Admit => true,
+                        Admission::Wait(_) => false,
+                        Admission::Reject(reason) => {
+                            rejected.push((job.id.clone(), reason));
+                            false
+                        }
+                    })
+                    .collect();
+                self.queue
+                    .next(&admitted, &running, Utc::now())
+                    .map(|job| (job.id.clone(), job.sql.clone()))
+            };
+            // lowered quotas may leave queued jobs that never fit
+            for (id, reason) in rejected {
+                self.reject(&id, reason);
+            }
+            let (id, sql) = match next {
+                Some(next) => next,
+                None => return,
+            };
+
+            let manager = self.clone();
+            let job_id = id.clone();
+            let handle = tokio::spawn(async move { manager.run(job_id, sql).await });
+            tasks.insert(id, handle);
+        }
+    }
+
+    /// Fail a queued job over the quotas
+    fn reject(&self, id: &str, reason: String) {
+        let rejected = self.update(id, |job| {
+            if job.transition(JobState::Failed).is_err() {
+                return false;
+            }
+            job.error = Some(RapidashError::ResourcesExhausted(reason).to_string());
+            true
+        });
+        if rejected == Some(true) {
+            warn!("Queued job {} rejected by the quotas", id);
+            self.finished.notify_waiters();
+        }
+    }
+
+    /// Cancel a job that has not finished yet, return false if it had already finished
+    pub fn cancel(&self, id: &str) -> Option<bool> {
+        let cancelled = self.update(id, |job| {
+            if job.transition(JobState::Cancelled).is_err() {
+                return false;
+            }
+            job.error = Some(RapidashError::Cancelled.to_string());
+            true
+        })?;
+        if !cancelled {
+            return Some(false);
+        }
+
+        // dropping the execution future stops every partition stream of the job
+        if let Some(handle) = self.tasks.lock().unwrap().remove(id) {
+            handle.abort();
+        }
+        info!("Job {} cancelled", id);
+        self.finished.notify_waiters();
+        self.dispatch();
+
+        Some(true)
+    }
+
+    /// Snapshot of a job
+    pub fn get(&self, id: &str) -> Option<Job> {
+        self.jobs.read().unwrap().get(id).cloned()
+    }
+
+    /// Result of a succeeded job, None once it expired
+    pub fn result(&self, id: &str) -> Option<JobResult> {
+        self.results.lock().unwrap().get(id).cloned()
+    }
+
+    /// Remove the results kept for longer than the retention, return their number
+    pub fn expire_results(&self, retention: Duration) -> usize {
+        let mut results = self.results.lock().unwrap();
+        let before = results.len();
+        results.retain(|id, result| {
+            let expired = result.time.elapsed() >= retention;
+            if expired {
+                info!("Result of job {} expired", id);
+            }
+            !expired
+        });
+        before - results.len()
+    }
+
+    /// Remove expired results in background every `interval`
+    pub fn start_result_expiry(&self, interval: Duration, retention: Duration) {
+        let jobs = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                jobs.expire_results(retention);
+            }
+        });
+    }
+
+    /// Wait until the job finishes and return its snapshot
+    pub async fn wait(&self, id: &str) -> Option<Job> {
+        loop {
+            // created before the check, so a job finishing in between still wakes us up
+            let finished = self.finished.notified();
+            let job = self.get(id)?;
+            if job.state().is_finished() {
+                return Some(job);
+            }
+            finished.await;
+        }
+    }
+
+    async fn run(&self, id: String, sql: String) {
+        // the job may have been cancelled while queued
+        if !self.transition(&id, JobState::Planning) {
+            return;
+        }
+        info!("Job {} planning", id);
+        // planned before running, a dropped table can't be planned anymore
+        let catalog_change = self.catalog_change(&sql);
+
+        match self.execute(&id, &sql).await {
+            Ok((schema, batches)) => {
+                // kept before the transition, so a client seeing the job
+                // succeed finds its result
+                let result = JobResult {
+                    schema,
+                    batches,
+                    time: Instant::now(),
+                };
+                self.results.lock().unwrap().insert(id.clone(), result);
+                if self.transition(&id, JobState::Succeeded) {
+                    info!("Job {} succeeded", id);
+                    if let Some((name, created)) = catalog_change {
+                        self.save_table(&name, &sql, created);
+                    }
+                } else {
+                    self.results.lock().unwrap().remove(&id);
+                }
+            }
+            Err(e) => {
+                if self.transition(&id, JobState::Failed) {
+                    error!("Job {} failed: {}", id, e);
+                    self.update(&id, |job| job.error = Some(e.to_string()));
+                }
+            }
+        }
+        self.tasks.lock().unwrap().remove(&id);
+        self.finished.notify_waiters();
+        self.dispatch();
+    }
+
+    /// Run the stages of a query and read its result. Ddl only changes the
+    /// catalog of the scheduler, except the query of a create table as
+    /// select which runs on the executors as well.
+    async fn execute(&self, id: &str, sql: &str) -> DFResult<(SchemaRef, Vec<RecordBatch>)> {
+        match self.ctx.create_logical_plan(sql)? {
+            LogicalPlan::CreateMemoryTable(CreateMemoryTable {
+                name,
+                input,
+                if_not_exists,
+                or_replace,
+            }) => {
+                let exists = self.ctx.table_exist(name.as_str())?;
+                if exists && if_not_exists {
+                    self.transition(id, JobState::Running);
+                    return Ok((Arc::new(Schema::empty()), vec![]));
+                }
+                if exists && !or_replace {
+                    return Err(DataFusionError::Execution(format!(
+                        "Table '{}' already exists",
+                        name
+                    )));
+                }
+                let (schema, batches) = self.run_stages(id, &input).await?;
+                let table = MemTable::try_new(schema, vec![batches])?;
+                self.ctx.deregister_table(name.as_str())?;
+                self.ctx.register_table(name.as_str(), Arc::new(table))?;
+                Ok((Arc::new(Schema::empty()), vec![]))
+            }
+            LogicalPlan::CreateExternalTable(_)
+            | LogicalPlan::CreateView(_)
+            | LogicalPlan::DropTable(_)
+            | LogicalPlan::DropView(_)
+            | LogicalPlan::SetVariable(_)
+            | LogicalPlan::CreateCatalogSchema(_)
+            | LogicalPlan::CreateCatalog(_) => {
+                self.transition(id, JobState::Running);
+                self.run_ddl(sql).await?;
+                Ok((Arc::new(Schema::empty()), vec![]))
+            }
+            plan => self.run_stages(id, &plan).await,
+        }
+    }
+
+    /// Run the stages of the plan on the runner and read the result the
+    /// last one wrote
+    async fn run_stages(
+        &self,
+        id: &str,
+        plan: &LogicalPlan,
+    ) -> DFResult<(SchemaRef, Vec<RecordBatch>)> {
+        let runner = self.runner.as_ref().ok_or_else(|| {
+            DataFusionError::Execution("The scheduler has no executors to run jobs".to_string())
+        })?;
+        let stages = plan_stages(&self.ctx, id, plan).await?;
+        // a plan has at least the stage writing its result
+        let schema = stages.last().unwrap().output_schema();
+        self.update(id, |job| -> Result<()> {
+            job.transition(JobState::Running)?;
+            for stage in &stages {
+                job.add_stage(stage.partition_count());
+            }
+            Ok(())
+        })
+        .transpose()
+        .map_err(|e| DataFusionError::Execution(e.to_string()))?;
+        info!("Job {} running {} stages", id, stages.len());
+
+        let mut locations = runner
+            .run_stages(id, stages)
+            .await
+            .map_err(|e| DataFusionError::Execution(e.to_string()))?;
+        // read in partition order, e.g. of a sorted result
+        locations.sort_by_key(|location| {
+            let partition = location.partition.as_ref().map(|p| p.partition_id);
+            (partition, location.map_partition_id)
+        });
+        let reader = ShuffleReaderExec::new(vec![locations], schema.clone());
+        let batches = collect(Arc::new(reader), self.ctx.task_ctx()).await?;
+        Ok((schema, batches))
+    }
+
+    /// Table of the catalog the ddl creates or drops, the flag is true when
+    /// it creates it
+    fn catalog_change(&self, sql: &str) -> Option<(String, bool)> {
+        match self.ctx.create_logical_plan(sql).ok()? {
+            LogicalPlan::CreateExternalTable(CreateExternalTable { name, .. })
+            | LogicalPlan::CreateMemoryTable(CreateMemoryTable { name, .. })
+            | LogicalPlan::CreateView(CreateView { name, .. }) => Some((name, true)),
+            LogicalPlan::DropTable(DropTable { name, .. }) => Some((name, false)),
+            _ => None,
+        }
+    }
+
+    fn save_table(&self, name: &str, sql: &str, created: bool) {
+        let result = if created {
+            self.state.save_table(name, sql)
+        } else {
+            self.state.remove_table(name)
+        };
+        if let Err(e) = result {
+            warn!("Failed to save table {}: {}", name, e);
+        }
+    }
+
+    /// Change a job and write it to the state
+    fn update<R, F: FnOnce(&mut Job) -> R>(&self, id: &str, f: F) -> Option<R> {
+        let mut jobs = self.jobs.write().unwrap();
+        let job = jobs.get_mut(id)?;
+        let result = f(job);
+        // written under the lock, so the state sees the changes in order
+        if let Err(e) = self.state.save_job(job) {
+            warn!("Failed to save job {}: {}", id, e);
+        }
+        Some(result)
+    }
+
+    /// Move the job to the next state, return false if the transition is not
+    /// valid, e.g. because the job was cancelled in the meantime
+    fn transition(&self, id: &str, next: JobState) -> bool {
+        matches!(self.update(id, |job| job.transition(next)), Some(Ok(())))
+    }
+
+    /// Move a task of a job to the next state as the runner reports it.
+    /// Tasks of a cancelled job were cancelled with it, and tasks run again,
+    /// e.g. after a failure, were already counted, so their late transitions
+    /// are ignored.
+    pub fn transition_task(
+        &self,
+        id: &str,
+        stage_id: usize,
+        partition: usize,
+        next: ExecutionState,
+    ) {
+        self.update(id, |job| job.transition_task(stage_id, partition, next));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use common::config::{Config, JOB_NAME};
+
+    use super::*;
+    use crate::query::Query;
+    use crate::session::SessionManager;
+    use crate::testing::start_executor;
+
+    #[test]
+    fn test_job_transitions() {
+        let mut job = Job::new("select 1".to_string(), None);
+        assert!(job.transition(JobState::Running).is_err());
+        job.transition(JobState::Planning).unwrap();
+        assert!(job.start_time.is_some());
+        job.transition(JobState::Running).unwrap();
+        job.transition(JobState::Succeeded).unwrap();
+        assert!(job.end_time.is_some());
+
+        // a finished job never changes its state again
+        assert!(job.transition(JobState::Cancelled).is_err());
+        assert_eq!(job.state(), JobState::Succeeded);
+    }
+
+    #[test]
+    fn test_task_transitions() {
+        let mut job = Job::new("select 1".to_string(), None);
+        let stage = job.add_stage(2);
+        assert!(job
+            .transition_task(stage, 0, ExecutionState::Succeeded)
+            .is_err());
+
+        job.transition_task(stage, 0, ExecutionState::Running)
+            .unwrap();
+        assert_eq!(job.stages[stage].state, ExecutionState::Running);
+        job.transition_task(stage, 0, ExecutionState::Succeeded)
+            .unwrap();
+        assert_eq!(job.progress(), 0.5);
+        job.transition_task(stage, 1, ExecutionState::Running)
+            .unwrap();
+        job.transition_task(stage, 1, ExecutionState::Succeeded)
+            .unwrap();
+        assert_eq!(job.stages[stage].state, ExecutionState::Succeeded);
+        assert!(job
+            .transition_task(stage, 2, ExecutionState::Running)
+            .is_err());
+    }
+
+    #[test]
+    fn test_cancel_cascades() {
+        let mut job = Job::new("select 1".to_string(), None);
+        job.transition(JobState::Planning).unwrap();
+        let stage = job.add_stage(2);
+        job.transition_task(stage, 0, ExecutionState::Running)
+            .unwrap();
+        job.transition(JobState::Cancelled).unwrap();
+
+        assert_eq!(job.stages[stage].state, ExecutionState::Cancelled);
+        assert_eq!(job.stages[stage].tasks, vec![ExecutionState::Cancelled; 2]);
+        // the late report of a cancelled task is rejected
+        assert!(job
+            .transition_task(stage, 0, ExecutionState::Succeeded)
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_recover_jobs() {
+        let state = SchedulerState::default();
+        let jobs = JobManager::with_state(state.clone());
+        let done = jobs
+            .submit(Job::new("create view v as select 1 as a".to_string(), None))
+            .await
+            .unwrap();
+        jobs.wait(&done).await.unwrap();
+
+        // jobs caught by the restart, one still queued and one running
+        let queued = Job::new("select a from v".to_string(), None);
+        let mut running = Job::new("select 2".to_string(), None);
+        running.transition(JobState::Planning).unwrap();
+        running.transition(JobState::Running).unwrap();
+        running.add_stage(1);
+        state.save_job(&queued).unwrap();
+        state.save_job(&running).unwrap();
+
+        let query = Query::with_state(Config::new().unwrap(), state).unwrap();
+        start_executor(&query).await;
+        let jobs = query.jobs();
+        assert_eq!(jobs.recover().await.unwrap(), 3);
+        assert_eq!(jobs.get(&done).unwrap().state(), JobState::Succeeded);
+
+        // the queued job runs again against the recovered catalog
+        let job = jobs.wait(&queued.id).await.unwrap();
+        assert_eq!(job.state(), JobState::Succeeded, "{:?}", job.error);
+        assert_eq!(jobs.result(&job.id).unwrap().batches[0].num_rows(), 1);
+        // results are not kept across restarts
+        assert!(jobs.result(&done).is_none());
+
+        let job = jobs.get(&running.id).unwrap();
+        assert_eq!(job.state(), JobState::Failed);
+        assert_eq!(job.stages[0].tasks, vec![ExecutionState::Cancelled]);
+    }
+
+    #[tokio::test]
+    async fn test_run_job() {
+        let sessions = SessionManager::new();
+        let settings = HashMap::from([(JOB_NAME.to_string(), "daily".to_string())]);
+        let session_id = sessions.create(settings, None).unwrap();
+        let session = sessions.get(&session_id).unwrap();
+
+        let query = Query::new(Config::new().unwrap()).unwrap();
+        start_executor(&query).await;
+        let jobs = query.jobs();
+        let id = jobs
+            .submit(Job::new("select 1".to_string(), None).with_session(&session))
+            .await
+            .unwrap();
+        let job = jobs.wait(&id).await.unwrap();
+
+        assert_eq!(job.state(), JobState::Succeeded, "{:?}", job.error);
+        assert_eq!(jobs.result(&id).unwrap().batches[0].num_rows(), 1);
+        assert_eq!(jobs.expire_results(Duration::from_secs(60)), 0);
+        assert_eq!(jobs.expire_results(Duration::ZERO), 1);
+        assert!(jobs.result(&id).is_none());
+        assert_eq!(job.name.as_deref(), Some("daily"));
+        assert_eq!(job.session_id, Some(session_id));
+        assert!(job.submit_time <= job.start_time.unwrap());
+        assert!(job.start_time <= job.end_time);
+        assert!(job
+            .stages
+            .iter()
+            .all(|stage| stage.state == ExecutionState::Succeeded));
+    }
+}
@@ -1,5 +1,20 @@
 //! Library
 
+// rpc handlers and their helpers return tonic's `Status` as error
+#![allow(clippy::result_large_err)]
+
+pub mod admission;
+pub mod cluster;
+pub mod flight_sql;
+pub mod graph;
+pub mod job;
+pub mod planner;
+pub mod policy;
 pub mod prelude;
 pub mod query;
-pub mod rpc;
+pub mod queue;
+pub mod session;
+pub mod state;
+pub mod task;
+#[cfg(test)]
+mod testing;
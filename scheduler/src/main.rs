@@ -1,5 +1,85 @@
 //! Server for the client to connect to.
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use arrow_flight::flight_service_server::FlightServiceServer;
+use common::config::Config;
+use log::info;
+use scheduler::flight_sql::FlightSqlServer;
+use scheduler::query::Query;
+use scheduler::state::SchedulerState;
+use tonic::service::interceptor::InterceptedService;
+use tonic::transport::Server;
+use transmit::auth::AuthInterceptor;
+use transmit::compressed;
+use transmit::compression::CompressionConfig;
+use transmit::proto::scheduler_proto_server::SchedulerProtoServer;
+use transmit::tls::TlsConfig;
+
+/// Default listen address, same as the cli defaults
+const DEFAULT_ADDR: &str = "127.0.0.1:51008";
+/// Interval of the placement stats in the log
+const STATS_INTERVAL: Duration = Duration::from_secs(60);
+/// Interval of the checks for expired job results and prepared statements
+const RESULT_EXPIRY_INTERVAL: Duration = Duration::from_secs(10);
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // `RUST_LOG` overrides the info level
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    // listen address can be overridden by the first argument, `key=value`
+    // arguments are configuration settings
+    let (args, settings) = Config::split_args(std::env::args().skip(1));
+    let addr: SocketAddr = args
+        .first()
+        .map(String::as_str)
+        .unwrap_or(DEFAULT_ADDR)
+        .parse()?;
+
+    let config = Config::with_settings(settings)?;
+    let tls = TlsConfig::from_config(&config);
+    let compression = CompressionConfig::from_config(&config);
+    let auth = AuthInterceptor::from_config(&config)?;
+    let interval = Duration::from_secs(config.executor_heartbeat_interval() as u64);
+    let batch_size = config.default_batch_size();
+    let retention = Duration::from_secs(config.result_retention() as u64);
+    let statement_retention = Duration::from_secs(config.statement_retention() as u64);
+    let speculation = config
+        .speculation()
+        .then(|| Duration::from_millis(config.speculation_interval() as u64));
+    let state = SchedulerState::from_config(&config)?;
+    let query = Query::with_state(config, state)?;
+    query.recover().await?;
+    query.start_expiry_check(interval);
+    query.scheduler().start_stats_report(STATS_INTERVAL);
+    query
+        .jobs()
+        .start_result_expiry(RESULT_EXPIRY_INTERVAL, retention);
+    if let Some(interval) = speculation {
+        query.start_speculation(interval);
+    }
+    // flight sql clients share the jobs and tables of the query service
+    let flight_sql = FlightSqlServer::new(query.jobs().clone(), batch_size);
+    flight_sql.start_statement_expiry(RESULT_EXPIRY_INTERVAL, statement_retention);
+
+    info!("Rapidash scheduler listening on {}", addr);
+    let mut server = Server::builder();
+    // flight sql shares the port, so it is served over TLS too
+    if let Some(tls) = tls.server_tls()? {
+        server = server.tls_config(tls)?;
+    }
+    server
+        .add_service(InterceptedService::new(
+            compressed!(SchedulerProtoServer::new(query), compression),
+            auth.clone(),
+        ))
+        .add_service(InterceptedService::new(
+            compressed!(FlightServiceServer::new(flight_sql), compression),
+            auth,
+        ))
+        .serve(addr)
+        .await?;
+
     Ok(())
 }
@@ -0,0 +1,524 @@
+//! Split the physical plan of a job into stages run by executors.
+//!
+//! The plan is cut at every exchange: a hash `RepartitionExec` becomes a
+//! stage writing hash partitioned shuffle files, and the input of a
+//! `CoalescePartitionsExec` or `SortPreservingMergeExec` becomes a stage
+//! whose partitions are merged by the next stage. Until its input stages
+//! finish, a stage reads them through `UnresolvedShuffleExec` placeholders.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::error::{DataFusionError, Result};
+use datafusion::execution::context::TaskContext;
+use datafusion::logical_expr::LogicalPlan;
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use datafusion::physical_plan::file_format::{AvroExec, CsvExec, FileScanConfig, ParquetExec};
+use datafusion::physical_plan::repartition::RepartitionExec;
+use datafusion::physical_plan::sorts::sort_preserving_merge::SortPreservingMergeExec;
+use datafusion::physical_plan::{
+    with_new_children_if_necessary, DisplayFormatType, ExecutionPlan, Partitioning,
+    SendableRecordBatchStream, Statistics,
+};
+use datafusion::prelude::SessionContext;
+use transmit::codec::encode_plan;
+use transmit::proto::{PartitionId, ShuffleLocation, TaskDefinition};
+use transmit::shuffle::{ShuffleReaderExec, ShuffleWriterExec};
+use url::Url;
+use uuid::Uuid;
+
+use crate::policy::{PendingTask, TaskLocality};
+
+/// Placeholder of the shuffle reader of a stage whose output is not written yet
+#[derive(Debug, Clone)]
+pub struct UnresolvedShuffleExec {
+    /// Stage the reader reads
+    pub stage_id: usize,
+    schema: SchemaRef,
+    /// Number of output partitions of the stage
+    partition_count: usize,
+}
+
+impl UnresolvedShuffleExec {
+    pub fn new(stage_id: usize, schema: SchemaRef, partition_count: usize) -> Self {
+        Self {
+            stage_id,
+            schema,
+            partition_count,
+        }
+    }
+}
+
+impl ExecutionPlan for UnresolvedShuffleExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(self.partition_count)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(self)
+    }
+
+    fn execute(
+        &self,
+        _partition: usize,
+        _context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        Err(DataFusionError::Internal(format!(
+            "Shuffle reader of stage {} is not resolved",
+            self.stage_id
+        )))
+    }
+
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default => write!(
+                f,
+                "UnresolvedShuffleExec: stage={}, partitions={}",
+                self.stage_id, self.partition_count
+            ),
+        }
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+/// A stage of a job, a plan fragment ending in a shuffle writer
+#[derive(Debug, Clone)]
+pub struct QueryStage {
+    pub id: usize,
+    /// Writer of the stage output, its input may read other stages through
+    /// unresolved shuffle readers
+    pub plan: Arc<ShuffleWriterExec>,
+    /// Ids of the stages this stage reads
+    pub inputs: Vec<usize>,
+}
+
+impl QueryStage {
+    /// Number of tasks of the stage, one per input partition of the writer
+    pub fn partition_count(&self) -> usize {
+        self.plan.children()[0]
+            .output_partitioning()
+            .partition_count()
+    }
+
+    /// Number of partitions the stages reading this one see
+    pub fn output_partition_count(&self) -> usize {
+        self.plan.output_partition_count()
+    }
+
+    /// Schema of the written batches, the writer itself returns a summary
+    /// of the written files
+    pub fn output_schema(&self) -> SchemaRef {
+        self.plan.children()[0].schema()
+    }
+
+    /// Plan of the stage reading the written locations of its input stages
+    pub fn resolve(
+        &self,
+        locations: &HashMap<usize, Vec<ShuffleLocation>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        resolve(self.plan.clone(), locations)
+    }
+
+    /// Tasks computing every partition of the stage, for the given attempt
+    pub fn tasks(
+        &self,
+        locations: &HashMap<usize, Vec<ShuffleLocation>>,
+        attempt: u32,
+    ) -> Result<Vec<TaskDefinition>> {
+        let plan = encode_plan(&self.resolve(locations)?)?;
+        Ok((0..self.partition_count())
+            .map(|partition| self.definition(partition, attempt, plan.clone()))
+            .collect())
+    }
+
+    /// Tasks of some partitions of the stage, each with its own attempt,
+    /// with the locality of their input
+    pub fn partition_tasks(
+        &self,
+        partitions: &[(usize, u32)],
+        locations: &HashMap<usize, Vec<ShuffleLocation>>,
+    ) -> Result<Vec<PendingTask>> {
+        let plan = encode_plan(&self.resolve(locations)?)?;
+        Ok(partitions
+            .iter()
+            .map(|&(partition, attempt)| PendingTask {
+                definition: self.definition(partition, attempt, plan.clone()),
+                locality: self.locality(partition, locations),
+                excluded: None,
+            })
+            .collect())
+    }
+
+    fn definition(&self, partition: usize, attempt: u32, plan: Vec<u8>) -> TaskDefinition {
+        TaskDefinition {
+            task_id: Uuid::new_v4().to_string(),
+            partition: Some(PartitionId {
+                job_id: self.plan.job_id().to_string(),
+                stage_id: self.id as u32,
+                partition_id: partition as u32,
+            }),
+            attempt,
+            plan,
+        }
+    }
+
+    /// Where the input of the task of a partition is: the executors that
+    /// wrote the shuffle partition it reads, and the hosts of the files it scans
+    pub fn locality(
+        &self,
+        partition: usize,
+        locations: &HashMap<usize, Vec<ShuffleLocation>>,
+    ) -> TaskLocality {
+        let mut locality = TaskLocality::default();
+        for input in &self.inputs {
+            let read = locations
+                .get(input)
+                .into_iter()
+                .flatten()
+                .filter(|location| {
+                    location
+                        .partition
+                        .as_ref()
+                        .is_some_and(|id| id.partition_id as usize == partition)
+                });
+            for location in read {
+                let bytes = location.stats.as_ref().map_or(0, |stats| stats.num_bytes);
+                // an empty partition still makes its executor a good place
+                *locality
+                    .executors
+                    .entry(location.executor_id.clone())
+                    .or_default() += bytes.max(1);
+            }
+        }
+        add_file_hosts(self.plan.children()[0].clone(), partition, &mut locality);
+        locality
+    }
+
+    /// Tasks of every partition of the stage with the locality of their input
+    pub fn pending_tasks(
+        &self,
+        locations: &HashMap<usize, Vec<ShuffleLocation>>,
+        attempt: u32,
+    ) -> Result<Vec<PendingTask>> {
+        let partitions: Vec<(usize, u32)> = (0..self.partition_count())
+            .map(|partition| (partition, attempt))
+            .collect();
+        self.partition_tasks(&partitions, locations)
+    }
+}
+
+/// Add the hosts of the files the partition of the plan scans, files of
+/// object stores without host are local to no executor
+fn add_file_hosts(plan: Arc<dyn ExecutionPlan>, partition: usize, locality: &mut TaskLocality) {
+    if let Some(config) = file_scan_config(plan.as_ref()) {
+        add_scan_hosts(config, partition, locality);
+    }
+    for child in plan.children() {
+        add_file_hosts(child, partition, locality);
+    }
+}
+
+/// Files read by a parquet, csv or avro scan
+pub(crate) fn file_scan_config(plan: &dyn ExecutionPlan) -> Option<&FileScanConfig> {
+    let any = plan.as_any();
+    if let Some(exec) = any.downcast_ref::<ParquetExec>() {
+        Some(exec.base_config())
+    } else if let Some(exec) = any.downcast_ref::<CsvExec>() {
+        Some(exec.base_config())
+    } else {
+        any.downcast_ref::<AvroExec>()
+            .map(|exec| exec.base_config())
+    }
+}
+
+fn add_scan_hosts(config: &FileScanConfig, partition: usize, locality: &mut TaskLocality) {
+    let url = Url::parse(config.object_store_url.as_ref()).ok();
+    let host = match url.as_ref().and_then(|url| url.host_str()) {
+        Some(host) => host.to_string(),
+        None => return,
+    };
+    let bytes: usize = config
+        .file_groups
+        .get(partition)
+        .into_iter()
+        .flatten()
+        .map(|file| file.object_meta.size)
+        .sum();
+    *locality.hosts.entry(host).or_default() += (bytes as u64).max(1);
+}
+
+/// Cut physical plans into stages, stages are numbered in the order they
+/// can run so every stage comes after the stages it reads
+#[derive(Debug, Default)]
+pub struct DistributedPlanner {
+    next_stage_id: usize,
+}
+
+impl DistributedPlanner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stages of the plan of a job, the last one writes the result
+    pub fn plan_query_stages(
+        &mut self,
+        job_id: &str,
+        plan: Arc<dyn ExecutionPlan>,
+    ) -> Result<Vec<QueryStage>> {
+        let mut stages = vec![];
+        let plan = self.plan_inputs(job_id, plan, &mut stages)?;
+        stages.push(self.new_stage(job_id, plan, None)?);
+        Ok(stages)
+    }
+
+    /// Replace the exchanges of the plan with unresolved shuffle readers and
+    /// add the stages writing them
+    fn plan_inputs(
+        &mut self,
+        job_id: &str,
+        plan: Arc<dyn ExecutionPlan>,
+        stages: &mut Vec<QueryStage>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let children = plan
+            .children()
+            .into_iter()
+            .map(|child| self.plan_inputs(job_id, child, stages))
+            .collect::<Result<Vec<_>>>()?;
+        let any = plan.as_any();
+
+        if let Some(repartition) = any.downcast_ref::<RepartitionExec>() {
+            return match repartition.partitioning() {
+                Partitioning::Hash(_, _) => {
+                    let partitioning = repartition.partitioning().clone();
+                    let stage = self.new_stage(job_id, children[0].clone(), Some(partitioning))?;
+                    Ok(self.add_stage(stage, stages))
+                }
+                // other repartitions only spread work inside a process, every
+                // task of a stage already runs on its own
+                _ => Ok(children[0].clone()),
+            };
+        }
+        if any.is::<CoalescePartitionsExec>() || any.is::<SortPreservingMergeExec>() {
+            let stage = self.new_stage(job_id, children[0].clone(), None)?;
+            let reader = self.add_stage(stage, stages);
+            return with_new_children_if_necessary(plan, vec![reader]);
+        }
+        with_new_children_if_necessary(plan, children)
+    }
+
+    fn new_stage(
+        &mut self,
+        job_id: &str,
+        plan: Arc<dyn ExecutionPlan>,
+        partitioning: Option<Partitioning>,
+    ) -> Result<QueryStage> {
+        let id = self.next_stage_id;
+        self.next_stage_id += 1;
+        let mut inputs = vec![];
+        find_inputs(&plan, &mut inputs);
+        // executors write shuffle data to their own work dir
+        let plan =
+            ShuffleWriterExec::try_new(job_id.to_string(), id, plan, String::new(), partitioning)?;
+        Ok(QueryStage {
+            id,
+            plan: Arc::new(plan),
+            inputs,
+        })
+    }
+
+    /// Add a stage and return the reader of its output
+    fn add_stage(
+        &mut self,
+        stage: QueryStage,
+        stages: &mut Vec<QueryStage>,
+    ) -> Arc<dyn ExecutionPlan> {
+        let reader = Arc::new(UnresolvedShuffleExec::new(
+            stage.id,
+            stage.output_schema(),
+            stage.output_partition_count(),
+        ));
+        stages.push(stage);
+        reader
+    }
+}
+
+/// Stages of the sql of a job, planned in the context
+pub async fn plan_job(ctx: &SessionContext, job_id: &str, sql: &str) -> Result<Vec<QueryStage>> {
+    plan_stages(ctx, job_id, &ctx.create_logical_plan(sql)?).await
+}
+
+/// Stages of the logical plan of a job, planned in the context
+pub async fn plan_stages(
+    ctx: &SessionContext,
+    job_id: &str,
+    plan: &LogicalPlan,
+) -> Result<Vec<QueryStage>> {
+    let plan = ctx.create_physical_plan(plan).await?;
+    DistributedPlanner::new().plan_query_stages(job_id, plan)
+}
+
+fn find_inputs(plan: &Arc<dyn ExecutionPlan>, inputs: &mut Vec<usize>) {
+    if let Some(reader) = plan.as_any().downcast_ref::<UnresolvedShuffleExec>() {
+        inputs.push(reader.stage_id);
+    }
+    for child in plan.children() {
+        find_inputs(&child, inputs);
+    }
+}
+
+fn resolve(
+    plan: Arc<dyn ExecutionPlan>,
+    locations: &HashMap<usize, Vec<ShuffleLocation>>,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    if let Some(reader) = plan.as_any().downcast_ref::<UnresolvedShuffleExec>() {
+        let written = locations.get(&reader.stage_id).ok_or_else(|| {
+            DataFusionError::Internal(format!("Stage {} has no output yet", reader.stage_id))
+        })?;
+        let mut partitions = vec![vec![]; reader.partition_count];
+        for location in written {
+            let partition = location
+                .partition
+                .as_ref()
+                .map(|partition| partition.partition_id as usize)
+                .unwrap_or_default();
+            partitions
+                .get_mut(partition)
+                .ok_or_else(|| {
+                    DataFusionError::Internal(format!(
+                        "Stage {} has no output partition {}",
+                        reader.stage_id, partition
+                    ))
+                })?
+                .push(location.clone());
+        }
+        return Ok(Arc::new(ShuffleReaderExec::new(
+            partitions,
+            reader.schema.clone(),
+        )));
+    }
+
+    let children = plan
+        .children()
+        .into_iter()
+        .map(|child| resolve(child, locations))
+        .collect::<Result<Vec<_>>>()?;
+    with_new_children_if_necessary(plan, children)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use datafusion::physical_plan::displayable;
+    use datafusion::prelude::{CsvReadOptions, SessionConfig};
+
+    use super::*;
+
+    async fn stages(sql: &str) -> Vec<QueryStage> {
+        let dir = std::env::temp_dir().join(format!("rapidash-planner-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("t.csv");
+        fs::write(&path, "a,b\n1,2\n1,3\n2,4\n").unwrap();
+
+        let ctx = SessionContext::with_config(SessionConfig::new().with_target_partitions(4));
+        ctx.register_csv("t", path.to_str().unwrap(), CsvReadOptions::new())
+            .await
+            .unwrap();
+        plan_job(&ctx, "job", sql).await.unwrap()
+    }
+
+    fn display(stage: &QueryStage) -> String {
+        let plan: Arc<dyn ExecutionPlan> = stage.plan.clone();
+        let display = displayable(plan.as_ref()).indent().to_string();
+        display
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_stages() {
+        let stages = stages("select a, sum(b) from t group by a").await;
+        assert_eq!(stages.len(), 2);
+
+        // the partial aggregate writes hash partitions for the final one
+        assert!(stages[0].inputs.is_empty());
+        assert_eq!(stages[0].output_partition_count(), 4);
+        assert_eq!(stages[1].inputs, vec![0]);
+        assert_eq!(stages[1].partition_count(), 4);
+        for stage in &stages {
+            assert!(!display(stage).contains("RepartitionExec"));
+        }
+        assert!(display(&stages[1]).contains("UnresolvedShuffleExec: stage=0"));
+        // the final aggregate reads the partial aggregate, not the writer summary
+        let written = stages[0].output_schema();
+        let aggregate = stages[1].plan.children()[0].children()[0].clone();
+        assert_eq!(aggregate.children()[0].schema(), written);
+    }
+
+    #[tokio::test]
+    async fn test_merge_stage() {
+        let stages = stages("select a, sum(b) as s from t group by a order by s").await;
+        assert_eq!(stages.len(), 3);
+        // the partitions are merged and sorted by the last stage
+        assert_eq!(stages[2].inputs, vec![1]);
+        assert_eq!(stages[2].partition_count(), 1);
+        assert!(display(&stages[2]).contains("CoalescePartitionsExec"));
+        assert!(display(&stages[2]).contains("SortExec"));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_tasks() {
+        let stages = stages("select a, sum(b) from t group by a").await;
+        assert!(stages[1].tasks(&HashMap::new(), 0).is_err());
+
+        let locations = (0..4)
+            .map(|partition| ShuffleLocation {
+                partition: Some(PartitionId {
+                    job_id: "job".to_string(),
+                    stage_id: 0,
+                    partition_id: partition,
+                }),
+                executor_id: "executor".to_string(),
+                ..Default::default()
+            })
+            .collect();
+        let locations = HashMap::from([(0, locations)]);
+        let plan = stages[1].resolve(&locations).unwrap();
+        let text = displayable(plan.as_ref()).indent().to_string();
+        assert!(text.contains("ShuffleReaderExec: partitions=4"));
+
+        // the partition 3 was written by the executor
+        let locality = stages[1].locality(3, &locations);
+        assert_eq!(locality.executors["executor"], 1);
+        assert!(locality.hosts.is_empty());
+
+        let tasks = stages[1].tasks(&locations, 1).unwrap();
+        assert_eq!(tasks.len(), 4);
+        assert_eq!(tasks[3].partition.as_ref().unwrap().partition_id, 3);
+        assert_eq!(tasks[3].attempt, 1);
+    }
+}
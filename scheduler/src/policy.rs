@@ -0,0 +1,455 @@
+//! Policies assigning tasks to executors.
+//!
+//! Push policies launch tasks on executors with free slots, round robin or
+//! preferring the executors close to the input of a task. With the pull
+//! policy tasks wait in a queue until executors poll for them.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use common::config::Config;
+use log::info;
+use transmit::proto::TaskDefinition;
+
+use crate::cluster::{ExecutorInfo, ExecutorManager, ExecutorMeta};
+
+/// How tasks are assigned to executors, from `rapidash.scheduler.policy`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SchedulingPolicy {
+    /// Launch tasks on executors in turn
+    PushRoundRobin,
+    /// Executors poll for tasks when they have free slots
+    Pull,
+    /// Launch tasks on the executors holding most of their input
+    Locality,
+}
+
+impl SchedulingPolicy {
+    pub fn from_config(config: &Config) -> Self {
+        // the values are validated by the configuration
+        match config.scheduling_policy().as_str() {
+            "pull" => SchedulingPolicy::Pull,
+            "locality" => SchedulingPolicy::Locality,
+            _ => SchedulingPolicy::PushRoundRobin,
+        }
+    }
+}
+
+impl fmt::Display for SchedulingPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            SchedulingPolicy::PushRoundRobin => "push",
+            SchedulingPolicy::Pull => "pull",
+            SchedulingPolicy::Locality => "locality",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Where the input of a task is, in bytes by executor id and by host
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TaskLocality {
+    /// Shuffle partitions written by executors
+    pub executors: HashMap<String, u64>,
+    /// Files stored on hosts
+    pub hosts: HashMap<String, u64>,
+}
+
+impl TaskLocality {
+    /// Whether the task reads nothing held by a particular executor
+    pub fn is_empty(&self) -> bool {
+        self.executors.is_empty() && self.hosts.is_empty()
+    }
+
+    /// Bytes of the input the executor can read locally
+    pub fn local_bytes(&self, executor: &ExecutorMeta) -> u64 {
+        self.executors
+            .get(&executor.id)
+            .copied()
+            .unwrap_or_default()
+            + self.hosts.get(&executor.host).copied().unwrap_or_default()
+    }
+}
+
+/// A task waiting for an executor
+#[derive(Debug, Clone)]
+pub struct PendingTask {
+    pub definition: TaskDefinition,
+    pub locality: TaskLocality,
+    /// Executor the task must not run on, the one running the original of
+    /// a speculative copy
+    pub excluded: Option<String>,
+}
+
+impl PendingTask {
+    pub fn new(definition: TaskDefinition) -> Self {
+        Self {
+            definition,
+            locality: TaskLocality::default(),
+            excluded: None,
+        }
+    }
+
+    pub fn can_run_on(&self, executor: &ExecutorMeta) -> bool {
+        self.excluded.as_ref() != Some(&executor.id)
+    }
+}
+
+/// Counts of placed tasks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PlacementStats {
+    pub tasks: u64,
+    /// Tasks placed on an executor holding some of their input
+    pub local: u64,
+    /// Tasks with a local executor placed elsewhere
+    pub remote: u64,
+}
+
+/// Assign tasks to executors following a policy
+#[derive(Clone)]
+pub struct TaskScheduler {
+    policy: SchedulingPolicy,
+    /// Tasks no executor took yet, in submission order
+    queue: Arc<Mutex<VecDeque<PendingTask>>>,
+    /// Executor the next round robin starts with
+    next: Arc<Mutex<usize>>,
+    stats: Arc<Mutex<HashMap<SchedulingPolicy, PlacementStats>>>,
+}
+
+impl TaskScheduler {
+    pub fn new(policy: SchedulingPolicy) -> Self {
+        Self {
+            policy,
+            queue: Arc::new(Mutex::new(VecDeque::new())),
+            next: Arc::new(Mutex::new(0)),
+            stats: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(SchedulingPolicy::from_config(config))
+    }
+
+    pub fn policy(&self) -> SchedulingPolicy {
+        self.policy
+    }
+
+    /// Number of tasks waiting for an executor
+    pub fn pending(&self) -> usize {
+        self.queue.lock().unwrap().len()
+    }
+
+    /// Placement stats of every policy that placed tasks
+    pub fn stats(&self) -> HashMap<SchedulingPolicy, PlacementStats> {
+        self.stats.lock().unwrap().clone()
+    }
+
+    /// Put tasks back in front of the queue, e.g. when launching them failed
+    pub fn requeue(&self, tasks: Vec<PendingTask>) {
+        let mut queue = self.queue.lock().unwrap();
+        for task in tasks.into_iter().rev() {
+            queue.push_front(task);
+        }
+    }
+
+    /// Drop queued tasks by id, return their number
+    pub fn remove_tasks(&self, task_ids: &[String]) -> usize {
+        let mut queue = self.queue.lock().unwrap();
+        let before = queue.len();
+        queue.retain(|task| !task_ids.contains(&task.definition.task_id));
+        before - queue.len()
+    }
+
+    /// Drop the queued tasks of a job, return their number
+    pub fn remove_job(&self, job_id: &str) -> usize {
+        let mut queue = self.queue.lock().unwrap();
+        let before = queue.len();
+        queue.retain(|task| {
+            task.definition
+                .partition
+                .as_ref()
+                .is_none_or(|partition| partition.job_id != job_id)
+        });
+        before - queue.len()
+    }
+
+    /// Queue the tasks, and with a push policy assign the queued tasks to the
+    /// free slots of the executors, reserving the slots until the tasks
+    /// finish. Tasks without a free slot stay queued until the next call.
+    pub fn schedule(
+        &self,
+        tasks: Vec<PendingTask>,
+        manager: &ExecutorManager,
+    ) -> Vec<(ExecutorMeta, Vec<PendingTask>)> {
+        // the queue lock orders the reservations of concurrent calls
+        let mut queue = self.queue.lock().unwrap();
+        queue.extend(tasks);
+        if self.policy == SchedulingPolicy::Pull {
+            return vec![];
+        }
+
+        let mut executors: Vec<ExecutorInfo> = manager
+            .executors()
+            .into_iter()
+            .filter(|executor| executor.free_slots() > 0)
+            .collect();
+        // a stable order, so the round robin goes through every executor
+        executors.sort_by(|a, b| a.meta.id.cmp(&b.meta.id));
+        let mut free: Vec<u32> = executors.iter().map(ExecutorInfo::free_slots).collect();
+        let mut assigned: Vec<Vec<PendingTask>> = vec![vec![]; executors.len()];
+        let mut next = self.next.lock().unwrap();
+        // tasks whose only free executors are excluded wait for the next call
+        let mut skipped = vec![];
+
+        while !free.iter().all(|slots| *slots == 0) {
+            let task = match queue.pop_front() {
+                Some(task) => task,
+                None => break,
+            };
+            let is_free =
+                |index: &usize| free[*index] > 0 && task.can_run_on(&executors[*index].meta);
+            let round_robin = match (0..executors.len())
+                .map(|offset| (*next + offset) % executors.len())
+                .find(is_free)
+            {
+                Some(index) => index,
+                None => {
+                    skipped.push(task);
+                    continue;
+                }
+            };
+            let index = match self.policy {
+                SchedulingPolicy::Locality => (0..executors.len())
+                    .filter(is_free)
+                    .max_by_key(|index| task.locality.local_bytes(&executors[*index].meta))
+                    .filter(|index| task.locality.local_bytes(&executors[*index].meta) > 0)
+                    .unwrap_or(round_robin),
+                _ => round_robin,
+            };
+            if index == round_robin {
+                *next = (index + 1) % executors.len();
+            }
+
+            free[index] -= 1;
+            self.record(&task.locality, &executors[index].meta);
+            assigned[index].push(task);
+        }
+        for task in skipped.into_iter().rev() {
+            queue.push_front(task);
+        }
+
+        let assigned: Vec<(ExecutorMeta, Vec<PendingTask>)> = executors
+            .into_iter()
+            .map(|executor| executor.meta)
+            .zip(assigned)
+            .filter(|(_, tasks)| !tasks.is_empty())
+            .collect();
+        for (executor, tasks) in &assigned {
+            manager.reserve(&executor.id, &task_ids(tasks));
+        }
+        assigned
+    }
+
+    /// Queued tasks an executor polling with free slots takes, the tasks
+    /// reading its local data first
+    pub fn poll(&self, executor: &ExecutorMeta, slots: u32) -> Vec<PendingTask> {
+        let mut queue = self.queue.lock().unwrap();
+        let (mut indices, others): (Vec<usize>, Vec<usize>) = (0..queue.len())
+            .filter(|index| queue[*index].can_run_on(executor))
+            .partition(|index| queue[*index].locality.local_bytes(executor) > 0);
+        indices.extend(others);
+        indices.truncate(slots as usize);
+        // remove from the back, so the indices of the others stay valid
+        indices.sort_unstable();
+        let mut taken: Vec<PendingTask> = indices
+            .into_iter()
+            .rev()
+            .filter_map(|index| queue.remove(index))
+            .collect();
+        taken.reverse();
+
+        for task in &taken {
+            self.record(&task.locality, executor);
+        }
+        taken
+    }
+
+    fn record(&self, locality: &TaskLocality, executor: &ExecutorMeta) {
+        let mut stats = self.stats.lock().unwrap();
+        let stats = stats.entry(self.policy).or_default();
+        stats.tasks += 1;
+        if locality.local_bytes(executor) > 0 {
+            stats.local += 1;
+        } else if !locality.is_empty() {
+            stats.remote += 1;
+        }
+    }
+
+    /// Log the placement stats of every policy in background every `interval`
+    pub fn start_stats_report(&self, interval: Duration) {
+        let scheduler = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                for (policy, stats) in scheduler.stats() {
+                    info!(
+                        "Policy {} placed {} tasks, {} local and {} remote, {} queued",
+                        policy,
+                        stats.tasks,
+                        stats.local,
+                        stats.remote,
+                        scheduler.pending()
+                    );
+                }
+            }
+        });
+    }
+}
+
+/// Ids of the tasks
+pub fn task_ids(tasks: &[PendingTask]) -> Vec<String> {
+    tasks
+        .iter()
+        .map(|task| task.definition.task_id.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta(id: &str, slots: u32) -> ExecutorMeta {
+        ExecutorMeta {
+            id: id.to_string(),
+            host: format!("{}.local", id),
+            port: 51009,
+            task_slots: slots,
+        }
+    }
+
+    fn executors(slots: &[(&str, u32)]) -> ExecutorManager {
+        let manager = ExecutorManager::new(Duration::from_secs(60));
+        for (id, slots) in slots {
+            manager.register(meta(id, *slots));
+        }
+        manager
+    }
+
+    fn task(id: &str, local_to: Option<&str>) -> PendingTask {
+        let mut task = PendingTask::new(TaskDefinition {
+            task_id: id.to_string(),
+            ..Default::default()
+        });
+        if let Some(executor) = local_to {
+            task.locality.executors.insert(executor.to_string(), 100);
+        }
+        task
+    }
+
+    fn assigned_ids(assigned: &[(ExecutorMeta, Vec<PendingTask>)]) -> Vec<(String, Vec<String>)> {
+        assigned
+            .iter()
+            .map(|(executor, tasks)| (executor.id.clone(), task_ids(tasks)))
+            .collect()
+    }
+
+    #[test]
+    fn test_round_robin() {
+        let scheduler = TaskScheduler::new(SchedulingPolicy::PushRoundRobin);
+        let executors = executors(&[("a", 1), ("b", 2)]);
+        let tasks = ["1", "2", "3", "4"].map(|id| task(id, None)).to_vec();
+        let assigned = scheduler.schedule(tasks, &executors);
+
+        assert_eq!(
+            assigned_ids(&assigned),
+            vec![
+                ("a".to_string(), vec!["1".to_string()]),
+                ("b".to_string(), vec!["2".to_string(), "3".to_string()]),
+            ]
+        );
+        // the task without a free slot waits until a task finishes
+        assert_eq!(scheduler.pending(), 1);
+        assert!(scheduler.schedule(vec![], &executors).is_empty());
+        executors.release(&["1".to_string()]);
+        let assigned = scheduler.schedule(vec![], &executors);
+        assert_eq!(
+            assigned_ids(&assigned),
+            vec![("a".to_string(), vec!["4".to_string()])]
+        );
+    }
+
+    #[test]
+    fn test_locality() {
+        let scheduler = TaskScheduler::new(SchedulingPolicy::Locality);
+        let executors = executors(&[("a", 2), ("b", 1)]);
+        let mut remote = task("3", None);
+        remote.locality.hosts.insert("b.local".to_string(), 10);
+        let tasks = vec![task("1", Some("b")), task("2", Some("b")), remote];
+        let assigned = scheduler.schedule(tasks, &executors);
+
+        // b has a single slot, so the second task reading from b runs on a
+        assert_eq!(
+            assigned_ids(&assigned),
+            vec![
+                ("a".to_string(), vec!["2".to_string(), "3".to_string()]),
+                ("b".to_string(), vec!["1".to_string()]),
+            ]
+        );
+        let stats = scheduler.stats()[&SchedulingPolicy::Locality];
+        assert_eq!(
+            stats,
+            PlacementStats {
+                tasks: 3,
+                local: 1,
+                remote: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_pull() {
+        let scheduler = TaskScheduler::new(SchedulingPolicy::Pull);
+        let executors = executors(&[("a", 4)]);
+        let tasks = vec![task("1", None), task("2", Some("a")), task("3", None)];
+        assert!(scheduler.schedule(tasks, &executors).is_empty());
+        let executor = meta("a", 4);
+
+        let ids = |tasks: Vec<PendingTask>| -> Vec<String> {
+            tasks
+                .into_iter()
+                .map(|task| task.definition.task_id)
+                .collect()
+        };
+        // the task local to the executor is taken first, then in order
+        assert_eq!(ids(scheduler.poll(&executor, 1)), vec!["2"]);
+        assert_eq!(ids(scheduler.poll(&executor, 1)), vec!["1"]);
+        assert_eq!(scheduler.pending(), 1);
+        assert_eq!(scheduler.stats()[&SchedulingPolicy::Pull].local, 1);
+    }
+
+    #[test]
+    fn test_excluded_executor() {
+        let scheduler = TaskScheduler::new(SchedulingPolicy::PushRoundRobin);
+        let mut copy = task("copy", None);
+        copy.excluded = Some("a".to_string());
+
+        // the copy waits rather than run next to its original
+        let executors = executors(&[("a", 2)]);
+        let assigned = scheduler.schedule(vec![copy, task("1", None)], &executors);
+        assert_eq!(
+            assigned_ids(&assigned),
+            vec![("a".to_string(), vec!["1".to_string()])]
+        );
+        assert_eq!(scheduler.pending(), 1);
+
+        executors.register(meta("b", 1));
+        let assigned = scheduler.schedule(vec![], &executors);
+        assert_eq!(
+            assigned_ids(&assigned),
+            vec![("b".to_string(), vec!["copy".to_string()])]
+        );
+    }
+}
@@ -1,6 +1,1462 @@
 //! Process queries from the client.
 
-pub struct Query {}
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
 
-// #[tonic::async_trait]
-// impl Query for Query
+use common::config::{Config, DEFAULT_BATCH_SIZE};
+use common::error::RapidashError;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::prelude::{SessionConfig, SessionContext};
+use futures::{stream, Stream, StreamExt};
+use log::{info, warn};
+use tonic::{Request, Response, Status};
+use transmit::auth::{principal, AuthInterceptor};
+use transmit::compression::CompressionConfig;
+use transmit::error::to_status;
+use transmit::handshake::{capabilities, check_compatible};
+use transmit::ipc::encode_batches;
+use transmit::proto::scheduler_proto_server::SchedulerProto;
+use transmit::proto::task_status;
+use transmit::proto::{
+    CancelQueryRequest, CancelQueryResponse, CloseSessionRequest, CloseSessionResponse,
+    CreateSessionRequest, CreateSessionResponse, ExecutorRegistration, FetchResultRequest,
+    FetchResultResponse, GetQuotasRequest, HandshakeRequest, HandshakeResponse, HeartbeatRequest,
+    HeartbeatResponse, PeerRole, PollWorkRequest, PollWorkResponse, QueryRequest, QueryResponse,
+    QueryState, QueryStatusRequest, QueryStatusResponse, QuotasResponse, RegisterExecutorResponse,
+    ResultBatch, ShuffleLocation, TaskDefinition, UpdateQuotasRequest, UpdateSessionRequest,
+    UpdateSessionResponse, UpdateTaskStatusRequest, UpdateTaskStatusResponse,
+};
+use transmit::tls::TlsConfig;
+
+use crate::admission::{AdmissionControl, Quotas};
+use crate::cluster::{ExecutorManager, ExecutorMeta};
+use crate::graph::ExecutionManager;
+use crate::job::{ExecutionState, Job, JobManager, JobResult, JobState, StageRunner};
+use crate::planner::QueryStage;
+use crate::policy::{task_ids, PendingTask, SchedulingPolicy, TaskScheduler};
+use crate::queue::JobQueue;
+use crate::session::{Session, SessionManager};
+use crate::state::SchedulerState;
+use crate::task::TaskManager;
+use transmit::rpc::ConnectionManager;
+
+type ResultBatchStream = Pin<Box<dyn Stream<Item = Result<ResultBatch, Status>> + Send>>;
+
+/// Scheduler side implementation of `SchedulerProto`.
+#[derive(Clone)]
+pub struct Query {
+    jobs: JobManager,
+    executors: ExecutorManager,
+    tasks: TaskManager,
+    scheduler: TaskScheduler,
+    executions: ExecutionManager,
+    sessions: SessionManager,
+    config: Config,
+}
+
+impl Query {
+    pub fn new(config: Config) -> common::error::Result<Self> {
+        Self::with_state(config, SchedulerState::default())
+    }
+
+    /// Service keeping its sessions, jobs, executors and catalog in the
+    /// state, fails when the token of the calls to executors is invalid
+    pub fn with_state(config: Config, state: SchedulerState) -> common::error::Result<Self> {
+        let timeout = Duration::from_secs(config.executor_timeout() as u64);
+        let connections = ConnectionManager::from_config(&config)?;
+        // job results are fetched from the executors like any shuffle output
+        let mut session_config = SessionConfig::new()
+            .with_extension(Arc::new(TlsConfig::from_config(&config)))
+            .with_extension(Arc::new(CompressionConfig::from_config(&config)))
+            .with_extension(Arc::new(connections.token()));
+        if let Some(partitions) = config.shuffle_partitions() {
+            session_config = session_config.with_target_partitions(partitions);
+        }
+        let ctx = SessionContext::with_config(session_config);
+        let mut query = Self {
+            jobs: JobManager::with_state(state.clone())
+                .with_context(ctx)
+                .with_queue(JobQueue::from_config(&config))
+                .with_admission(AdmissionControl::from_config(&config)),
+            executors: ExecutorManager::with_state(timeout, state.clone()),
+            tasks: TaskManager::new(connections),
+            scheduler: TaskScheduler::from_config(&config),
+            executions: ExecutionManager::from_config(&config),
+            sessions: SessionManager::with_state(state),
+            config,
+        };
+        // the runner only submits stages, the jobs of its copy are never run
+        let runner = Arc::new(query.clone());
+        query.jobs = query.jobs.clone().with_runner(runner);
+        Ok(query)
+    }
+
+    /// Recover what the state kept before a restart
+    pub async fn recover(&self) -> common::error::Result<()> {
+        let sessions = self.sessions.recover()?;
+        let executors = self.executors.recover()?;
+        let jobs = self.jobs.recover().await?;
+        info!(
+            "Recovered {} sessions, {} executors and {} jobs",
+            sessions, executors, jobs
+        );
+        Ok(())
+    }
+
+    pub fn jobs(&self) -> &JobManager {
+        &self.jobs
+    }
+
+    pub fn executors(&self) -> &ExecutorManager {
+        &self.executors
+    }
+
+    pub fn tasks(&self) -> &TaskManager {
+        &self.tasks
+    }
+
+    pub fn sessions(&self) -> &SessionManager {
+        &self.sessions
+    }
+
+    pub fn scheduler(&self) -> &TaskScheduler {
+        &self.scheduler
+    }
+
+    pub fn executions(&self) -> &ExecutionManager {
+        &self.executions
+    }
+
+    /// Run the stages of a job on the executors, later stages are
+    /// submitted as the executors report the status of their tasks. Jobs
+    /// submit their stages through `StageRunner::run_stages`.
+    async fn submit_stages(
+        &self,
+        job_id: &str,
+        stages: Vec<QueryStage>,
+    ) -> datafusion::error::Result<()> {
+        let tasks = self.executions.submit(job_id, stages)?;
+        self.submit_tasks(tasks).await;
+        Ok(())
+    }
+
+    /// Assign tasks with the scheduling policy, pushed tasks are launched
+    /// right away and pulled ones wait for executors to poll
+    pub async fn submit_tasks(&self, tasks: Vec<PendingTask>) {
+        let assigned = self.scheduler.schedule(tasks, &self.executors);
+        for (executor, tasks) in assigned {
+            let definitions: Vec<TaskDefinition> =
+                tasks.iter().map(|task| task.definition.clone()).collect();
+            self.launched(&executor.id, &definitions);
+            if let Err(e) = self.tasks.launch(&executor, definitions).await {
+                // another executor may take them
+                warn!("Failed to launch tasks on executor {}: {}", executor.id, e);
+                self.executors.release(&task_ids(&tasks));
+                self.scheduler.requeue(tasks);
+            }
+        }
+    }
+
+    /// Tasks given to an executor, for the graphs and the progress of their jobs
+    fn launched(&self, executor_id: &str, tasks: &[TaskDefinition]) {
+        self.executions.launched(executor_id, tasks);
+        for id in tasks.iter().filter_map(|task| task.partition.as_ref()) {
+            self.jobs.transition_task(
+                &id.job_id,
+                id.stage_id as usize,
+                id.partition_id as usize,
+                ExecutionState::Running,
+            );
+        }
+    }
+
+    /// Cancel tasks not needed anymore, whether queued or running
+    async fn cancel_tasks(&self, task_ids: &[String]) {
+        if task_ids.is_empty() {
+            return;
+        }
+        self.scheduler.remove_tasks(task_ids);
+        self.executors.release(task_ids);
+        self.tasks.cancel_tasks(task_ids, &self.executors).await;
+    }
+
+    /// Run again elsewhere the tasks of executors that died and the outputs they wrote
+    async fn executors_lost(&self, executor_ids: &[String]) {
+        for executor_id in executor_ids {
+            let lost = self.tasks.executor_lost(executor_id);
+            let update = self.executions.executor_lost(executor_id);
+            info!(
+                "Executor {} lost {} running tasks, {} tasks run again",
+                executor_id,
+                lost.len(),
+                update.tasks.len()
+            );
+            self.cancel_tasks(&update.cancelled).await;
+            self.submit_tasks(update.tasks).await;
+        }
+    }
+
+    /// Mark dead the executors that missed heartbeats in background every `interval`
+    pub fn start_expiry_check(&self, interval: Duration) {
+        let query = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let dead = query.executors.remove_expired();
+                if !dead.is_empty() {
+                    query.executors_lost(&dead).await;
+                }
+            }
+        });
+    }
+
+    /// Copy the slow tasks of running stages on other executors every interval
+    pub fn start_speculation(&self, interval: Duration) {
+        let query = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let copies = query.executions.speculative_tasks();
+                if !copies.is_empty() {
+                    query.submit_tasks(copies).await;
+                }
+            }
+        });
+    }
+
+    fn session(&self, id: &str) -> Result<Session, Status> {
+        if id.is_empty() {
+            return Err(Status::invalid_argument("Missing session id"));
+        }
+        self.sessions
+            .get(id)
+            .ok_or_else(|| Status::not_found(format!("Session {} not found", id)))
+    }
+
+    /// A session is only used by the user that created it
+    fn owned_session(&self, id: &str, principal: &Option<String>) -> Result<Session, Status> {
+        let session = self.session(id)?;
+        if &session.principal != principal {
+            return Err(Status::permission_denied(format!(
+                "Session {} belongs to another user",
+                id
+            )));
+        }
+        Ok(session)
+    }
+
+    /// Executor rpcs are reserved to `rapidash.executor.principals` once
+    /// clients authenticate
+    fn check_executor<T>(&self, request: &Request<T>) -> Result<(), Status> {
+        match principal(request) {
+            Some(principal) if !self.config.executor_principals().contains(&principal) => Err(
+                Status::permission_denied(format!("{} is not an executor", principal)),
+            ),
+            None if AuthInterceptor::is_configured(&self.config) => Err(Status::unauthenticated(
+                "Executor rpcs need an authenticated executor",
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Admin rpcs are reserved to `rapidash.admin.principals`, so they need
+    /// authentication
+    fn check_admin<T>(&self, request: &Request<T>) -> Result<String, Status> {
+        match principal(request) {
+            Some(principal) if self.config.admin_principals().contains(&principal) => Ok(principal),
+            Some(principal) => Err(Status::permission_denied(format!(
+                "{} is not an admin",
+                principal
+            ))),
+            None => Err(Status::unauthenticated(
+                "Admin rpcs need an authenticated admin",
+            )),
+        }
+    }
+
+    /// A job is only seen and cancelled by the user that submitted it
+    fn owned_job(&self, id: &str, principal: &Option<String>) -> Result<Job, Status> {
+        let job = self
+            .jobs
+            .get(id)
+            .ok_or_else(|| Status::not_found(format!("Job {} not found", id)))?;
+        if &job.principal != principal {
+            return Err(Status::permission_denied(format!(
+                "Job {} belongs to another user",
+                id
+            )));
+        }
+        Ok(job)
+    }
+
+    /// Result of a succeeded job, kept until `rapidash.result.retention` expires
+    fn result(&self, id: &str) -> Result<JobResult, Status> {
+        self.jobs
+            .result(id)
+            .ok_or_else(|| Status::failed_precondition(format!("Result of job {} expired", id)))
+    }
+}
+
+#[tonic::async_trait]
+impl StageRunner for Query {
+    /// Submit the stages and wait for their graph to finish, the graph is
+    /// removed either way
+    async fn run_stages(
+        &self,
+        job_id: &str,
+        stages: Vec<QueryStage>,
+    ) -> common::error::Result<Vec<ShuffleLocation>> {
+        self.submit_stages(job_id, stages)
+            .await
+            .map_err(|e| RapidashError::Execution(e.to_string()))?;
+        let result = match self.executions.wait(job_id).await {
+            Some(ExecutionState::Succeeded) => self
+                .executions
+                .result(job_id)
+                .ok_or_else(|| RapidashError::Internal(format!("Job {} has no result", job_id))),
+            Some(_) => Err(RapidashError::Execution(
+                self.executions
+                    .error(job_id)
+                    .unwrap_or_else(|| format!("Job {} failed", job_id)),
+            )),
+            // removed by a cancellation
+            None => Err(RapidashError::Cancelled),
+        };
+        self.executions.remove(job_id);
+        self.scheduler.remove_job(job_id);
+        result
+    }
+}
+
+/// Slice batches so that none of them is larger than `batch_size` rows
+pub(crate) fn rebatch(batches: Vec<RecordBatch>, batch_size: usize) -> Vec<RecordBatch> {
+    batches
+        .into_iter()
+        .flat_map(|batch| {
+            (0..batch.num_rows())
+                .step_by(batch_size)
+                .map(|offset| batch.slice(offset, batch_size.min(batch.num_rows() - offset)))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[tonic::async_trait]
+impl SchedulerProto for Query {
+    type StreamResultStream = ResultBatchStream;
+
+    async fn handshake(
+        &self,
+        request: Request<HandshakeRequest>,
+    ) -> Result<Response<HandshakeResponse>, Status> {
+        let request = request.into_inner();
+        let role = request.role();
+        let peer = request
+            .capabilities
+            .ok_or_else(|| Status::invalid_argument("Missing capabilities"))?;
+        if let Err(e) = check_compatible(role, &peer) {
+            warn!("Rejected {:?} {}: {}", role, request.peer_id, e);
+            return Err(to_status(&e));
+        }
+        if role == PeerRole::Executor {
+            self.executors.accept(&request.peer_id);
+        }
+
+        Ok(Response::new(HandshakeResponse {
+            capabilities: Some(capabilities()),
+        }))
+    }
+
+    async fn create_session(
+        &self,
+        request: Request<CreateSessionRequest>,
+    ) -> Result<Response<CreateSessionResponse>, Status> {
+        let principal = principal(&request);
+        let settings = request.into_inner().settings;
+        let session_id = self
+            .sessions
+            .create(settings, principal)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        Ok(Response::new(CreateSessionResponse { session_id }))
+    }
+
+    async fn update_session(
+        &self,
+        request: Request<UpdateSessionRequest>,
+    ) -> Result<Response<UpdateSessionResponse>, Status> {
+        let principal = principal(&request);
+        let UpdateSessionRequest {
+            session_id,
+            settings,
+        } = request.into_inner();
+        self.owned_session(&session_id, &principal)?;
+        self.sessions
+            .update(&session_id, settings)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        Ok(Response::new(UpdateSessionResponse { success: true }))
+    }
+
+    async fn close_session(
+        &self,
+        request: Request<CloseSessionRequest>,
+    ) -> Result<Response<CloseSessionResponse>, Status> {
+        let principal = principal(&request);
+        let session_id = request.into_inner().session_id;
+        // closing an unknown session is a no-op
+        if self.sessions.get(&session_id).is_some() {
+            self.owned_session(&session_id, &principal)?;
+        }
+        let closed = self.sessions.close(&session_id);
+
+        Ok(Response::new(CloseSessionResponse { closed }))
+    }
+
+    async fn query(
+        &self,
+        request: Request<QueryRequest>,
+    ) -> Result<Response<QueryResponse>, Status> {
+        let principal = principal(&request);
+        let QueryRequest { sql, session_id } = request.into_inner();
+        let session = self.owned_session(&session_id, &principal)?;
+        if sql.trim().is_empty() {
+            return Err(Status::invalid_argument("Empty sql in QueryRequest"));
+        }
+
+        let id = self
+            .jobs
+            .submit(Job::new(sql, principal).with_session(&session))
+            .await
+            .map_err(|e| to_status(&e))?;
+
+        Ok(Response::new(QueryResponse { success: true, id }))
+    }
+
+    async fn get_query_status(
+        &self,
+        request: Request<QueryStatusRequest>,
+    ) -> Result<Response<QueryStatusResponse>, Status> {
+        let principal = principal(&request);
+        let job = self.owned_job(&request.into_inner().id, &principal)?;
+        let (state, progress) = (job.state(), job.progress());
+
+        Ok(Response::new(QueryStatusResponse {
+            id: job.id,
+            state: QueryState::from(state) as i32,
+            progress,
+            error: job.error.unwrap_or_default(),
+        }))
+    }
+
+    async fn fetch_result(
+        &self,
+        request: Request<FetchResultRequest>,
+    ) -> Result<Response<FetchResultResponse>, Status> {
+        let principal = principal(&request);
+        let job = self.owned_job(&request.into_inner().id, &principal)?;
+
+        // only a succeeded job has data, others report their state
+        let state = job.state();
+        let data = match state {
+            JobState::Succeeded => {
+                let result = self.result(&job.id)?;
+                encode_batches(&result.schema, &result.batches)
+                    .map_err(|e| Status::internal(format!("Failed to encode result: {}", e)))?
+            }
+            _ => vec![],
+        };
+
+        Ok(Response::new(FetchResultResponse {
+            state: QueryState::from(state) as i32,
+            data,
+        }))
+    }
+
+    async fn stream_result(
+        &self,
+        request: Request<FetchResultRequest>,
+    ) -> Result<Response<Self::StreamResultStream>, Status> {
+        let principal = principal(&request);
+        let job = self.owned_job(&request.into_inner().id, &principal)?;
+        let result = match job.state() {
+            JobState::Succeeded => self.result(&job.id)?,
+            JobState::Cancelled => return Err(to_status(&RapidashError::Cancelled)),
+            state => {
+                return Err(Status::failed_precondition(format!(
+                    "Job {} is {:?}, only the result of a succeeded job can be streamed",
+                    job.id, state
+                )))
+            }
+        };
+
+        // batches of the size set by the session, or else by the scheduler,
+        // step_by panics on zero so never slice by less than one row
+        let config = job
+            .config
+            .as_ref()
+            .filter(|config| config.settings().contains_key(DEFAULT_BATCH_SIZE))
+            .unwrap_or(&self.config);
+        let batches = rebatch(result.batches, config.default_batch_size().max(1));
+        let schema = result.schema;
+        let stream = stream::iter(batches).map(move |batch| {
+            encode_batches(&schema, &[batch])
+                .map(|data| ResultBatch { data })
+                .map_err(|e| Status::internal(format!("Failed to encode result: {}", e)))
+        });
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    async fn cancel_query(
+        &self,
+        request: Request<CancelQueryRequest>,
+    ) -> Result<Response<CancelQueryResponse>, Status> {
+        let principal = principal(&request);
+        let id = request.into_inner().id;
+        self.owned_job(&id, &principal)?;
+        let cancelled = self
+            .jobs
+            .cancel(&id)
+            .ok_or_else(|| Status::not_found(format!("Job {} not found", id)))?;
+        if cancelled {
+            self.executions.remove(&id);
+            self.scheduler.remove_job(&id);
+            self.tasks.cancel_job(&id, &self.executors).await;
+        }
+
+        Ok(Response::new(CancelQueryResponse { cancelled }))
+    }
+
+    async fn register_executor(
+        &self,
+        request: Request<ExecutorRegistration>,
+    ) -> Result<Response<RegisterExecutorResponse>, Status> {
+        self.check_executor(&request)?;
+        let ExecutorRegistration {
+            id,
+            host,
+            port,
+            task_slots,
+        } = request.into_inner();
+        if id.is_empty() || host.is_empty() {
+            return Err(Status::invalid_argument(
+                "Executor id and host must not be empty",
+            ));
+        }
+        let port = u16::try_from(port)
+            .map_err(|_| Status::invalid_argument(format!("Invalid executor port {}", port)))?;
+        // executors older than the handshake would misread our messages
+        if !self.executors.is_accepted(&id) {
+            return Err(Status::failed_precondition(format!(
+                "Executor {} must handshake before registering",
+                id
+            )));
+        }
+
+        let meta = ExecutorMeta {
+            id,
+            host,
+            port,
+            task_slots,
+        };
+        // a live executor that registers again restarted, the tasks it ran are
+        // gone, those of a dead executor already ran elsewhere
+        let live = self.executors.get(&meta.id).is_some();
+        if self.executors.register(meta.clone()) {
+            if live {
+                self.executors_lost(std::slice::from_ref(&meta.id)).await;
+            }
+            self.tasks.cancel_lost(&meta).await;
+        }
+        // tasks may have waited for a first executor
+        if self.scheduler.pending() > 0 {
+            self.submit_tasks(vec![]).await;
+        }
+
+        Ok(Response::new(RegisterExecutorResponse {
+            success: true,
+            heartbeat_interval: self.config.executor_heartbeat_interval() as u64,
+            pull: self.scheduler.policy() == SchedulingPolicy::Pull,
+        }))
+    }
+
+    async fn heartbeat(
+        &self,
+        request: Request<HeartbeatRequest>,
+    ) -> Result<Response<HeartbeatResponse>, Status> {
+        self.check_executor(&request)?;
+        let HeartbeatRequest {
+            executor_id,
+            running_tasks,
+            available_slots,
+        } = request.into_inner();
+        let known = self
+            .executors
+            .heartbeat(&executor_id, running_tasks, available_slots);
+
+        // slots freed since the last heartbeat take the queued tasks
+        if known && self.scheduler.pending() > 0 {
+            self.submit_tasks(vec![]).await;
+        }
+
+        Ok(Response::new(HeartbeatResponse { reregister: !known }))
+    }
+
+    async fn poll_work(
+        &self,
+        request: Request<PollWorkRequest>,
+    ) -> Result<Response<PollWorkResponse>, Status> {
+        self.check_executor(&request)?;
+        let PollWorkRequest {
+            executor_id,
+            available_slots,
+        } = request.into_inner();
+        let executor = self.executors.get(&executor_id).ok_or_else(|| {
+            Status::not_found(format!("Executor {} is not registered", executor_id))
+        })?;
+
+        let tasks: Vec<TaskDefinition> = self
+            .scheduler
+            .poll(&executor.meta, available_slots)
+            .into_iter()
+            .map(|task| task.definition)
+            .collect();
+        let task_ids: Vec<String> = tasks.iter().map(|task| task.task_id.clone()).collect();
+        self.executors.reserve(&executor_id, &task_ids);
+        self.launched(&executor_id, &tasks);
+        self.tasks.track(&executor_id, &tasks);
+
+        Ok(Response::new(PollWorkResponse { tasks }))
+    }
+
+    async fn update_task_status(
+        &self,
+        request: Request<UpdateTaskStatusRequest>,
+    ) -> Result<Response<UpdateTaskStatusResponse>, Status> {
+        self.check_executor(&request)?;
+        let statuses = request.into_inner().statuses;
+        let finished: Vec<String> = statuses
+            .iter()
+            .filter(|status| {
+                matches!(
+                    status.status,
+                    Some(task_status::Status::Successful(_) | task_status::Status::Failed(_))
+                )
+            })
+            .map(|status| status.task_id.clone())
+            .collect();
+        self.executors.release(&finished);
+        // failed tasks run again, lost inputs are computed again and
+        // finished stages let the stages reading them start
+        let update = self.executions.update(&statuses);
+        for status in &statuses {
+            if let (Some(id), Some(task_status::Status::Successful(_))) =
+                (&status.partition, &status.status)
+            {
+                self.jobs.transition_task(
+                    &id.job_id,
+                    id.stage_id as usize,
+                    id.partition_id as usize,
+                    ExecutionState::Succeeded,
+                );
+            }
+        }
+        self.tasks.update(statuses);
+        self.cancel_tasks(&update.cancelled).await;
+        self.submit_tasks(update.tasks).await;
+
+        Ok(Response::new(UpdateTaskStatusResponse { success: true }))
+    }
+
+    async fn get_quotas(
+        &self,
+        request: Request<GetQuotasRequest>,
+    ) -> Result<Response<QuotasResponse>, Status> {
+        self.check_admin(&request)?;
+
+        Ok(Response::new(QuotasResponse {
+            quotas: Some(self.jobs.quotas().into()),
+        }))
+    }
+
+    async fn update_quotas(
+        &self,
+        request: Request<UpdateQuotasRequest>,
+    ) -> Result<Response<QuotasResponse>, Status> {
+        let principal = self.check_admin(&request)?;
+        let quotas: Quotas = request
+            .into_inner()
+            .quotas
+            .ok_or_else(|| Status::invalid_argument("Missing quotas"))?
+            .into();
+        info!("Quotas updated by {}: {:?}", principal, quotas);
+        self.jobs.set_quotas(quotas);
+
+        Ok(Response::new(QuotasResponse {
+            quotas: Some(quotas.into()),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::fs;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use common::config::{
+        ADMIN_PRINCIPALS, AUTH_TOKEN_FILE, EXECUTOR_PRINCIPALS, RPC_RETRY_MAX, SCHEDULING_POLICY,
+        SHUFFLE_PARTITIONS, SPECULATION, SPECULATION_MULTIPLIER, SPECULATION_QUANTILE,
+    };
+    use datafusion::arrow::array::Int64Array;
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use futures::TryStreamExt;
+    use transmit::auth::Principal;
+    use transmit::error::from_status;
+    use transmit::handshake::handshake_request;
+    use transmit::ipc::{decode_batch, decode_batches};
+    use transmit::proto::scheduler_proto_client::SchedulerProtoClient;
+    use transmit::proto::{PartitionId, Quotas as QuotasMessage};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::task::TaskState;
+    use crate::testing::start_executor;
+
+    async fn create_session(query: &Query) -> String {
+        let request = Request::new(CreateSessionRequest {
+            settings: HashMap::new(),
+        });
+        query
+            .create_session(request)
+            .await
+            .unwrap()
+            .into_inner()
+            .session_id
+    }
+
+    async fn wait_finished(query: &Query, id: &str) -> QueryStatusResponse {
+        loop {
+            let request = Request::new(QueryStatusRequest { id: id.to_string() });
+            let status = query.get_query_status(request).await.unwrap().into_inner();
+            if status.state() != QueryState::Queued && status.state() != QueryState::Running {
+                return status;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_query_returns_job_id() {
+        let query = Query::new(Config::new().unwrap()).unwrap();
+        let session_id = create_session(&query).await;
+        let request = Request::new(QueryRequest {
+            sql: "select 1".to_string(),
+            session_id,
+        });
+        let response = query.query(request).await.unwrap().into_inner();
+
+        assert!(response.success);
+        assert!(Uuid::parse_str(&response.id).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_query_rejects_empty_sql() {
+        let query = Query::new(Config::new().unwrap()).unwrap();
+        let session_id = create_session(&query).await;
+        let request = Request::new(QueryRequest {
+            sql: " ".to_string(),
+            session_id,
+        });
+        let status = query.query(request).await.unwrap_err();
+
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_query_requires_session() {
+        let query = Query::new(Config::new().unwrap()).unwrap();
+        let request = Request::new(QueryRequest {
+            sql: "select 1".to_string(),
+            session_id: String::new(),
+        });
+        let status = query.query(request).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+        let session_id = create_session(&query).await;
+        let request = Request::new(CloseSessionRequest {
+            session_id: session_id.clone(),
+        });
+        assert!(
+            query
+                .close_session(request)
+                .await
+                .unwrap()
+                .into_inner()
+                .closed
+        );
+        let request = Request::new(QueryRequest {
+            sql: "select 1".to_string(),
+            session_id,
+        });
+        let status = query.query(request).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_session_of_another_user() {
+        let query = Query::new(Config::new().unwrap()).unwrap();
+        let mut request = Request::new(CreateSessionRequest {
+            settings: HashMap::new(),
+        });
+        request
+            .extensions_mut()
+            .insert(Principal("alice".to_string()));
+        let session_id = query
+            .create_session(request)
+            .await
+            .unwrap()
+            .into_inner()
+            .session_id;
+        let session = query.sessions().get(&session_id).unwrap();
+        assert_eq!(session.principal.as_deref(), Some("alice"));
+
+        let request = Request::new(QueryRequest {
+            sql: "select 1".to_string(),
+            session_id: session_id.clone(),
+        });
+        let status = query.query(request).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+
+        // nor changed or closed by another user
+        let request = Request::new(UpdateSessionRequest {
+            session_id: session_id.clone(),
+            settings: HashMap::new(),
+        });
+        let status = query.update_session(request).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+        let request = Request::new(CloseSessionRequest {
+            session_id: session_id.clone(),
+        });
+        let status = query.close_session(request).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+        assert!(query.sessions().get(&session_id).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_executor_principals() {
+        let config = Config::builder()
+            .set(AUTH_TOKEN_FILE, "tokens")
+            .set(EXECUTOR_PRINCIPALS, "executor")
+            .build()
+            .unwrap();
+        let query = Query::new(config).unwrap();
+        let request = Request::new(handshake_request(PeerRole::Executor, "executor"));
+        query.handshake(request).await.unwrap();
+        let registration = ExecutorRegistration {
+            id: "executor".to_string(),
+            host: "localhost".to_string(),
+            port: 51009,
+            task_slots: 4,
+        };
+
+        let status = query
+            .register_executor(Request::new(registration.clone()))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::Unauthenticated);
+        for principal in ["alice", "executor"] {
+            let mut request = Request::new(registration.clone());
+            request
+                .extensions_mut()
+                .insert(Principal(principal.to_string()));
+            let result = query.register_executor(request).await;
+            assert_eq!(result.is_ok(), principal == "executor", "{}", principal);
+
+            let mut request = Request::new(PollWorkRequest {
+                executor_id: "executor".to_string(),
+                available_slots: 4,
+            });
+            request
+                .extensions_mut()
+                .insert(Principal(principal.to_string()));
+            let result = query.poll_work(request).await;
+            assert_eq!(result.is_ok(), principal == "executor", "{}", principal);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_job_owner() {
+        let query = Query::new(Config::new().unwrap()).unwrap();
+        let id = query
+            .jobs()
+            .submit(Job::new("select 1".to_string(), Some("alice".to_string())))
+            .await
+            .unwrap();
+        let request = |principal: &str| {
+            let mut request = Request::new(QueryStatusRequest { id: id.clone() });
+            request
+                .extensions_mut()
+                .insert(Principal(principal.to_string()));
+            request
+        };
+        query.get_query_status(request("alice")).await.unwrap();
+        let status = query.get_query_status(request("bob")).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+
+        let mut request = Request::new(FetchResultRequest { id: id.clone() });
+        request
+            .extensions_mut()
+            .insert(Principal("bob".to_string()));
+        let status = query.fetch_result(request).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+        let mut request = Request::new(FetchResultRequest { id: id.clone() });
+        request
+            .extensions_mut()
+            .insert(Principal("bob".to_string()));
+        let status = query.stream_result(request).await.err().unwrap();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+        let mut request = Request::new(CancelQueryRequest { id: id.clone() });
+        request
+            .extensions_mut()
+            .insert(Principal("bob".to_string()));
+        let status = query.cancel_query(request).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+        // a client without authentication doesn't see it either
+        let request = Request::new(QueryStatusRequest { id });
+        let status = query.get_query_status(request).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+    }
+
+    #[tokio::test]
+    async fn test_session_settings() {
+        let query = Query::new(Config::new().unwrap()).unwrap();
+        let settings = HashMap::from([("rapidash.batch.size".to_string(), "x".to_string())]);
+        let request = Request::new(CreateSessionRequest {
+            settings: settings.clone(),
+        });
+        let status = query.create_session(request).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+        let session_id = create_session(&query).await;
+        let request = Request::new(UpdateSessionRequest {
+            session_id: session_id.clone(),
+            settings,
+        });
+        let status = query.update_session(request).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+        let request = Request::new(UpdateSessionRequest {
+            session_id: session_id.clone(),
+            settings: HashMap::from([("rapidash.batch.size".to_string(), "10".to_string())]),
+        });
+        query.update_session(request).await.unwrap();
+        let session = query.sessions().get(&session_id).unwrap();
+        assert_eq!(session.config.default_batch_size(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_poll_and_fetch_result() {
+        let query = Query::new(Config::new().unwrap()).unwrap();
+        start_executor(&query).await;
+        let session_id = create_session(&query).await;
+        let request = Request::new(QueryRequest {
+            sql: "select 1 as a".to_string(),
+            session_id,
+        });
+        let id = query.query(request).await.unwrap().into_inner().id;
+
+        let status = wait_finished(&query, &id).await;
+        assert_eq!(status.state(), QueryState::Succeeded);
+        assert_eq!(status.progress, 1.0);
+
+        let request = Request::new(FetchResultRequest { id });
+        let result = query.fetch_result(request).await.unwrap().into_inner();
+        let batches = decode_batches(&result.data).unwrap();
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_query_reports_error() {
+        let query = Query::new(Config::new().unwrap()).unwrap();
+        let session_id = create_session(&query).await;
+        let request = Request::new(QueryRequest {
+            sql: "select * from missing_table".to_string(),
+            session_id,
+        });
+        let id = query.query(request).await.unwrap().into_inner().id;
+
+        let status = wait_finished(&query, &id).await;
+        assert_eq!(status.state(), QueryState::Failed);
+        assert!(status.error.contains("missing_table"));
+    }
+
+    #[tokio::test]
+    async fn test_quotas() {
+        let settings = HashMap::from([(ADMIN_PRINCIPALS.to_string(), "alice".to_string())]);
+        let query = Query::new(Config::with_settings(settings).unwrap()).unwrap();
+        let dir = std::env::temp_dir().join(format!("rapidash-quotas-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("t.csv");
+        // just over the MiB of the quota
+        let rows: String = (0..100_000).map(|i| format!("{},{}\n", i, i)).collect();
+        fs::write(&path, format!("a,b\n{}", rows)).unwrap();
+
+        let session_id = create_session(&query).await;
+        let sql = format!(
+            "create external table t (a bigint, b bigint) stored as csv with header row location '{}'",
+            path.display()
+        );
+        let request = Request::new(QueryRequest {
+            sql,
+            session_id: session_id.clone(),
+        });
+        let id = query.query(request).await.unwrap().into_inner().id;
+        assert_eq!(
+            wait_finished(&query, &id).await.state(),
+            QueryState::Succeeded
+        );
+
+        // only admins change the quotas
+        let quotas = QuotasMessage {
+            cluster_memory: 1,
+            ..Default::default()
+        };
+        let mut request = Request::new(UpdateQuotasRequest {
+            quotas: Some(quotas.clone()),
+        });
+        request
+            .extensions_mut()
+            .insert(Principal("bob".to_string()));
+        let status = query.update_quotas(request).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+
+        let mut request = Request::new(UpdateQuotasRequest {
+            quotas: Some(quotas.clone()),
+        });
+        request
+            .extensions_mut()
+            .insert(Principal("alice".to_string()));
+        query.update_quotas(request).await.unwrap();
+        let mut request = Request::new(GetQuotasRequest {});
+        request
+            .extensions_mut()
+            .insert(Principal("alice".to_string()));
+        let response = query.get_quotas(request).await.unwrap().into_inner();
+        assert_eq!(response.quotas, Some(quotas));
+
+        // admins need to authenticate
+        let request = Request::new(GetQuotasRequest {});
+        let status = query.get_quotas(request).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::Unauthenticated);
+
+        // scanning the table takes more than the MiB of the quota
+        let request = Request::new(QueryRequest {
+            sql: "select * from t".to_string(),
+            session_id,
+        });
+        let status = query.query(request).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::ResourceExhausted);
+        assert!(
+            status.message().contains("over the cluster quota"),
+            "{}",
+            status.message()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unknown_job() {
+        let query = Query::new(Config::new().unwrap()).unwrap();
+        let request = Request::new(QueryStatusRequest {
+            id: "unknown".to_string(),
+        });
+        let status = query.get_query_status(request).await.unwrap_err();
+
+        assert_eq!(status.code(), tonic::Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_query() {
+        let query = Query::new(Config::new().unwrap()).unwrap();
+        let session_id = create_session(&query).await;
+        let request = Request::new(QueryRequest {
+            sql: "select 1".to_string(),
+            session_id,
+        });
+        let id = query.query(request).await.unwrap().into_inner().id;
+
+        let request = Request::new(CancelQueryRequest { id: id.clone() });
+        let cancelled = query.cancel_query(request).await.unwrap().into_inner();
+        let status = wait_finished(&query, &id).await;
+        // the job may finish before it gets cancelled
+        if cancelled.cancelled {
+            assert_eq!(status.state(), QueryState::Cancelled);
+            let request = Request::new(FetchResultRequest { id: id.clone() });
+            let result = query.fetch_result(request).await.unwrap().into_inner();
+            assert_eq!(result.state(), QueryState::Cancelled);
+            assert!(result.data.is_empty());
+        } else {
+            assert_eq!(status.state(), QueryState::Succeeded);
+        }
+
+        // cancelling a finished job is a no-op
+        let request = Request::new(CancelQueryRequest { id });
+        let cancelled = query.cancel_query(request).await.unwrap().into_inner();
+        assert!(!cancelled.cancelled);
+    }
+
+    #[tokio::test]
+    async fn test_register_executor_and_heartbeat() {
+        let query = Query::new(Config::new().unwrap()).unwrap();
+        let request = Request::new(HeartbeatRequest {
+            executor_id: "executor".to_string(),
+            running_tasks: 0,
+            available_slots: 4,
+        });
+        let response = query.heartbeat(request).await.unwrap().into_inner();
+        assert!(response.reregister);
+
+        let registration = ExecutorRegistration {
+            id: "executor".to_string(),
+            host: "localhost".to_string(),
+            port: 51009,
+            task_slots: 4,
+        };
+        // an executor must handshake first
+        let status = query
+            .register_executor(Request::new(registration.clone()))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::FailedPrecondition);
+
+        let request = Request::new(handshake_request(PeerRole::Executor, "executor"));
+        query.handshake(request).await.unwrap();
+        let request = Request::new(registration);
+        let response = query.register_executor(request).await.unwrap().into_inner();
+        assert!(response.success);
+        assert_eq!(response.heartbeat_interval, 5);
+
+        let request = Request::new(HeartbeatRequest {
+            executor_id: "executor".to_string(),
+            running_tasks: 1,
+            available_slots: 3,
+        });
+        let response = query.heartbeat(request).await.unwrap().into_inner();
+        assert!(!response.reregister);
+        assert_eq!(query.executors().get("executor").unwrap().running_tasks, 1);
+    }
+
+    #[tokio::test]
+    async fn test_pull_policy() {
+        let config = Config::builder()
+            .set(SCHEDULING_POLICY, "pull")
+            .build()
+            .unwrap();
+        let query = Query::new(config).unwrap();
+        let request = Request::new(handshake_request(PeerRole::Executor, "executor"));
+        query.handshake(request).await.unwrap();
+        let request = Request::new(ExecutorRegistration {
+            id: "executor".to_string(),
+            host: "localhost".to_string(),
+            port: 51009,
+            task_slots: 4,
+        });
+        let response = query.register_executor(request).await.unwrap().into_inner();
+        assert!(response.pull);
+
+        // queued until the executor polls, nothing is launched
+        let task = TaskDefinition {
+            task_id: "task".to_string(),
+            partition: Some(PartitionId {
+                job_id: "job".to_string(),
+                stage_id: 0,
+                partition_id: 0,
+            }),
+            ..Default::default()
+        };
+        query.submit_tasks(vec![PendingTask::new(task)]).await;
+        assert_eq!(query.scheduler().pending(), 1);
+
+        let request = Request::new(PollWorkRequest {
+            executor_id: "executor".to_string(),
+            available_slots: 4,
+        });
+        let response = query.poll_work(request).await.unwrap().into_inner();
+        assert_eq!(response.tasks.len(), 1);
+        assert_eq!(query.tasks().get("task").unwrap().executor_id, "executor");
+        assert_eq!(query.scheduler().stats()[&SchedulingPolicy::Pull].tasks, 1);
+    }
+
+    #[tokio::test]
+    async fn test_handshake_rejects_incompatible() {
+        let query = Query::new(Config::new().unwrap()).unwrap();
+        let mut request = handshake_request(PeerRole::Executor, "old");
+        if let Some(capabilities) = request.capabilities.as_mut() {
+            capabilities.proto_revision = 0;
+        }
+        let status = query.handshake(Request::new(request)).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::FailedPrecondition);
+        assert!(matches!(
+            from_status(&status),
+            RapidashError::Incompatible(_)
+        ));
+        assert!(!query.executors().is_accepted("old"));
+    }
+
+    #[test]
+    fn test_rebatch() {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, false)]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![Arc::new(Int64Array::from((0..10).collect::<Vec<i64>>()))],
+        )
+        .unwrap();
+
+        let batches = rebatch(vec![batch.clone(), batch], 4);
+        let rows: Vec<usize> = batches.iter().map(|b| b.num_rows()).collect();
+        assert_eq!(rows, vec![4, 4, 2, 4, 4, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_stream_result() {
+        let config = Config::builder()
+            .set("rapidash.batch.size", "3")
+            .build()
+            .unwrap();
+        let query = Query::new(config).unwrap();
+        let scheduler = start_executor(&query).await;
+        let dir = std::env::temp_dir().join(format!("rapidash-stream-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("t.csv");
+        fs::write(&path, "a\n1\n2\n3\n4\n5\n").unwrap();
+
+        let session_id = create_session(&query).await;
+        let sql = format!(
+            "create external table t (a bigint) stored as csv with header row location '{}'",
+            path.display()
+        );
+        let request = Request::new(QueryRequest {
+            sql,
+            session_id: session_id.clone(),
+        });
+        let id = query.query(request).await.unwrap().into_inner().id;
+        wait_finished(&query, &id).await;
+        let request = Request::new(QueryRequest {
+            sql: "select a from t".to_string(),
+            session_id,
+        });
+        let id = query.query(request).await.unwrap().into_inner().id;
+        assert_eq!(
+            wait_finished(&query, &id).await.state(),
+            QueryState::Succeeded
+        );
+
+        let request = Request::new(FetchResultRequest { id });
+        let response = query.stream_result(request).await.unwrap().into_inner();
+        let batches: Vec<RecordBatch> = response
+            .map(|message| decode_batch(&message.unwrap().data).unwrap())
+            .collect()
+            .await;
+
+        assert!(batches.iter().all(|b| b.num_rows() <= 3));
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 5);
+
+        // the batch size of a session overrides the one of the scheduler
+        let request = Request::new(CreateSessionRequest {
+            settings: HashMap::from([(DEFAULT_BATCH_SIZE.to_string(), "2".to_string())]),
+        });
+        let session_id = query
+            .create_session(request)
+            .await
+            .unwrap()
+            .into_inner()
+            .session_id;
+        let request = Request::new(QueryRequest {
+            sql: "select a from t".to_string(),
+            session_id,
+        });
+        let id = query.query(request).await.unwrap().into_inner().id;
+        wait_finished(&query, &id).await;
+        // clients fetch the streamed batches over the network
+        let mut client = SchedulerProtoClient::connect(scheduler).await.unwrap();
+        let batches: Vec<RecordBatch> = client
+            .fetch_record_batches(id)
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        let rows: Vec<usize> = batches.iter().map(|b| b.num_rows()).collect();
+        assert!(
+            rows.len() > 1 && rows.iter().all(|rows| *rows <= 2),
+            "{:?}",
+            rows
+        );
+        let mut values: Vec<i64> = batches
+            .iter()
+            .flat_map(|b| {
+                let array = b.column(0).as_any().downcast_ref::<Int64Array>().unwrap();
+                array.values().to_vec()
+            })
+            .collect();
+        values.sort_unstable();
+        assert_eq!(values, vec![1, 2, 3, 4, 5]);
+
+        let status = client
+            .fetch_record_batches("unknown".to_string())
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(status.code(), tonic::Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_speculate_straggler() {
+        let config = Config::builder()
+            .set(SCHEDULING_POLICY, "pull")
+            .set(SHUFFLE_PARTITIONS, "4")
+            .set(SPECULATION, "true")
+            .set(SPECULATION_QUANTILE, "50")
+            .set(SPECULATION_MULTIPLIER, "100")
+            .set(RPC_RETRY_MAX, "0")
+            .build()
+            .unwrap();
+        let query = Query::new(config).unwrap();
+        query.start_speculation(Duration::from_millis(50));
+        start_executor(&query).await;
+        // an executor that takes a task and never runs it
+        let request = Request::new(handshake_request(PeerRole::Executor, "straggler"));
+        query.handshake(request).await.unwrap();
+        let request = Request::new(ExecutorRegistration {
+            id: "straggler".to_string(),
+            host: "127.0.0.1".to_string(),
+            port: 1,
+            task_slots: 1,
+        });
+        query.register_executor(request).await.unwrap();
+
+        let dir = std::env::temp_dir().join(format!("rapidash-straggler-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("t.csv");
+        fs::write(&path, "a,b\n1,2\n1,3\n2,4\n3,5\n4,6\n").unwrap();
+        let sql = format!(
+            "create external table t (a bigint, b bigint) stored as csv with header row location '{}'",
+            path.display()
+        );
+        let id = query.jobs().submit(Job::new(sql, None)).await.unwrap();
+        query.jobs().wait(&id).await.unwrap();
+
+        let sql = "select a, sum(b) from t group by a".to_string();
+        let id = query.jobs().submit(Job::new(sql, None)).await.unwrap();
+        // the scan is left to the executor, the straggler takes one of the
+        // 4 tasks reading it
+        while query
+            .jobs()
+            .get(&id)
+            .unwrap()
+            .stages
+            .first()
+            .is_none_or(|stage| stage.state != ExecutionState::Succeeded)
+        {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        let straggler = loop {
+            let request = Request::new(PollWorkRequest {
+                executor_id: "straggler".to_string(),
+                available_slots: 1,
+            });
+            let tasks = query.poll_work(request).await.unwrap().into_inner().tasks;
+            match tasks.into_iter().next() {
+                Some(task) => break task,
+                None => tokio::time::sleep(Duration::from_millis(1)).await,
+            }
+        };
+
+        // the copy on the executor computes the partition of the straggler
+        let job = query.jobs().wait(&id).await.unwrap();
+        assert_eq!(job.state(), JobState::Succeeded, "{:?}", job.error);
+        let result = query.jobs().result(&id).unwrap();
+        assert_eq!(
+            result.batches.iter().map(|b| b.num_rows()).sum::<usize>(),
+            4
+        );
+        assert_eq!(job.stages[1].tasks, vec![ExecutionState::Succeeded; 4]);
+        // the straggler is cancelled once the copy wins
+        assert_eq!(
+            query.tasks().get(&straggler.task_id).unwrap().state,
+            TaskState::Cancelled
+        );
+    }
+
+    #[tokio::test]
+    async fn test_executor_restart() {
+        let config = Config::builder()
+            .set(SCHEDULING_POLICY, "pull")
+            .set(SHUFFLE_PARTITIONS, "4")
+            .set(RPC_RETRY_MAX, "0")
+            .build()
+            .unwrap();
+        let query = Query::new(config).unwrap();
+        start_executor(&query).await;
+        // an executor that takes a task and restarts before running it
+        let request = Request::new(handshake_request(PeerRole::Executor, "restarted"));
+        query.handshake(request).await.unwrap();
+        let registration = ExecutorRegistration {
+            id: "restarted".to_string(),
+            host: "127.0.0.1".to_string(),
+            port: 1,
+            task_slots: 1,
+        };
+        query
+            .register_executor(Request::new(registration.clone()))
+            .await
+            .unwrap();
+
+        let dir = std::env::temp_dir().join(format!("rapidash-restart-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("t.csv");
+        fs::write(&path, "a,b\n1,2\n1,3\n2,4\n3,5\n4,6\n").unwrap();
+        let sql = format!(
+            "create external table t (a bigint, b bigint) stored as csv with header row location '{}'",
+            path.display()
+        );
+        let id = query.jobs().submit(Job::new(sql, None)).await.unwrap();
+        query.jobs().wait(&id).await.unwrap();
+
+        let sql = "select a, sum(b) from t group by a".to_string();
+        let id = query.jobs().submit(Job::new(sql, None)).await.unwrap();
+        let task = loop {
+            let request = Request::new(PollWorkRequest {
+                executor_id: "restarted".to_string(),
+                available_slots: 1,
+            });
+            let tasks = query.poll_work(request).await.unwrap().into_inner().tasks;
+            match tasks.into_iter().next() {
+                Some(task) => break task,
+                None => tokio::time::sleep(Duration::from_millis(1)).await,
+            }
+        };
+        query
+            .register_executor(Request::new(registration))
+            .await
+            .unwrap();
+
+        // the task it took runs again on the executor
+        let job = query.jobs().wait(&id).await.unwrap();
+        assert_eq!(job.state(), JobState::Succeeded, "{:?}", job.error);
+        let result = query.jobs().result(&id).unwrap();
+        assert_eq!(
+            result.batches.iter().map(|b| b.num_rows()).sum::<usize>(),
+            4
+        );
+        assert_eq!(
+            query.tasks().get(&task.task_id).unwrap().state,
+            TaskState::Cancelled
+        );
+    }
+}
@@ -0,0 +1,167 @@
+//! Order in which queued jobs start.
+//!
+//! At most `rapidash.scheduler.max.concurrent.jobs` jobs run at the same
+//! time. When one finishes, the queued job of highest priority starts, and
+//! among those the one of the user or session with the smallest weighted
+//! share of the running jobs. Queued jobs gain priority while they wait, so
+//! low priority jobs such as nightly backfills are delayed but never starved.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use common::config::Config;
+
+use crate::job::Job;
+
+/// What jobs share the scheduler fairly between, from `rapidash.scheduler.fair.share`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FairShare {
+    User,
+    Session,
+}
+
+/// Settings of the job queue
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JobQueue {
+    pub max_concurrent_jobs: usize,
+    pub fair_share: FairShare,
+    /// Wait adding one priority level, None disables aging
+    pub aging: Option<Duration>,
+}
+
+impl Default for JobQueue {
+    fn default() -> Self {
+        // infallible because the default settings are valid
+        Self::from_config(&Config::new().unwrap())
+    }
+}
+
+impl JobQueue {
+    pub fn from_config(config: &Config) -> Self {
+        // the values are validated by the configuration
+        let fair_share = match config.fair_share().as_str() {
+            "session" => FairShare::Session,
+            _ => FairShare::User,
+        };
+        let aging = match config.priority_aging() {
+            0 => None,
+            secs => Some(Duration::from_secs(secs as u64)),
+        };
+        Self {
+            // positive, a queue that never starts a job would hang every query
+            max_concurrent_jobs: config.max_concurrent_jobs(),
+            fair_share,
+            aging,
+        }
+    }
+
+    /// User or session the job shares the scheduler with
+    fn group<'a>(&self, job: &'a Job) -> &'a str {
+        let group = match self.fair_share {
+            FairShare::User => &job.principal,
+            FairShare::Session => &job.session_id,
+        };
+        group.as_deref().unwrap_or_default()
+    }
+
+    /// Priority of the job grown by its wait
+    pub fn priority(&self, job: &Job, now: DateTime<Utc>) -> usize {
+        let waited = (now - job.submit_time).to_std().unwrap_or_default();
+        let aged = match self.aging {
+            Some(aging) => (waited.as_secs() / aging.as_secs().max(1)) as usize,
+            None => 0,
+        };
+        job.priority.saturating_add(aged)
+    }
+
+    /// Queued job to start next, None when the running jobs use every slot
+    pub fn next<'a>(
+        &self,
+        queued: &[&'a Job],
+        running: &[&Job],
+        now: DateTime<Utc>,
+    ) -> Option<&'a Job> {
+        if running.len() >= self.max_concurrent_jobs {
+            return None;
+        }
+
+        let mut shares: HashMap<&str, usize> = HashMap::new();
+        for job in running {
+            *shares.entry(self.group(job)).or_default() += 1;
+        }
+        let running_of = |job: &Job| shares.get(self.group(job)).copied().unwrap_or_default();
+
+        queued.iter().copied().min_by(|a, b| {
+            let priority = self.priority(b, now).cmp(&self.priority(a, now));
+            // running / weight compared without dividing
+            let share = (running_of(a) * b.weight.max(1)).cmp(&(running_of(b) * a.weight.max(1)));
+            priority.then(share).then(a.submit_time.cmp(&b.submit_time))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(principal: &str, priority: usize, weight: usize, age: i64, now: DateTime<Utc>) -> Job {
+        let mut job = Job::new("select 1".to_string(), Some(principal.to_string()));
+        job.priority = priority;
+        job.weight = weight;
+        job.submit_time = now - chrono::Duration::seconds(age);
+        job
+    }
+
+    fn queue(max_concurrent_jobs: usize) -> JobQueue {
+        JobQueue {
+            max_concurrent_jobs,
+            fair_share: FairShare::User,
+            aging: Some(Duration::from_secs(60)),
+        }
+    }
+
+    #[test]
+    fn test_priority_first() {
+        let now = Utc::now();
+        let backfill = job("nightly", 0, 1, 10, now);
+        let research = job("alice", 5, 1, 0, now);
+        let next = queue(4).next(&[&backfill, &research], &[], now).unwrap();
+        assert_eq!(next.id, research.id);
+
+        // waiting 6 minutes ages the backfill above the research job
+        let backfill = job("nightly", 0, 1, 360, now);
+        let next = queue(4).next(&[&backfill, &research], &[], now).unwrap();
+        assert_eq!(next.id, backfill.id);
+    }
+
+    #[test]
+    fn test_weighted_fair_share() {
+        let now = Utc::now();
+        let running = [job("alice", 0, 1, 0, now), job("alice", 0, 1, 0, now)];
+        let running: Vec<&Job> = running.iter().collect();
+
+        // bob runs nothing, so bob's job goes first even though alice's waited longer
+        let alice = job("alice", 0, 1, 30, now);
+        let bob = job("bob", 0, 1, 0, now);
+        let next = queue(4).next(&[&alice, &bob], &running, now).unwrap();
+        assert_eq!(next.id, bob.id);
+
+        // with a weight of 3 alice's 2 running jobs are a smaller share than bob's one
+        let alice = job("alice", 0, 3, 30, now);
+        let bob_running = job("bob", 0, 1, 0, now);
+        let running = [running[0], running[1], &bob_running];
+        let next = queue(4).next(&[&alice, &bob], &running, now).unwrap();
+        assert_eq!(next.id, alice.id);
+    }
+
+    #[test]
+    fn test_max_concurrent_jobs() {
+        let now = Utc::now();
+        let running = job("alice", 0, 1, 0, now);
+        let queued = job("bob", 9, 1, 0, now);
+        assert!(queue(1).next(&[&queued], &[&running], now).is_none());
+        assert!(queue(2).next(&[&queued], &[&running], now).is_some());
+        assert!(queue(2).next(&[], &[&running], now).is_none());
+    }
+}
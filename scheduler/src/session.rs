@@ -0,0 +1,178 @@
+//! Client sessions and their settings.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use chrono::{DateTime, Utc};
+use common::config::Config;
+use common::error::{RapidashError, Result};
+use log::{info, warn};
+use uuid::Uuid;
+
+use crate::state::SchedulerState;
+
+/// A session opened by a client
+#[derive(Debug, Clone)]
+pub struct Session {
+    /// Uuid for the session
+    pub id: String,
+    /// Settings of the session, validated when they are set
+    pub config: Config,
+    /// Authenticated user that created the session, None without authentication
+    pub principal: Option<String>,
+    pub start_time: DateTime<Utc>,
+}
+
+/// Keep track of the open sessions
+#[derive(Clone, Default)]
+pub struct SessionManager {
+    sessions: Arc<RwLock<HashMap<String, Session>>>,
+    state: SchedulerState,
+}
+
+impl SessionManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_state(state: SchedulerState) -> Self {
+        Self {
+            state,
+            ..Default::default()
+        }
+    }
+
+    /// Reopen the sessions of the state after a restart, return their number
+    pub fn recover(&self) -> Result<usize> {
+        let recovered = self.state.sessions()?;
+        let count = recovered.len();
+        let mut sessions = self.sessions.write().unwrap();
+        for session in recovered {
+            sessions.insert(session.id.clone(), session);
+        }
+        Ok(count)
+    }
+
+    /// Open a session of the principal with the settings, return the session id
+    pub fn create(
+        &self,
+        settings: HashMap<String, String>,
+        principal: Option<String>,
+    ) -> Result<String> {
+        let config = Config::with_settings(settings)?;
+        let id = Uuid::new_v4().to_string();
+        info!(
+            "Session {} created by {}",
+            id,
+            principal.as_deref().unwrap_or("anonymous")
+        );
+        let session = Session {
+            id: id.clone(),
+            config,
+            principal,
+            start_time: Utc::now(),
+        };
+        self.state.save_session(&session)?;
+        self.sessions.write().unwrap().insert(id.clone(), session);
+
+        Ok(id)
+    }
+
+    /// Merge the settings into the settings of the session
+    pub fn update(&self, id: &str, settings: HashMap<String, String>) -> Result<()> {
+        let mut sessions = self.sessions.write().unwrap();
+        let session = sessions
+            .get_mut(id)
+            .ok_or_else(|| RapidashError::General(format!("Session {} not found", id)))?;
+
+        let mut merged = session.config.settings().clone();
+        merged.extend(settings);
+        // the session keeps its settings if the new ones are invalid
+        let mut updated = session.clone();
+        updated.config = Config::with_settings(merged)?;
+        self.state.save_session(&updated)?;
+        *session = updated;
+
+        Ok(())
+    }
+
+    /// Close a session, return false if it was not open
+    pub fn close(&self, id: &str) -> bool {
+        let closed = self.sessions.write().unwrap().remove(id).is_some();
+        if closed {
+            info!("Session {} closed", id);
+            if let Err(e) = self.state.remove_session(id) {
+                warn!("Failed to remove session {}: {}", id, e);
+            }
+        }
+        closed
+    }
+
+    pub fn get(&self, id: &str) -> Option<Session> {
+        self.sessions.read().unwrap().get(id).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::config::DEFAULT_BATCH_SIZE;
+
+    use super::*;
+
+    fn settings(key: &str, value: &str) -> HashMap<String, String> {
+        HashMap::from([(key.to_string(), value.to_string())])
+    }
+
+    #[test]
+    fn test_session_lifecycle() {
+        let sessions = SessionManager::new();
+        let id = sessions
+            .create(
+                settings(DEFAULT_BATCH_SIZE, "100"),
+                Some("alice".to_string()),
+            )
+            .unwrap();
+        assert_eq!(sessions.get(&id).unwrap().config.default_batch_size(), 100);
+        assert_eq!(sessions.get(&id).unwrap().principal.unwrap(), "alice");
+
+        sessions
+            .update(&id, settings(DEFAULT_BATCH_SIZE, "200"))
+            .unwrap();
+        assert_eq!(sessions.get(&id).unwrap().config.default_batch_size(), 200);
+
+        // invalid settings are rejected and the old ones kept
+        assert!(sessions
+            .update(&id, settings(DEFAULT_BATCH_SIZE, "x"))
+            .is_err());
+        assert_eq!(sessions.get(&id).unwrap().config.default_batch_size(), 200);
+
+        assert!(sessions.close(&id));
+        assert!(!sessions.close(&id));
+        assert!(sessions.get(&id).is_none());
+    }
+
+    #[test]
+    fn test_recover_sessions() {
+        let state = SchedulerState::default();
+        let sessions = SessionManager::with_state(state.clone());
+        let id = sessions
+            .create(settings(DEFAULT_BATCH_SIZE, "100"), None)
+            .unwrap();
+        let closed = sessions.create(HashMap::new(), None).unwrap();
+        sessions.close(&closed);
+
+        let sessions = SessionManager::with_state(state);
+        assert_eq!(sessions.recover().unwrap(), 1);
+        assert_eq!(sessions.get(&id).unwrap().config.default_batch_size(), 100);
+        assert!(sessions.get(&closed).is_none());
+    }
+
+    #[test]
+    fn test_invalid_settings() {
+        let sessions = SessionManager::new();
+        assert!(sessions
+            .create(settings(DEFAULT_BATCH_SIZE, "-1"), None)
+            .is_err());
+        assert!(sessions.update("unknown", HashMap::new()).is_err());
+    }
+}
@@ -0,0 +1,103 @@
+//! State store keeping the state in an embedded sled database.
+
+use std::path::Path;
+
+use common::error::{RapidashError, Result};
+use log::info;
+
+use super::{Keyspace, StateStore};
+
+/// State written to disk, a keyspace per sled tree
+#[derive(Debug, Clone)]
+pub struct DiskStateStore {
+    db: sled::Db,
+}
+
+impl DiskStateStore {
+    /// Open the database in the directory, created if it does not exist
+    pub fn open<P: AsRef<Path>>(dir: P) -> Result<Self> {
+        let dir = dir.as_ref();
+        let db = sled::open(dir).map_err(|e| {
+            RapidashError::General(format!("Failed to open state at {}: {}", dir.display(), e))
+        })?;
+        info!("State kept at {}", dir.display());
+        Ok(Self { db })
+    }
+
+    fn tree(&self, keyspace: Keyspace) -> Result<sled::Tree> {
+        self.db.open_tree(keyspace.name()).map_err(state_error)
+    }
+}
+
+fn state_error(e: sled::Error) -> RapidashError {
+    RapidashError::General(format!("State store error: {}", e))
+}
+
+impl StateStore for DiskStateStore {
+    fn get(&self, keyspace: Keyspace, key: &str) -> Result<Option<Vec<u8>>> {
+        let value = self.tree(keyspace)?.get(key).map_err(state_error)?;
+        Ok(value.map(|value| value.to_vec()))
+    }
+
+    fn put(&self, keyspace: Keyspace, key: &str, value: Vec<u8>) -> Result<()> {
+        let tree = self.tree(keyspace)?;
+        tree.insert(key, value).map_err(state_error)?;
+        // sled only syncs in background, a crash right after would lose the write
+        tree.flush().map_err(state_error)?;
+        Ok(())
+    }
+
+    fn delete(&self, keyspace: Keyspace, key: &str) -> Result<()> {
+        let tree = self.tree(keyspace)?;
+        tree.remove(key).map_err(state_error)?;
+        tree.flush().map_err(state_error)?;
+        Ok(())
+    }
+
+    fn scan(&self, keyspace: Keyspace) -> Result<Vec<(String, Vec<u8>)>> {
+        self.tree(keyspace)?
+            .iter()
+            .map(|entry| {
+                let (key, value) = entry.map_err(state_error)?;
+                Ok((String::from_utf8_lossy(&key).into_owned(), value.to_vec()))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[test]
+    fn test_reopen() {
+        let dir = std::env::temp_dir().join(format!("rapidash-state-{}", Uuid::new_v4()));
+        {
+            let store = DiskStateStore::open(&dir).unwrap();
+            store.put(Keyspace::Jobs, "b", vec![2]).unwrap();
+            store.put(Keyspace::Jobs, "a", vec![1]).unwrap();
+            store.put(Keyspace::Sessions, "a", vec![3]).unwrap();
+            store.delete(Keyspace::Sessions, "a").unwrap();
+        }
+
+        // a restarted scheduler finds what was written before, sled releases
+        // its lock once its background threads stop
+        let store = loop {
+            match DiskStateStore::open(&dir) {
+                Ok(store) => break store,
+                Err(e) if e.to_string().contains("lock") => {
+                    std::thread::sleep(std::time::Duration::from_millis(10))
+                }
+                Err(e) => panic!("{}", e),
+            }
+        };
+        assert_eq!(
+            store.scan(Keyspace::Jobs).unwrap(),
+            vec![("a".to_string(), vec![1]), ("b".to_string(), vec![2])]
+        );
+        assert_eq!(store.get(Keyspace::Sessions, "a").unwrap(), None);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,54 @@
+//! State store keeping the state in memory only.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::RwLock;
+
+use common::error::Result;
+
+use super::{Keyspace, StateStore};
+
+/// State of a scheduler that starts from scratch every time
+#[derive(Debug, Default)]
+pub struct MemoryStateStore {
+    keyspaces: RwLock<HashMap<Keyspace, BTreeMap<String, Vec<u8>>>>,
+}
+
+impl StateStore for MemoryStateStore {
+    fn get(&self, keyspace: Keyspace, key: &str) -> Result<Option<Vec<u8>>> {
+        let keyspaces = self.keyspaces.read().unwrap();
+        Ok(keyspaces
+            .get(&keyspace)
+            .and_then(|values| values.get(key))
+            .cloned())
+    }
+
+    fn put(&self, keyspace: Keyspace, key: &str, value: Vec<u8>) -> Result<()> {
+        self.keyspaces
+            .write()
+            .unwrap()
+            .entry(keyspace)
+            .or_default()
+            .insert(key.to_string(), value);
+        Ok(())
+    }
+
+    fn delete(&self, keyspace: Keyspace, key: &str) -> Result<()> {
+        if let Some(values) = self.keyspaces.write().unwrap().get_mut(&keyspace) {
+            values.remove(key);
+        }
+        Ok(())
+    }
+
+    fn scan(&self, keyspace: Keyspace) -> Result<Vec<(String, Vec<u8>)>> {
+        let keyspaces = self.keyspaces.read().unwrap();
+        Ok(keyspaces
+            .get(&keyspace)
+            .map(|values| {
+                values
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+}
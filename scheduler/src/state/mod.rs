@@ -0,0 +1,252 @@
+//! Scheduler state kept across restarts.
+//!
+//! Sessions, jobs with their stages, executor registrations and the ddl of
+//! the catalog are written to a `StateStore` as protobuf records. The memory
+//! store forgets everything with the process, the disk store lets a
+//! restarted scheduler recover them.
+
+mod disk;
+mod memory;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, TimeZone, Utc};
+use common::config::Config;
+use common::error::{RapidashError, Result};
+use prost::Message;
+use transmit::proto::{ExecutorRegistration, JobRecord, SessionRecord, TableRecord};
+
+pub use disk::DiskStateStore;
+pub use memory::MemoryStateStore;
+
+use crate::cluster::ExecutorMeta;
+use crate::job::Job;
+use crate::session::Session;
+
+/// Kinds of records, each store keeps them apart
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Keyspace {
+    Sessions,
+    Jobs,
+    Executors,
+    Catalog,
+}
+
+impl Keyspace {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Keyspace::Sessions => "sessions",
+            Keyspace::Jobs => "jobs",
+            Keyspace::Executors => "executors",
+            Keyspace::Catalog => "catalog",
+        }
+    }
+}
+
+/// Key value storage of the scheduler state, a write is durable once it returns
+pub trait StateStore: Send + Sync {
+    fn get(&self, keyspace: Keyspace, key: &str) -> Result<Option<Vec<u8>>>;
+
+    fn put(&self, keyspace: Keyspace, key: &str, value: Vec<u8>) -> Result<()>;
+
+    /// Delete a key, deleting a missing key is not an error
+    fn delete(&self, keyspace: Keyspace, key: &str) -> Result<()>;
+
+    /// All keys and values of the keyspace, ordered by key
+    fn scan(&self, keyspace: Keyspace) -> Result<Vec<(String, Vec<u8>)>>;
+}
+
+/// Typed access to the records of a state store
+#[derive(Clone)]
+pub struct SchedulerState {
+    store: Arc<dyn StateStore>,
+}
+
+impl Default for SchedulerState {
+    fn default() -> Self {
+        Self::new(Arc::new(MemoryStateStore::default()))
+    }
+}
+
+impl SchedulerState {
+    pub fn new(store: Arc<dyn StateStore>) -> Self {
+        Self { store }
+    }
+
+    /// State in the store of `rapidash.state.backend`
+    pub fn from_config(config: &Config) -> Result<Self> {
+        // the values are validated by the configuration
+        match config.state_backend().as_str() {
+            "sled" => Ok(Self::new(Arc::new(DiskStateStore::open(
+                config.state_dir(),
+            )?))),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn save_session(&self, session: &Session) -> Result<()> {
+        let record = SessionRecord {
+            id: session.id.clone(),
+            settings: session.config.settings().clone(),
+            principal: session.principal.clone().unwrap_or_default(),
+            start_time: session.start_time.timestamp_millis(),
+        };
+        self.put(Keyspace::Sessions, &session.id, &record)
+    }
+
+    pub fn remove_session(&self, id: &str) -> Result<()> {
+        self.store.delete(Keyspace::Sessions, id)
+    }
+
+    pub fn sessions(&self) -> Result<Vec<Session>> {
+        self.scan::<SessionRecord>(Keyspace::Sessions)?
+            .into_iter()
+            .map(|record| {
+                Ok(Session {
+                    id: record.id,
+                    config: Config::with_settings(record.settings)?,
+                    principal: optional(record.principal),
+                    start_time: time(record.start_time).unwrap_or_else(Utc::now),
+                })
+            })
+            .collect()
+    }
+
+    pub fn save_job(&self, job: &Job) -> Result<()> {
+        self.put(Keyspace::Jobs, &job.id, &job.to_record())
+    }
+
+    pub fn jobs(&self) -> Result<Vec<Job>> {
+        self.scan::<JobRecord>(Keyspace::Jobs)?
+            .into_iter()
+            .map(Job::from_record)
+            .collect()
+    }
+
+    pub fn save_executor(&self, meta: &ExecutorMeta) -> Result<()> {
+        let record = ExecutorRegistration {
+            id: meta.id.clone(),
+            host: meta.host.clone(),
+            port: meta.port as u32,
+            task_slots: meta.task_slots,
+        };
+        self.put(Keyspace::Executors, &meta.id, &record)
+    }
+
+    pub fn remove_executor(&self, id: &str) -> Result<()> {
+        self.store.delete(Keyspace::Executors, id)
+    }
+
+    pub fn executors(&self) -> Result<Vec<ExecutorMeta>> {
+        self.scan::<ExecutorRegistration>(Keyspace::Executors)?
+            .into_iter()
+            .map(|record| {
+                Ok(ExecutorMeta {
+                    port: u16::try_from(record.port).map_err(|_| {
+                        RapidashError::Internal(format!(
+                            "Invalid port {} of executor {}",
+                            record.port, record.id
+                        ))
+                    })?,
+                    id: record.id,
+                    host: record.host,
+                    task_slots: record.task_slots,
+                })
+            })
+            .collect()
+    }
+
+    /// Record the ddl creating a table or view of the catalog
+    pub fn save_table(&self, name: &str, sql: &str) -> Result<()> {
+        let record = TableRecord {
+            name: name.to_string(),
+            sql: sql.to_string(),
+        };
+        self.put(Keyspace::Catalog, name, &record)
+    }
+
+    pub fn remove_table(&self, name: &str) -> Result<()> {
+        self.store.delete(Keyspace::Catalog, name)
+    }
+
+    /// Ddl of the tables of the catalog, by table name
+    pub fn tables(&self) -> Result<HashMap<String, String>> {
+        Ok(self
+            .scan::<TableRecord>(Keyspace::Catalog)?
+            .into_iter()
+            .map(|record| (record.name, record.sql))
+            .collect())
+    }
+
+    fn put<M: Message>(&self, keyspace: Keyspace, key: &str, record: &M) -> Result<()> {
+        self.store.put(keyspace, key, record.encode_to_vec())
+    }
+
+    fn scan<M: Message + Default>(&self, keyspace: Keyspace) -> Result<Vec<M>> {
+        self.store
+            .scan(keyspace)?
+            .into_iter()
+            .map(|(key, value)| {
+                M::decode(value.as_slice()).map_err(|e| {
+                    RapidashError::Internal(format!(
+                        "Invalid {} record {}: {}",
+                        keyspace.name(),
+                        key,
+                        e
+                    ))
+                })
+            })
+            .collect()
+    }
+}
+
+/// Empty strings of records stand for unset optional strings
+pub(crate) fn optional(value: String) -> Option<String> {
+    Some(value).filter(|value| !value.is_empty())
+}
+
+/// Time of a record, 0 stands for unset
+pub(crate) fn time(millis: i64) -> Option<DateTime<Utc>> {
+    match millis {
+        0 => None,
+        millis => Utc.timestamp_millis_opt(millis).single(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::config::JOB_NAME;
+
+    use super::*;
+
+    #[test]
+    fn test_records_round_trip() {
+        let state = SchedulerState::default();
+        let session = Session {
+            id: "session".to_string(),
+            config: Config::builder().set(JOB_NAME, "daily").build().unwrap(),
+            principal: None,
+            start_time: Utc.timestamp_millis_opt(1_000).unwrap(),
+        };
+        state.save_session(&session).unwrap();
+        let sessions = state.sessions().unwrap();
+        assert_eq!(sessions[0].config, session.config);
+        assert_eq!(sessions[0].principal, None);
+        assert_eq!(sessions[0].start_time, session.start_time);
+        state.remove_session("session").unwrap();
+        assert!(state.sessions().unwrap().is_empty());
+
+        let meta = ExecutorMeta {
+            id: "executor".to_string(),
+            host: "localhost".to_string(),
+            port: 51009,
+            task_slots: 4,
+        };
+        state.save_executor(&meta).unwrap();
+        assert_eq!(state.executors().unwrap(), vec![meta]);
+
+        state.save_table("t", "create view t as select 1").unwrap();
+        assert_eq!(state.tables().unwrap()["t"], "create view t as select 1");
+    }
+}
@@ -0,0 +1,266 @@
+//! Tasks launched on executors.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use common::error::Result;
+use log::{info, warn};
+use transmit::compressed;
+use transmit::proto::executor_proto_client::ExecutorProtoClient;
+use transmit::proto::{
+    task_status, CancelTaskRequest, LaunchTaskRequest, PartitionId, ShuffleLocation,
+    TaskDefinition, TaskMetrics, TaskStatus,
+};
+
+use crate::cluster::{ExecutorManager, ExecutorMeta};
+use transmit::rpc::ConnectionManager;
+
+/// State of a task
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskState {
+    Running,
+    Failed(String),
+    /// Output partitions written by the task
+    Succeeded(Vec<ShuffleLocation>),
+    Cancelled,
+    /// Was running on an executor that died, it ran again elsewhere
+    Lost,
+}
+
+/// A task launched on an executor
+#[derive(Debug, Clone)]
+pub struct TaskInfo {
+    pub task_id: String,
+    pub partition: PartitionId,
+    pub attempt: u32,
+    pub executor_id: String,
+    pub state: TaskState,
+    pub metrics: Option<TaskMetrics>,
+}
+
+/// Push tasks to executors and keep track of their status
+#[derive(Clone, Default)]
+pub struct TaskManager {
+    tasks: Arc<RwLock<HashMap<String, TaskInfo>>>,
+    /// Connections to executors
+    connections: ConnectionManager,
+}
+
+impl TaskManager {
+    pub fn new(connections: ConnectionManager) -> Self {
+        Self {
+            connections,
+            ..Default::default()
+        }
+    }
+
+    /// Record tasks as running on an executor, launched or taken by a poll
+    pub fn track(&self, executor_id: &str, tasks: &[TaskDefinition]) {
+        let mut infos = self.tasks.write().unwrap();
+        for task in tasks {
+            infos.insert(
+                task.task_id.clone(),
+                TaskInfo {
+                    task_id: task.task_id.clone(),
+                    partition: task.partition.clone().unwrap_or_default(),
+                    attempt: task.attempt,
+                    executor_id: executor_id.to_string(),
+                    state: TaskState::Running,
+                    metrics: None,
+                },
+            );
+        }
+    }
+
+    /// Launch tasks on an executor
+    pub async fn launch(&self, executor: &ExecutorMeta, tasks: Vec<TaskDefinition>) -> Result<()> {
+        self.track(&executor.id, &tasks);
+
+        // launching twice would run the tasks twice, so it is not retried once sent
+        let url = self.connections.url(&executor.host, executor.port);
+        let compression = self.connections.compression();
+        let token = self.connections.token();
+        self.connections
+            .call(&url, false, |channel| {
+                let request = LaunchTaskRequest {
+                    tasks: tasks.clone(),
+                };
+                let mut client = compressed!(
+                    ExecutorProtoClient::with_interceptor(channel, token.clone()),
+                    compression
+                );
+                async move { client.launch_task(request).await }
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Record the statuses reported by an executor
+    pub fn update(&self, statuses: Vec<TaskStatus>) {
+        let mut tasks = self.tasks.write().unwrap();
+        for status in statuses {
+            let task = match tasks.get_mut(&status.task_id) {
+                Some(task) => task,
+                None => {
+                    warn!("Status of unknown task {}", status.task_id);
+                    continue;
+                }
+            };
+            // a cancelled or lost task may still report when it finishes
+            if matches!(task.state, TaskState::Cancelled | TaskState::Lost) {
+                continue;
+            }
+            match status.status {
+                Some(task_status::Status::Running(_)) => task.state = TaskState::Running,
+                Some(task_status::Status::Failed(failed)) => {
+                    task.state = TaskState::Failed(failed.error)
+                }
+                Some(task_status::Status::Successful(successful)) => {
+                    task.state = TaskState::Succeeded(successful.locations);
+                    task.metrics = successful.metrics;
+                }
+                None => {}
+            }
+        }
+    }
+
+    pub fn get(&self, task_id: &str) -> Option<TaskInfo> {
+        self.tasks.read().unwrap().get(task_id).cloned()
+    }
+
+    /// All tasks of a job
+    pub fn job_tasks(&self, job_id: &str) -> Vec<TaskInfo> {
+        self.tasks
+            .read()
+            .unwrap()
+            .values()
+            .filter(|task| task.partition.job_id == job_id)
+            .cloned()
+            .collect()
+    }
+
+    /// Stop running tasks, e.g. the slower copy of a speculated task
+    pub async fn cancel_tasks(&self, task_ids: &[String], executors: &ExecutorManager) {
+        let mut running: HashMap<String, Vec<String>> = HashMap::new();
+        {
+            let mut tasks = self.tasks.write().unwrap();
+            for task_id in task_ids {
+                if let Some(task) = tasks.get_mut(task_id) {
+                    if task.state == TaskState::Running {
+                        task.state = TaskState::Cancelled;
+                        running
+                            .entry(task.executor_id.clone())
+                            .or_default()
+                            .push(task.task_id.clone());
+                    }
+                }
+            }
+        }
+
+        for (executor_id, task_ids) in running {
+            let executor = match executors.get(&executor_id) {
+                Some(executor) => executor,
+                None => continue,
+            };
+            let request = CancelTaskRequest {
+                task_ids,
+                clean_job_ids: vec![],
+            };
+            if let Err(e) = self.cancel(&executor.meta, request).await {
+                warn!("Failed to cancel tasks on executor {}: {}", executor_id, e);
+            }
+        }
+    }
+
+    /// Mark the running tasks of an executor that died as lost, return their ids
+    pub fn executor_lost(&self, executor_id: &str) -> Vec<String> {
+        let mut tasks = self.tasks.write().unwrap();
+        let mut lost = vec![];
+        for task in tasks.values_mut() {
+            if task.executor_id == executor_id && task.state == TaskState::Running {
+                task.state = TaskState::Lost;
+                lost.push(task.task_id.clone());
+            }
+        }
+        lost
+    }
+
+    /// Stop the lost tasks still running on an executor that recovered
+    pub async fn cancel_lost(&self, executor: &ExecutorMeta) {
+        let task_ids: Vec<String> = {
+            let mut tasks = self.tasks.write().unwrap();
+            tasks
+                .values_mut()
+                .filter(|task| task.executor_id == executor.id && task.state == TaskState::Lost)
+                .map(|task| {
+                    task.state = TaskState::Cancelled;
+                    task.task_id.clone()
+                })
+                .collect()
+        };
+        if task_ids.is_empty() {
+            return;
+        }
+        let request = CancelTaskRequest {
+            task_ids,
+            clean_job_ids: vec![],
+        };
+        if let Err(e) = self.cancel(executor, request).await {
+            warn!(
+                "Failed to cancel lost tasks on executor {}: {}",
+                executor.id, e
+            );
+        }
+    }
+
+    /// Stop the running tasks of a job and remove its shuffle data on every executor
+    pub async fn cancel_job(&self, job_id: &str, executors: &ExecutorManager) {
+        let mut running: HashMap<String, Vec<String>> = HashMap::new();
+        {
+            let mut tasks = self.tasks.write().unwrap();
+            for task in tasks.values_mut() {
+                if task.partition.job_id == job_id && task.state == TaskState::Running {
+                    task.state = TaskState::Cancelled;
+                    running
+                        .entry(task.executor_id.clone())
+                        .or_default()
+                        .push(task.task_id.clone());
+                }
+            }
+        }
+        // cancelled tasks never report, so their slots are freed now
+        let cancelled: Vec<String> = running.values().flatten().cloned().collect();
+        executors.release(&cancelled);
+
+        for executor in executors.executors() {
+            let request = CancelTaskRequest {
+                task_ids: running.remove(&executor.meta.id).unwrap_or_default(),
+                clean_job_ids: vec![job_id.to_string()],
+            };
+            if let Err(e) = self.cancel(&executor.meta, request).await {
+                warn!(
+                    "Failed to cancel job {} on executor {}: {}",
+                    job_id, executor.meta.id, e
+                );
+            }
+        }
+        info!("Tasks of job {} cancelled", job_id);
+    }
+
+    async fn cancel(&self, executor: &ExecutorMeta, request: CancelTaskRequest) -> Result<()> {
+        let url = self.connections.url(&executor.host, executor.port);
+        let compression = self.connections.compression();
+        let token = self.connections.token();
+        self.connections
+            .call(&url, true, |channel| {
+                let request = request.clone();
+                let mut client = compressed!(
+                    ExecutorProtoClient::with_interceptor(channel, token.clone()),
+                    compression
+                );
+                async move { client.cancel_task(request).await }
+            })
+            .await?;
+        Ok(())
+    }
+}
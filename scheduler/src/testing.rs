@@ -0,0 +1,41 @@
+//! A scheduler with an executor in the same process, for the tests running jobs
+
+use std::time::Duration;
+
+use common::config::Config;
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::transport::Server;
+use transmit::proto::scheduler_proto_server::SchedulerProtoServer;
+use uuid::Uuid;
+
+use crate::query::Query;
+
+/// Serve the query on a free port and start an executor connected to it,
+/// return the url of the scheduler once the executor registered
+pub async fn start_executor(query: &Query) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let scheduler = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(
+        Server::builder()
+            .add_service(SchedulerProtoServer::new(query.clone()))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let work_dir = std::env::temp_dir().join(format!("rapidash-executor-{}", Uuid::new_v4()));
+    tokio::spawn(executor::server::serve(
+        Config::new().unwrap(),
+        scheduler.clone(),
+        listener,
+        work_dir.to_string_lossy().to_string(),
+    ));
+
+    for _ in 0..100 {
+        if !query.executors().executors().is_empty() {
+            return scheduler;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("The executor did not register");
+}
@@ -0,0 +1,161 @@
+//! Flight sql tests against a local scheduler
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use arrow_flight::flight_service_client::FlightServiceClient;
+use arrow_flight::flight_service_server::FlightServiceServer;
+use arrow_flight::sql::{
+    ActionCreatePreparedStatementRequest, ActionCreatePreparedStatementResult, CommandGetCatalogs,
+    CommandGetTables, CommandPreparedStatementQuery, CommandStatementQuery, ProstMessageExt,
+};
+use arrow_flight::utils::flight_data_to_arrow_batch;
+use arrow_flight::{Action, FlightDescriptor, Ticket};
+use common::config::Config;
+use datafusion::arrow::array::StringArray;
+use datafusion::arrow::datatypes::Schema;
+use datafusion::arrow::record_batch::RecordBatch;
+use executor::server::serve;
+use prost::Message;
+use scheduler::flight_sql::FlightSqlServer;
+use scheduler::query::Query;
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::transport::{Channel, Server};
+use transmit::proto::scheduler_proto_server::SchedulerProtoServer;
+use uuid::Uuid;
+
+/// Start a scheduler serving flight sql on a free port with an executor
+/// running its jobs, and connect to it
+async fn client() -> FlightServiceClient<Channel> {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let query = Query::new(Config::new().unwrap()).unwrap();
+    let server = FlightSqlServer::new(query.jobs().clone(), 1024);
+    tokio::spawn(
+        Server::builder()
+            .add_service(SchedulerProtoServer::new(query.clone()))
+            .add_service(FlightServiceServer::new(server))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let work_dir = std::env::temp_dir().join(format!("rapidash-executor-{}", Uuid::new_v4()));
+    tokio::spawn(serve(
+        Config::new().unwrap(),
+        format!("http://{}", addr),
+        listener,
+        work_dir.to_string_lossy().to_string(),
+    ));
+    while query.executors().executors().is_empty() {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    FlightServiceClient::connect(format!("http://{}", addr))
+        .await
+        .unwrap()
+}
+
+/// Get the flight info of a command and fetch its single endpoint
+async fn fetch<T: ProstMessageExt>(
+    client: &mut FlightServiceClient<Channel>,
+    command: T,
+) -> Vec<RecordBatch> {
+    let descriptor = FlightDescriptor::new_cmd(command.as_any().encode_to_vec());
+    let info = client
+        .get_flight_info(descriptor)
+        .await
+        .unwrap()
+        .into_inner();
+    let ticket: Ticket = info.endpoint[0].ticket.clone().unwrap();
+
+    let mut stream = client.do_get(ticket).await.unwrap().into_inner();
+    let schema = Arc::new(Schema::try_from(&stream.message().await.unwrap().unwrap()).unwrap());
+    let mut batches = vec![];
+    while let Some(data) = stream.message().await.unwrap() {
+        batches.push(flight_data_to_arrow_batch(&data, schema.clone(), &HashMap::new()).unwrap());
+    }
+    batches
+}
+
+fn strings(batch: &RecordBatch, column: usize) -> Vec<String> {
+    let array = batch
+        .column(column)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    array.iter().map(|v| v.unwrap().to_string()).collect()
+}
+
+#[tokio::test]
+async fn test_statement_query() {
+    let mut client = client().await;
+    let batches = fetch(
+        &mut client,
+        CommandStatementQuery {
+            query: "select 1 as a, 'x' as b".to_string(),
+        },
+    )
+    .await;
+
+    assert_eq!(batches.len(), 1);
+    assert_eq!(batches[0].num_rows(), 1);
+    assert_eq!(batches[0].schema().field(0).name(), "a");
+    assert_eq!(strings(&batches[0], 1), vec!["x"]);
+}
+
+#[tokio::test]
+async fn test_prepared_statement() {
+    let mut client = client().await;
+    let action = Action {
+        r#type: "CreatePreparedStatement".to_string(),
+        body: ActionCreatePreparedStatementRequest {
+            query: "select 2 as b".to_string(),
+        }
+        .as_any()
+        .encode_to_vec(),
+    };
+    let mut results = client.do_action(action).await.unwrap().into_inner();
+    let result = results.message().await.unwrap().unwrap();
+    let any = prost_types::Any::decode(result.body.as_slice()).unwrap();
+    let prepared = ActionCreatePreparedStatementResult::decode(any.value.as_slice()).unwrap();
+
+    let batches = fetch(
+        &mut client,
+        CommandPreparedStatementQuery {
+            prepared_statement_handle: prepared.prepared_statement_handle,
+        },
+    )
+    .await;
+    assert_eq!(batches[0].num_rows(), 1);
+    assert_eq!(batches[0].schema().field(0).name(), "b");
+}
+
+#[tokio::test]
+async fn test_catalogs_and_tables() {
+    let mut client = client().await;
+    fetch(
+        &mut client,
+        CommandStatementQuery {
+            query: "create table t as select 1 as a".to_string(),
+        },
+    )
+    .await;
+
+    let catalogs = fetch(&mut client, CommandGetCatalogs {}).await;
+    assert_eq!(strings(&catalogs[0], 0), vec!["datafusion"]);
+
+    let tables = fetch(
+        &mut client,
+        CommandGetTables {
+            catalog: None,
+            db_schema_filter_pattern: Some("pub%".to_string()),
+            table_name_filter_pattern: None,
+            table_types: vec![],
+            include_schema: false,
+        },
+    )
+    .await;
+    assert_eq!(strings(&tables[0], 2), vec!["t"]);
+    assert_eq!(strings(&tables[0], 3), vec!["TABLE"]);
+}
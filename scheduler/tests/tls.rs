@@ -0,0 +1,130 @@
+//! TLS tests against a local scheduler with certificates generated at test time
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use common::config::Config;
+use rcgen::{
+    BasicConstraints, Certificate, CertificateParams, DnType, ExtendedKeyUsagePurpose, IsCa,
+};
+use scheduler::query::Query;
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::transport::Server;
+use transmit::proto::scheduler_proto_client::SchedulerProtoClient;
+use transmit::proto::scheduler_proto_server::SchedulerProtoServer;
+use transmit::proto::CreateSessionRequest;
+use transmit::tls::TlsConfig;
+use uuid::Uuid;
+
+fn ca() -> Certificate {
+    let mut params = CertificateParams::new(vec![]);
+    params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    params
+        .distinguished_name
+        .push(DnType::CommonName, "rapidash test ca");
+    Certificate::from_params(params).unwrap()
+}
+
+/// Write a certificate signed by the CA and its key to the directory
+fn write_signed(dir: &Path, name: &str, ca: &Certificate, usage: ExtendedKeyUsagePurpose) {
+    let mut params = CertificateParams::new(vec!["localhost".to_string()]);
+    params.extended_key_usages = vec![usage];
+    let cert = Certificate::from_params(params).unwrap();
+    fs::write(
+        dir.join(format!("{}.pem", name)),
+        cert.serialize_pem_with_signer(ca).unwrap(),
+    )
+    .unwrap();
+    fs::write(
+        dir.join(format!("{}.key", name)),
+        cert.serialize_private_key_pem(),
+    )
+    .unwrap();
+}
+
+/// Certificates of a CA, a server and a client in a fresh directory
+fn certificates() -> String {
+    let dir = std::env::temp_dir().join(format!("rapidash-tls-{}", Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    let ca = ca();
+    fs::write(dir.join("ca.pem"), ca.serialize_pem().unwrap()).unwrap();
+    write_signed(&dir, "server", &ca, ExtendedKeyUsagePurpose::ServerAuth);
+    write_signed(&dir, "client", &ca, ExtendedKeyUsagePurpose::ClientAuth);
+    dir.to_string_lossy().to_string()
+}
+
+fn tls(dir: &str, name: &str, client_auth: bool) -> TlsConfig {
+    TlsConfig {
+        cert: Some(format!("{}/{}.pem", dir, name)),
+        key: Some(format!("{}/{}.key", dir, name)),
+        ca: Some(format!("{}/ca.pem", dir)),
+        client_auth,
+        domain: None,
+    }
+}
+
+/// Start a scheduler with TLS on a free port, return its port
+async fn serve(tls: TlsConfig) -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let query = Query::new(Config::new().unwrap()).unwrap();
+    tokio::spawn(
+        Server::builder()
+            .tls_config(tls.server_tls().unwrap().unwrap())
+            .unwrap()
+            .add_service(SchedulerProtoServer::new(query))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+    port
+}
+
+/// Create a session over a new connection, the error message on failure
+async fn create_session(tls: &TlsConfig, port: u16) -> Result<String, String> {
+    let channel = tls
+        .endpoint(tls.url("localhost", port))
+        .map_err(|e| e.to_string())?
+        .connect()
+        .await
+        .map_err(|e| e.to_string())?;
+    let request = CreateSessionRequest {
+        settings: HashMap::new(),
+    };
+    SchedulerProtoClient::new(channel)
+        .create_session(request)
+        .await
+        .map(|response| response.into_inner().session_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tokio::test]
+async fn test_tls() {
+    let dir = certificates();
+    let port = serve(tls(&dir, "server", false)).await;
+
+    // clients only need the CA
+    let client = TlsConfig {
+        ca: Some(format!("{}/ca.pem", dir)),
+        ..Default::default()
+    };
+    assert!(create_session(&client, port).await.is_ok());
+    // a plaintext client can't talk to a TLS server
+    assert!(create_session(&TlsConfig::default(), port).await.is_err());
+}
+
+#[tokio::test]
+async fn test_mutual_tls() {
+    let dir = certificates();
+    let port = serve(tls(&dir, "server", true)).await;
+
+    assert!(create_session(&tls(&dir, "client", true), port)
+        .await
+        .is_ok());
+
+    // without a client certificate the handshake fails
+    let client = TlsConfig {
+        ca: Some(format!("{}/ca.pem", dir)),
+        ..Default::default()
+    };
+    assert!(create_session(&client, port).await.is_err());
+}
@@ -1,9 +1,12 @@
 //! build proto to rust
 fn main() -> Result<(), String> {
     tonic_build::configure()
-        .build_server(false)
+        .build_server(true)
         .out_dir("src/generated")
-        .compile(&["src/proto/rapidash.proto"], &["proto"])
+        .compile(
+            &["src/proto/rapidash.proto", "src/proto/plan.proto"],
+            &["src/proto"],
+        )
         .unwrap();
     Ok(())
 }
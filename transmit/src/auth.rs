@@ -0,0 +1,319 @@
+//! Credentials attached by clients and their authentication by servers.
+//!
+//! Clients send `authorization: Bearer <token>` metadata, the server
+//! interceptor checks the token with every configured authenticator and
+//! records the authenticated principal in the request extensions.
+
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use common::config::Config;
+use common::error::{RapidashError, Result};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use subtle::ConstantTimeEq;
+use tonic::metadata::{Ascii, MetadataValue};
+use tonic::service::interceptor::InterceptedService;
+use tonic::service::Interceptor;
+use tonic::transport::Channel;
+use tonic::{Request, Status};
+
+/// Metadata key of the credentials
+pub const AUTHORIZATION: &str = "authorization";
+
+/// Channel of a client attaching its token to every request
+pub type AuthenticatedChannel = InterceptedService<Channel, TokenInterceptor>;
+
+/// Attach a bearer token to every request, or nothing without a token
+#[derive(Debug, Clone, Default)]
+pub struct TokenInterceptor {
+    authorization: Option<MetadataValue<Ascii>>,
+}
+
+impl TokenInterceptor {
+    pub fn new(token: Option<&str>) -> Result<Self> {
+        let authorization = token
+            .map(|token| format!("Bearer {}", token).parse())
+            .transpose()
+            .map_err(|_| RapidashError::General("Invalid characters in token".to_string()))?;
+        Ok(Self { authorization })
+    }
+
+    /// Interceptor with the token of the `rapidash.auth.token` setting
+    pub fn from_config(config: &Config) -> Result<Self> {
+        Self::new(config.auth_token().as_deref())
+    }
+}
+
+impl Interceptor for TokenInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> std::result::Result<Request<()>, Status> {
+        if let Some(authorization) = &self.authorization {
+            request
+                .metadata_mut()
+                .insert(AUTHORIZATION, authorization.clone());
+        }
+        Ok(request)
+    }
+}
+
+/// Token of the bearer credentials of a request
+pub fn bearer_token<T>(request: &Request<T>) -> Option<&str> {
+    request
+        .metadata()
+        .get(AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+}
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// User a request is authenticated as
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Principal(pub String);
+
+/// Principal of an authenticated request, None without authentication
+pub fn principal<T>(request: &Request<T>) -> Option<String> {
+    request
+        .extensions()
+        .get::<Principal>()
+        .map(|principal| principal.0.clone())
+}
+
+/// Check the token of a request
+pub trait Authenticator: Send + Sync {
+    /// Principal the token belongs to, None if the token is not valid
+    fn authenticate(&self, token: &str) -> Option<String>;
+}
+
+/// Tokens listed in a file, one `principal token` pair per line
+#[derive(Debug, Clone, Default)]
+pub struct StaticTokens {
+    /// Principal of every token
+    tokens: HashMap<String, String>,
+}
+
+impl StaticTokens {
+    pub fn new(tokens: HashMap<String, String>) -> Self {
+        Self { tokens }
+    }
+
+    /// Read the tokens file, empty lines and lines starting with `#` are skipped
+    pub fn from_file(path: &str) -> Result<Self> {
+        let mut tokens = HashMap::new();
+        for line in fs::read_to_string(path)?.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line.split_whitespace().collect::<Vec<_>>()[..] {
+                [principal, token] => {
+                    tokens.insert(token.to_string(), principal.to_string());
+                }
+                _ => {
+                    return Err(RapidashError::General(format!(
+                        "Invalid line in tokens file {}, expected `principal token`",
+                        path
+                    )))
+                }
+            }
+        }
+        Ok(Self::new(tokens))
+    }
+}
+
+impl Authenticator for StaticTokens {
+    fn authenticate(&self, token: &str) -> Option<String> {
+        // constant time comparison with every token, a lookup would leak
+        // how much of a token matches
+        let mut found = None;
+        for (candidate, principal) in &self.tokens {
+            if bool::from(candidate.as_bytes().ct_eq(token.as_bytes())) {
+                found = Some(principal.clone());
+            }
+        }
+        found
+    }
+}
+
+/// Tokens `principal.expiry.signature` signed with a shared secret, the
+/// expiry is in seconds since unix epoch and the signature is the hex encoded
+/// HMAC-SHA256 of `principal.expiry`
+#[derive(Clone)]
+pub struct HmacTokens {
+    secret: Vec<u8>,
+}
+
+impl HmacTokens {
+    pub fn new(secret: Vec<u8>) -> Self {
+        Self { secret }
+    }
+
+    pub fn from_file(path: &str) -> Result<Self> {
+        let secret = fs::read_to_string(path)?;
+        Ok(Self::new(secret.trim().as_bytes().to_vec()))
+    }
+
+    /// Issue a token for the principal, valid until the expiry
+    pub fn sign(&self, principal: &str, expiry: u64) -> Result<String> {
+        if principal.is_empty() || principal.contains('.') {
+            return Err(RapidashError::General(format!(
+                "Invalid principal '{}'",
+                principal
+            )));
+        }
+        let payload = format!("{}.{}", principal, expiry);
+        let signature = hex::encode(self.mac(&payload).finalize().into_bytes());
+        Ok(format!("{}.{}", payload, signature))
+    }
+
+    fn mac(&self, payload: &str) -> HmacSha256 {
+        // infallible because HMAC takes keys of any size
+        let mut mac = HmacSha256::new_from_slice(&self.secret).unwrap();
+        mac.update(payload.as_bytes());
+        mac
+    }
+}
+
+impl Authenticator for HmacTokens {
+    fn authenticate(&self, token: &str) -> Option<String> {
+        let (payload, signature) = token.rsplit_once('.')?;
+        let (principal, expiry) = payload.split_once('.')?;
+        let signature = hex::decode(signature).ok()?;
+        // constant time comparison of the signature
+        self.mac(payload).verify_slice(&signature).ok()?;
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+        if expiry.parse::<u64>().ok()? < now {
+            return None;
+        }
+        Some(principal.to_string())
+    }
+}
+
+/// Server interceptor rejecting requests without a valid token, every request
+/// is accepted when no authenticator is configured
+#[derive(Clone, Default)]
+pub struct AuthInterceptor {
+    authenticators: Arc<Vec<Box<dyn Authenticator>>>,
+}
+
+impl AuthInterceptor {
+    pub fn new(authenticators: Vec<Box<dyn Authenticator>>) -> Self {
+        Self {
+            authenticators: Arc::new(authenticators),
+        }
+    }
+
+    /// Authenticators of the `rapidash.auth.*` settings
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let mut authenticators: Vec<Box<dyn Authenticator>> = vec![];
+        if let Some(path) = config.auth_token_file() {
+            authenticators.push(Box::new(StaticTokens::from_file(&path)?));
+        }
+        if let Some(path) = config.auth_hmac_secret_file() {
+            authenticators.push(Box::new(HmacTokens::from_file(&path)?));
+        }
+        Ok(Self::new(authenticators))
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.authenticators.is_empty()
+    }
+
+    /// Whether the `rapidash.auth.*` settings enable authentication, without
+    /// reading their files
+    pub fn is_configured(config: &Config) -> bool {
+        config.auth_token_file().is_some() || config.auth_hmac_secret_file().is_some()
+    }
+}
+
+impl Interceptor for AuthInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> std::result::Result<Request<()>, Status> {
+        if !self.is_enabled() {
+            return Ok(request);
+        }
+
+        let token = bearer_token(&request)
+            .ok_or_else(|| Status::unauthenticated("Missing bearer token"))?;
+        let principal = self
+            .authenticators
+            .iter()
+            .find_map(|authenticator| authenticator.authenticate(token))
+            .ok_or_else(|| Status::unauthenticated("Invalid token"))?;
+        request.extensions_mut().insert(Principal(principal));
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_interceptor() {
+        let mut interceptor = TokenInterceptor::new(Some("secret")).unwrap();
+        let request = interceptor.call(Request::new(())).unwrap();
+        assert_eq!(bearer_token(&request), Some("secret"));
+
+        let mut interceptor = TokenInterceptor::default();
+        let request = interceptor.call(Request::new(())).unwrap();
+        assert_eq!(bearer_token(&request), None);
+
+        assert!(TokenInterceptor::new(Some("line\nbreak")).is_err());
+    }
+
+    fn authenticated(interceptor: &mut AuthInterceptor, token: Option<&str>) -> Result<String> {
+        let request = TokenInterceptor::new(token)?
+            .call(Request::new(()))
+            .unwrap();
+        interceptor
+            .call(request)
+            .map(|request| principal(&request).unwrap_or_default())
+            .map_err(|status| RapidashError::General(status.message().to_string()))
+    }
+
+    #[test]
+    fn test_static_tokens() {
+        let tokens =
+            StaticTokens::new(HashMap::from([("secret".to_string(), "alice".to_string())]));
+        let mut interceptor = AuthInterceptor::new(vec![Box::new(tokens)]);
+
+        assert_eq!(
+            authenticated(&mut interceptor, Some("secret")).unwrap(),
+            "alice"
+        );
+        assert!(authenticated(&mut interceptor, Some("wrong")).is_err());
+        assert!(authenticated(&mut interceptor, None).is_err());
+
+        let tokens = StaticTokens::new(HashMap::from([
+            ("secret".to_string(), "alice".to_string()),
+            ("other".to_string(), "bob".to_string()),
+        ]));
+        assert_eq!(tokens.authenticate("other").unwrap(), "bob");
+        assert!(tokens.authenticate("secre").is_none());
+        assert!(tokens.authenticate("secrets").is_none());
+    }
+
+    #[test]
+    fn test_hmac_tokens() {
+        let tokens = HmacTokens::new(b"key".to_vec());
+        let token = tokens.sign("bob", u64::MAX).unwrap();
+        assert_eq!(tokens.authenticate(&token), Some("bob".to_string()));
+
+        // expired, tampered and foreign tokens are rejected
+        assert_eq!(tokens.authenticate(&tokens.sign("bob", 1).unwrap()), None);
+        assert_eq!(tokens.authenticate(&token.replacen("bob", "eve", 1)), None);
+        let other = HmacTokens::new(b"other".to_vec());
+        assert_eq!(other.authenticate(&token), None);
+        assert!(tokens.sign("a.b", u64::MAX).is_err());
+    }
+
+    #[test]
+    fn test_disabled() {
+        let mut interceptor = AuthInterceptor::default();
+        assert_eq!(authenticated(&mut interceptor, None).unwrap(), "");
+    }
+}
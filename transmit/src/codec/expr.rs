@@ -0,0 +1,386 @@
+//! Codecs of physical expressions.
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use datafusion::arrow::compute::SortOptions;
+use datafusion::arrow::datatypes::{DataType, Schema};
+use datafusion::error::{DataFusionError, Result};
+use datafusion::execution::context::ExecutionProps;
+use datafusion::logical_expr::{AggregateFunction, BuiltinScalarFunction, Operator};
+use datafusion::physical_plan::aggregates::create_aggregate_expr;
+use datafusion::physical_plan::expressions::{
+    Avg, BinaryExpr, CaseExpr, CastExpr, Column, Count, DistinctCount, InListExpr, IsNotNullExpr,
+    IsNullExpr, Literal, Max, Min, NegativeExpr, NotExpr, PhysicalSortExpr, Sum, TryCastExpr,
+    DEFAULT_DATAFUSION_CAST_OPTIONS,
+};
+use datafusion::physical_expr::ScalarFunctionExpr;
+use datafusion::physical_plan::functions;
+use datafusion::physical_plan::{AggregateExpr, PhysicalExpr};
+
+use super::missing;
+use crate::proto;
+use crate::proto::physical_expr_node::ExprType;
+
+pub(crate) fn encode_column(column: &Column) -> proto::PhysicalColumn {
+    proto::PhysicalColumn {
+        name: column.name().to_string(),
+        index: column.index() as u32,
+    }
+}
+
+pub(crate) fn decode_column(column: &proto::PhysicalColumn) -> Column {
+    Column::new(&column.name, column.index as usize)
+}
+
+fn encode_boxed(expr: &Arc<dyn PhysicalExpr>) -> Result<Option<Box<proto::PhysicalExprNode>>> {
+    Ok(Some(Box::new(encode_expr(expr)?)))
+}
+
+pub(crate) fn encode_exprs(
+    exprs: &[Arc<dyn PhysicalExpr>],
+) -> Result<Vec<proto::PhysicalExprNode>> {
+    exprs.iter().map(encode_expr).collect()
+}
+
+pub(crate) fn encode_expr(expr: &Arc<dyn PhysicalExpr>) -> Result<proto::PhysicalExprNode> {
+    let any = expr.as_any();
+    let unary = |arg: &Arc<dyn PhysicalExpr>| -> Result<Box<proto::PhysicalUnaryNode>> {
+        Ok(Box::new(proto::PhysicalUnaryNode {
+            expr: encode_boxed(arg)?,
+        }))
+    };
+
+    let expr_type = if let Some(column) = any.downcast_ref::<Column>() {
+        ExprType::Column(encode_column(column))
+    } else if let Some(literal) = any.downcast_ref::<Literal>() {
+        ExprType::Literal(literal.value().into())
+    } else if let Some(binary) = any.downcast_ref::<BinaryExpr>() {
+        ExprType::BinaryExpr(Box::new(proto::PhysicalBinaryExprNode {
+            l: encode_boxed(binary.left())?,
+            r: encode_boxed(binary.right())?,
+            op: format!("{:?}", binary.op()),
+        }))
+    } else if let Some(cast) = any.downcast_ref::<CastExpr>() {
+        ExprType::Cast(Box::new(proto::PhysicalCastNode {
+            expr: encode_boxed(cast.expr())?,
+            arrow_type: Some(cast.cast_type().into()),
+        }))
+    } else if let Some(cast) = any.downcast_ref::<TryCastExpr>() {
+        ExprType::TryCast(Box::new(proto::PhysicalCastNode {
+            expr: encode_boxed(cast.expr())?,
+            arrow_type: Some(cast.cast_type().into()),
+        }))
+    } else if let Some(expr) = any.downcast_ref::<IsNullExpr>() {
+        ExprType::IsNullExpr(unary(expr.arg())?)
+    } else if let Some(expr) = any.downcast_ref::<IsNotNullExpr>() {
+        ExprType::IsNotNullExpr(unary(expr.arg())?)
+    } else if let Some(expr) = any.downcast_ref::<NotExpr>() {
+        ExprType::NotExpr(unary(expr.arg())?)
+    } else if let Some(expr) = any.downcast_ref::<NegativeExpr>() {
+        ExprType::Negative(unary(expr.arg())?)
+    } else if let Some(case) = any.downcast_ref::<CaseExpr>() {
+        ExprType::Case(Box::new(proto::PhysicalCaseNode {
+            expr: case.expr().as_ref().map(encode_boxed).transpose()?.flatten(),
+            when_then_expr: case
+                .when_then_expr()
+                .iter()
+                .map(|(when, then)| {
+                    Ok(proto::PhysicalWhenThen {
+                        when_expr: Some(encode_expr(when)?),
+                        then_expr: Some(encode_expr(then)?),
+                    })
+                })
+                .collect::<Result<_>>()?,
+            else_expr: case.else_expr().map(encode_boxed).transpose()?.flatten(),
+        }))
+    } else if let Some(in_list) = any.downcast_ref::<InListExpr>() {
+        ExprType::InList(Box::new(proto::PhysicalInListNode {
+            expr: encode_boxed(in_list.expr())?,
+            list: encode_exprs(in_list.list())?,
+            negated: in_list.negated(),
+        }))
+    } else if let Some(function) = any.downcast_ref::<ScalarFunctionExpr>() {
+        ExprType::ScalarFunction(proto::PhysicalScalarFunctionNode {
+            name: function.name().to_string(),
+            args: encode_exprs(function.args())?,
+        })
+    } else {
+        return Err(DataFusionError::NotImplemented(format!(
+            "Serializing physical expression {:?}",
+            expr
+        )));
+    };
+
+    Ok(proto::PhysicalExprNode {
+        expr_type: Some(expr_type),
+    })
+}
+
+fn decode_boxed(
+    expr: &Option<Box<proto::PhysicalExprNode>>,
+    name: &str,
+    input_schema: &Schema,
+) -> Result<Arc<dyn PhysicalExpr>> {
+    let expr = expr.as_deref().ok_or_else(|| missing(name))?;
+    decode_expr(expr, input_schema)
+}
+
+pub(crate) fn decode_exprs(
+    exprs: &[proto::PhysicalExprNode],
+    input_schema: &Schema,
+) -> Result<Vec<Arc<dyn PhysicalExpr>>> {
+    exprs
+        .iter()
+        .map(|expr| decode_expr(expr, input_schema))
+        .collect()
+}
+
+fn decode_type(arrow_type: &Option<proto::ArrowType>) -> Result<DataType> {
+    let arrow_type = arrow_type
+        .as_ref()
+        .ok_or_else(|| missing("PhysicalCastNode.arrow_type"))?;
+    DataType::try_from(arrow_type)
+}
+
+/// Decode an expression, columns index into `input_schema`
+pub(crate) fn decode_expr(
+    expr: &proto::PhysicalExprNode,
+    input_schema: &Schema,
+) -> Result<Arc<dyn PhysicalExpr>> {
+    let expr_type = expr
+        .expr_type
+        .as_ref()
+        .ok_or_else(|| missing("PhysicalExprNode.expr_type"))?;
+    let unary = |node: &proto::PhysicalUnaryNode| {
+        decode_boxed(&node.expr, "PhysicalUnaryNode.expr", input_schema)
+    };
+
+    Ok(match expr_type {
+        ExprType::Column(column) => Arc::new(decode_column(column)),
+        ExprType::Literal(value) => Arc::new(Literal::new(value.try_into()?)),
+        ExprType::BinaryExpr(binary) => Arc::new(BinaryExpr::new(
+            decode_boxed(&binary.l, "PhysicalBinaryExprNode.l", input_schema)?,
+            decode_operator(&binary.op)?,
+            decode_boxed(&binary.r, "PhysicalBinaryExprNode.r", input_schema)?,
+        )),
+        ExprType::Cast(cast) => Arc::new(CastExpr::new(
+            decode_boxed(&cast.expr, "PhysicalCastNode.expr", input_schema)?,
+            decode_type(&cast.arrow_type)?,
+            DEFAULT_DATAFUSION_CAST_OPTIONS,
+        )),
+        ExprType::TryCast(cast) => Arc::new(TryCastExpr::new(
+            decode_boxed(&cast.expr, "PhysicalCastNode.expr", input_schema)?,
+            decode_type(&cast.arrow_type)?,
+        )),
+        ExprType::IsNullExpr(node) => Arc::new(IsNullExpr::new(unary(node)?)),
+        ExprType::IsNotNullExpr(node) => Arc::new(IsNotNullExpr::new(unary(node)?)),
+        ExprType::NotExpr(node) => Arc::new(NotExpr::new(unary(node)?)),
+        ExprType::Negative(node) => Arc::new(NegativeExpr::new(unary(node)?)),
+        ExprType::Case(case) => {
+            let when_then_expr = case
+                .when_then_expr
+                .iter()
+                .map(|when_then| {
+                    let when = when_then
+                        .when_expr
+                        .as_ref()
+                        .ok_or_else(|| missing("PhysicalWhenThen.when_expr"))?;
+                    let then = when_then
+                        .then_expr
+                        .as_ref()
+                        .ok_or_else(|| missing("PhysicalWhenThen.then_expr"))?;
+                    Ok((
+                        decode_expr(when, input_schema)?,
+                        decode_expr(then, input_schema)?,
+                    ))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Arc::new(CaseExpr::try_new(
+                case.expr
+                    .as_deref()
+                    .map(|expr| decode_expr(expr, input_schema))
+                    .transpose()?,
+                when_then_expr,
+                case.else_expr
+                    .as_deref()
+                    .map(|expr| decode_expr(expr, input_schema))
+                    .transpose()?,
+            )?)
+        }
+        ExprType::InList(in_list) => Arc::new(InListExpr::new(
+            decode_boxed(&in_list.expr, "PhysicalInListNode.expr", input_schema)?,
+            decode_exprs(&in_list.list, input_schema)?,
+            in_list.negated,
+            input_schema,
+        )),
+        ExprType::ScalarFunction(function) => {
+            // only built in functions are known on every executor
+            let fun = BuiltinScalarFunction::from_str(&function.name).map_err(|_| {
+                DataFusionError::NotImplemented(format!(
+                    "Deserializing scalar function {}",
+                    function.name
+                ))
+            })?;
+            let args = decode_exprs(&function.args, input_schema)?;
+            functions::create_physical_expr(&fun, &args, input_schema, &ExecutionProps::new())?
+        }
+    })
+}
+
+fn decode_operator(op: &str) -> Result<Operator> {
+    Ok(match op {
+        "Eq" => Operator::Eq,
+        "NotEq" => Operator::NotEq,
+        "Lt" => Operator::Lt,
+        "LtEq" => Operator::LtEq,
+        "Gt" => Operator::Gt,
+        "GtEq" => Operator::GtEq,
+        "Plus" => Operator::Plus,
+        "Minus" => Operator::Minus,
+        "Multiply" => Operator::Multiply,
+        "Divide" => Operator::Divide,
+        "Modulo" => Operator::Modulo,
+        "And" => Operator::And,
+        "Or" => Operator::Or,
+        "Like" => Operator::Like,
+        "NotLike" => Operator::NotLike,
+        "IsDistinctFrom" => Operator::IsDistinctFrom,
+        "IsNotDistinctFrom" => Operator::IsNotDistinctFrom,
+        "RegexMatch" => Operator::RegexMatch,
+        "RegexIMatch" => Operator::RegexIMatch,
+        "RegexNotMatch" => Operator::RegexNotMatch,
+        "RegexNotIMatch" => Operator::RegexNotIMatch,
+        "BitwiseAnd" => Operator::BitwiseAnd,
+        "BitwiseOr" => Operator::BitwiseOr,
+        "BitwiseShiftRight" => Operator::BitwiseShiftRight,
+        "BitwiseShiftLeft" => Operator::BitwiseShiftLeft,
+        "StringConcat" => Operator::StringConcat,
+        other => {
+            return Err(DataFusionError::NotImplemented(format!(
+                "Deserializing operator {}",
+                other
+            )))
+        }
+    })
+}
+
+pub(crate) fn encode_named_exprs(
+    exprs: &[(Arc<dyn PhysicalExpr>, String)],
+) -> Result<Vec<proto::PhysicalNamedExpr>> {
+    exprs
+        .iter()
+        .map(|(expr, name)| {
+            Ok(proto::PhysicalNamedExpr {
+                expr: Some(encode_expr(expr)?),
+                name: name.clone(),
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn decode_named_exprs(
+    exprs: &[proto::PhysicalNamedExpr],
+    input_schema: &Schema,
+) -> Result<Vec<(Arc<dyn PhysicalExpr>, String)>> {
+    exprs
+        .iter()
+        .map(|named| {
+            let expr = named
+                .expr
+                .as_ref()
+                .ok_or_else(|| missing("PhysicalNamedExpr.expr"))?;
+            Ok((decode_expr(expr, input_schema)?, named.name.clone()))
+        })
+        .collect()
+}
+
+pub(crate) fn encode_sort_exprs(
+    exprs: &[PhysicalSortExpr],
+) -> Result<Vec<proto::PhysicalSortExprNode>> {
+    exprs
+        .iter()
+        .map(|sort| {
+            Ok(proto::PhysicalSortExprNode {
+                expr: Some(encode_expr(&sort.expr)?),
+                asc: !sort.options.descending,
+                nulls_first: sort.options.nulls_first,
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn decode_sort_exprs(
+    exprs: &[proto::PhysicalSortExprNode],
+    input_schema: &Schema,
+) -> Result<Vec<PhysicalSortExpr>> {
+    exprs
+        .iter()
+        .map(|sort| {
+            let expr = sort
+                .expr
+                .as_ref()
+                .ok_or_else(|| missing("PhysicalSortExprNode.expr"))?;
+            Ok(PhysicalSortExpr {
+                expr: decode_expr(expr, input_schema)?,
+                options: SortOptions {
+                    descending: !sort.asc,
+                    nulls_first: sort.nulls_first,
+                },
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn encode_aggregate_expr(
+    expr: &Arc<dyn AggregateExpr>,
+) -> Result<proto::PhysicalAggregateExprNode> {
+    let any = expr.as_any();
+    let (fun, distinct) = if any.is::<Count>() {
+        (proto::AggregateFunction::Count, false)
+    } else if any.is::<DistinctCount>() {
+        (proto::AggregateFunction::Count, true)
+    } else if any.is::<Sum>() {
+        (proto::AggregateFunction::Sum, false)
+    } else if any.is::<Min>() {
+        (proto::AggregateFunction::Min, false)
+    } else if any.is::<Max>() {
+        (proto::AggregateFunction::Max, false)
+    } else if any.is::<Avg>() {
+        (proto::AggregateFunction::Avg, false)
+    } else {
+        return Err(DataFusionError::NotImplemented(format!(
+            "Serializing aggregate expression {:?}",
+            expr
+        )));
+    };
+
+    Ok(proto::PhysicalAggregateExprNode {
+        fun: fun as i32,
+        expr: encode_exprs(&expr.expressions())?,
+        distinct,
+        name: expr.name().to_string(),
+    })
+}
+
+/// Decode an aggregate expression, its arguments index into `input_schema`
+pub(crate) fn decode_aggregate_expr(
+    expr: &proto::PhysicalAggregateExprNode,
+    input_schema: &Schema,
+) -> Result<Arc<dyn AggregateExpr>> {
+    let fun = match proto::AggregateFunction::from_i32(expr.fun) {
+        Some(proto::AggregateFunction::Min) => AggregateFunction::Min,
+        Some(proto::AggregateFunction::Max) => AggregateFunction::Max,
+        Some(proto::AggregateFunction::Sum) => AggregateFunction::Sum,
+        Some(proto::AggregateFunction::Avg) => AggregateFunction::Avg,
+        Some(proto::AggregateFunction::Count) => AggregateFunction::Count,
+        None => {
+            return Err(DataFusionError::Internal(format!(
+                "Unknown aggregate function {}",
+                expr.fun
+            )))
+        }
+    };
+    let args = decode_exprs(&expr.expr, input_schema)?;
+    create_aggregate_expr(&fun, expr.distinct, &args, input_schema, expr.name.clone())
+}
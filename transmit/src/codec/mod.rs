@@ -0,0 +1,17 @@
+//! Protobuf codecs of the physical plan fragments the scheduler sends to executors.
+//!
+//! Arrow types and scalar values convert with `From`/`TryFrom` between the
+//! generated messages in `crate::proto` and the arrow/DataFusion types.
+
+mod expr;
+mod plan;
+mod scalar;
+
+use datafusion::error::DataFusionError;
+
+pub use plan::{decode_plan, encode_plan};
+
+/// Error of a message without a field every encoded message has
+pub(crate) fn missing(field: &str) -> DataFusionError {
+    DataFusionError::Internal(format!("Missing required field {}", field))
+}
@@ -0,0 +1,752 @@
+//! Codec of physical plans.
+
+use std::sync::Arc;
+
+use chrono::{TimeZone, Utc};
+use datafusion::arrow::datatypes::{Schema, SchemaRef};
+use datafusion::config::ConfigOptions;
+use datafusion::datasource::file_format::file_type::FileCompressionType;
+use datafusion::datasource::listing::{FileRange, PartitionedFile};
+use datafusion::datasource::object_store::ObjectStoreUrl;
+use datafusion::error::{DataFusionError, Result};
+use datafusion::logical_expr::JoinType;
+use datafusion::physical_plan::aggregates::{AggregateExec, AggregateMode, PhysicalGroupBy};
+use datafusion::physical_plan::coalesce_batches::CoalesceBatchesExec;
+use datafusion::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use datafusion::physical_plan::empty::EmptyExec;
+use datafusion::physical_plan::file_format::{AvroExec, CsvExec, FileScanConfig, ParquetExec};
+use datafusion::physical_plan::filter::FilterExec;
+use datafusion::physical_plan::joins::utils::{ColumnIndex, JoinFilter, JoinSide};
+use datafusion::physical_plan::joins::{CrossJoinExec, HashJoinExec, PartitionMode};
+use datafusion::physical_plan::limit::{GlobalLimitExec, LocalLimitExec};
+use datafusion::physical_plan::projection::ProjectionExec;
+use datafusion::physical_plan::repartition::RepartitionExec;
+use datafusion::physical_plan::sorts::sort::SortExec;
+use datafusion::physical_plan::sorts::sort_preserving_merge::SortPreservingMergeExec;
+use datafusion::physical_plan::{
+    ColumnStatistics, ExecutionPlan, Partitioning, PhysicalExpr, Statistics,
+};
+use object_store::path::Path;
+use object_store::ObjectMeta;
+use prost::Message;
+
+use super::expr::{
+    decode_aggregate_expr, decode_column, decode_expr, decode_exprs, decode_named_exprs,
+    decode_sort_exprs, encode_aggregate_expr, encode_column, encode_expr, encode_exprs,
+    encode_named_exprs, encode_sort_exprs,
+};
+use super::missing;
+use crate::proto;
+use crate::proto::physical_plan_node::PhysicalPlanType;
+use crate::proto::repartition_exec_node::PartitionMethod;
+use crate::shuffle::{ShuffleReaderExec, ShuffleWriterExec};
+
+/// Serialize a plan fragment
+pub fn encode_plan(plan: &Arc<dyn ExecutionPlan>) -> Result<Vec<u8>> {
+    Ok(encode_node(plan)?.encode_to_vec())
+}
+
+/// Deserialize a plan fragment serialized by `encode_plan`
+pub fn decode_plan(plan: &[u8]) -> Result<Arc<dyn ExecutionPlan>> {
+    let node = proto::PhysicalPlanNode::decode(plan)
+        .map_err(|e| DataFusionError::Internal(format!("Invalid physical plan: {}", e)))?;
+    decode_node(&node)
+}
+
+fn encode_boxed(plan: &Arc<dyn ExecutionPlan>) -> Result<Option<Box<proto::PhysicalPlanNode>>> {
+    Ok(Some(Box::new(encode_node(plan)?)))
+}
+
+fn encode_node(plan: &Arc<dyn ExecutionPlan>) -> Result<proto::PhysicalPlanNode> {
+    let any = plan.as_any();
+
+    let plan_type = if let Some(exec) = any.downcast_ref::<ParquetExec>() {
+        // the pruning predicate is not sent, the filter above the scan still applies
+        PhysicalPlanType::ParquetScan(proto::ParquetScanExecNode {
+            base_conf: Some(encode_scan_config(exec.base_config())?),
+        })
+    } else if let Some(exec) = any.downcast_ref::<CsvExec>() {
+        PhysicalPlanType::CsvScan(proto::CsvScanExecNode {
+            base_conf: Some(encode_scan_config(exec.base_config())?),
+            has_header: exec.has_header(),
+            delimiter: exec.delimiter() as u32,
+        })
+    } else if let Some(exec) = any.downcast_ref::<AvroExec>() {
+        PhysicalPlanType::AvroScan(proto::AvroScanExecNode {
+            base_conf: Some(encode_scan_config(exec.base_config())?),
+        })
+    } else if let Some(exec) = any.downcast_ref::<ProjectionExec>() {
+        PhysicalPlanType::Projection(Box::new(proto::ProjectionExecNode {
+            input: encode_boxed(exec.input())?,
+            expr: encode_named_exprs(exec.expr())?,
+        }))
+    } else if let Some(exec) = any.downcast_ref::<FilterExec>() {
+        PhysicalPlanType::Filter(Box::new(proto::FilterExecNode {
+            input: encode_boxed(exec.input())?,
+            predicate: Some(encode_expr(exec.predicate())?),
+        }))
+    } else if let Some(exec) = any.downcast_ref::<AggregateExec>() {
+        let mode = match exec.mode() {
+            AggregateMode::Partial => proto::AggregateMode::Partial,
+            AggregateMode::Final => proto::AggregateMode::Final,
+            AggregateMode::FinalPartitioned => proto::AggregateMode::FinalPartitioned,
+        };
+        let group_by = exec.group_expr();
+        PhysicalPlanType::Aggregate(Box::new(proto::AggregateExecNode {
+            input: encode_boxed(exec.input())?,
+            mode: mode as i32,
+            group_expr: encode_named_exprs(group_by.expr())?,
+            null_expr: encode_named_exprs(group_by.null_expr())?,
+            groups: group_by.groups().iter().flatten().copied().collect(),
+            aggr_expr: exec
+                .aggr_expr()
+                .iter()
+                .map(encode_aggregate_expr)
+                .collect::<Result<_>>()?,
+            input_schema: Some(exec.input_schema().as_ref().into()),
+        }))
+    } else if let Some(exec) = any.downcast_ref::<HashJoinExec>() {
+        let partition_mode = match exec.partition_mode() {
+            PartitionMode::CollectLeft => proto::PartitionMode::CollectLeft,
+            PartitionMode::Partitioned => proto::PartitionMode::Partitioned,
+        };
+        PhysicalPlanType::HashJoin(Box::new(proto::HashJoinExecNode {
+            left: encode_boxed(exec.left())?,
+            right: encode_boxed(exec.right())?,
+            on: exec
+                .on()
+                .iter()
+                .map(|(left, right)| proto::JoinOn {
+                    left: Some(encode_column(left)),
+                    right: Some(encode_column(right)),
+                })
+                .collect(),
+            join_type: encode_join_type(exec.join_type()) as i32,
+            partition_mode: partition_mode as i32,
+            null_equals_null: *exec.null_equals_null(),
+            filter: exec.filter().as_ref().map(encode_join_filter).transpose()?,
+        }))
+    } else if let Some(exec) = any.downcast_ref::<CrossJoinExec>() {
+        PhysicalPlanType::CrossJoin(Box::new(proto::CrossJoinExecNode {
+            left: encode_boxed(exec.left())?,
+            right: encode_boxed(exec.right())?,
+        }))
+    } else if let Some(exec) = any.downcast_ref::<SortExec>() {
+        PhysicalPlanType::Sort(Box::new(proto::SortExecNode {
+            input: encode_boxed(exec.input())?,
+            expr: encode_sort_exprs(exec.expr())?,
+            fetch: exec.fetch().map(|fetch| fetch as i64).unwrap_or(-1),
+            // a sort merging its input has a single output partition
+            preserve_partitioning: exec.output_partitioning().partition_count() > 1,
+        }))
+    } else if let Some(exec) = any.downcast_ref::<SortPreservingMergeExec>() {
+        PhysicalPlanType::SortPreservingMerge(Box::new(proto::SortPreservingMergeExecNode {
+            input: encode_boxed(exec.input())?,
+            expr: encode_sort_exprs(exec.expr())?,
+        }))
+    } else if let Some(exec) = any.downcast_ref::<RepartitionExec>() {
+        let partition_method = match exec.partitioning() {
+            Partitioning::RoundRobinBatch(count) => PartitionMethod::RoundRobin(*count as u64),
+            Partitioning::Hash(exprs, count) => {
+                PartitionMethod::Hash(encode_hash_partitioning(exprs, *count)?)
+            }
+            Partitioning::UnknownPartitioning(count) => PartitionMethod::Unknown(*count as u64),
+        };
+        PhysicalPlanType::Repartition(Box::new(proto::RepartitionExecNode {
+            input: encode_boxed(exec.input())?,
+            partition_method: Some(partition_method),
+        }))
+    } else if let Some(exec) = any.downcast_ref::<CoalesceBatchesExec>() {
+        PhysicalPlanType::CoalesceBatches(Box::new(proto::CoalesceBatchesExecNode {
+            input: encode_boxed(exec.input())?,
+            target_batch_size: exec.target_batch_size() as u32,
+        }))
+    } else if let Some(exec) = any.downcast_ref::<CoalescePartitionsExec>() {
+        PhysicalPlanType::Merge(Box::new(proto::CoalescePartitionsExecNode {
+            input: encode_boxed(exec.input())?,
+        }))
+    } else if let Some(exec) = any.downcast_ref::<GlobalLimitExec>() {
+        PhysicalPlanType::GlobalLimit(Box::new(proto::GlobalLimitExecNode {
+            input: encode_boxed(exec.input())?,
+            skip: exec.skip() as u32,
+            fetch: exec.fetch().map(|fetch| *fetch as i64).unwrap_or(-1),
+        }))
+    } else if let Some(exec) = any.downcast_ref::<LocalLimitExec>() {
+        PhysicalPlanType::LocalLimit(Box::new(proto::LocalLimitExecNode {
+            input: encode_boxed(exec.input())?,
+            fetch: exec.fetch() as u32,
+        }))
+    } else if let Some(exec) = any.downcast_ref::<EmptyExec>() {
+        PhysicalPlanType::Empty(proto::EmptyExecNode {
+            produce_one_row: exec.produce_one_row(),
+            schema: Some(exec.schema().as_ref().into()),
+        })
+    } else if let Some(exec) = any.downcast_ref::<ShuffleWriterExec>() {
+        let output_partitioning = match exec.shuffle_output_partitioning() {
+            Some(Partitioning::Hash(exprs, count)) => {
+                Some(encode_hash_partitioning(exprs, *count)?)
+            }
+            _ => None,
+        };
+        PhysicalPlanType::ShuffleWriter(Box::new(proto::ShuffleWriterExecNode {
+            job_id: exec.job_id().to_string(),
+            stage_id: exec.stage_id() as u32,
+            input: encode_boxed(&exec.children()[0])?,
+            output_partitioning,
+        }))
+    } else if let Some(exec) = any.downcast_ref::<ShuffleReaderExec>() {
+        PhysicalPlanType::ShuffleReader(proto::ShuffleReaderExecNode {
+            partitions: exec
+                .partitions()
+                .iter()
+                .map(|locations| proto::ShuffleReaderPartition {
+                    locations: locations.clone(),
+                })
+                .collect(),
+            schema: Some(exec.schema().as_ref().into()),
+        })
+    } else {
+        return Err(DataFusionError::NotImplemented(format!(
+            "Serializing physical plan {:?}",
+            plan
+        )));
+    };
+
+    Ok(proto::PhysicalPlanNode {
+        physical_plan_type: Some(plan_type),
+    })
+}
+
+fn decode_input(
+    input: &Option<Box<proto::PhysicalPlanNode>>,
+    name: &str,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let input = input.as_deref().ok_or_else(|| missing(name))?;
+    decode_node(input)
+}
+
+fn decode_schema(schema: &Option<proto::Schema>, name: &str) -> Result<SchemaRef> {
+    let schema = schema.as_ref().ok_or_else(|| missing(name))?;
+    Ok(Arc::new(Schema::try_from(schema)?))
+}
+
+fn decode_node(node: &proto::PhysicalPlanNode) -> Result<Arc<dyn ExecutionPlan>> {
+    let plan_type = node
+        .physical_plan_type
+        .as_ref()
+        .ok_or_else(|| missing("PhysicalPlanNode.physical_plan_type"))?;
+
+    Ok(match plan_type {
+        PhysicalPlanType::ParquetScan(scan) => Arc::new(ParquetExec::new(
+            decode_scan_config(&scan.base_conf)?,
+            None,
+            None,
+        )),
+        PhysicalPlanType::CsvScan(scan) => {
+            let config = decode_scan_config(&scan.base_conf)?;
+            let compression = file_compression_type(&config);
+            Arc::new(CsvExec::new(
+                config,
+                scan.has_header,
+                scan.delimiter as u8,
+                compression,
+            ))
+        }
+        PhysicalPlanType::AvroScan(scan) => {
+            Arc::new(AvroExec::new(decode_scan_config(&scan.base_conf)?))
+        }
+        PhysicalPlanType::Projection(projection) => {
+            let input = decode_input(&projection.input, "ProjectionExecNode.input")?;
+            let expr = decode_named_exprs(&projection.expr, &input.schema())?;
+            Arc::new(ProjectionExec::try_new(expr, input)?)
+        }
+        PhysicalPlanType::Filter(filter) => {
+            let input = decode_input(&filter.input, "FilterExecNode.input")?;
+            let predicate = filter
+                .predicate
+                .as_ref()
+                .ok_or_else(|| missing("FilterExecNode.predicate"))?;
+            let predicate = decode_expr(predicate, &input.schema())?;
+            Arc::new(FilterExec::try_new(predicate, input)?)
+        }
+        PhysicalPlanType::Aggregate(aggregate) => {
+            let input = decode_input(&aggregate.input, "AggregateExecNode.input")?;
+            let input_schema =
+                decode_schema(&aggregate.input_schema, "AggregateExecNode.input_schema")?;
+            let mode = match proto::AggregateMode::from_i32(aggregate.mode) {
+                Some(proto::AggregateMode::Partial) => AggregateMode::Partial,
+                Some(proto::AggregateMode::Final) => AggregateMode::Final,
+                Some(proto::AggregateMode::FinalPartitioned) => AggregateMode::FinalPartitioned,
+                None => {
+                    return Err(DataFusionError::Internal(format!(
+                        "Unknown aggregate mode {}",
+                        aggregate.mode
+                    )))
+                }
+            };
+            let group_expr = decode_named_exprs(&aggregate.group_expr, &input.schema())?;
+            let null_expr = decode_named_exprs(&aggregate.null_expr, &input.schema())?;
+            let groups = if group_expr.is_empty() {
+                vec![]
+            } else {
+                aggregate
+                    .groups
+                    .chunks(group_expr.len())
+                    .map(|group| group.to_vec())
+                    .collect()
+            };
+            // the aggregate arguments refer to the input of the partial aggregation
+            let aggr_expr = aggregate
+                .aggr_expr
+                .iter()
+                .map(|expr| decode_aggregate_expr(expr, &input_schema))
+                .collect::<Result<Vec<_>>>()?;
+            Arc::new(AggregateExec::try_new(
+                mode,
+                PhysicalGroupBy::new(group_expr, null_expr, groups),
+                aggr_expr,
+                input,
+                input_schema,
+            )?)
+        }
+        PhysicalPlanType::HashJoin(join) => {
+            let left = decode_input(&join.left, "HashJoinExecNode.left")?;
+            let right = decode_input(&join.right, "HashJoinExecNode.right")?;
+            let on = join
+                .on
+                .iter()
+                .map(|on| {
+                    let left = on.left.as_ref().ok_or_else(|| missing("JoinOn.left"))?;
+                    let right = on.right.as_ref().ok_or_else(|| missing("JoinOn.right"))?;
+                    Ok((decode_column(left), decode_column(right)))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let partition_mode = match proto::PartitionMode::from_i32(join.partition_mode) {
+                Some(proto::PartitionMode::CollectLeft) => PartitionMode::CollectLeft,
+                Some(proto::PartitionMode::Partitioned) => PartitionMode::Partitioned,
+                None => {
+                    return Err(DataFusionError::Internal(format!(
+                        "Unknown partition mode {}",
+                        join.partition_mode
+                    )))
+                }
+            };
+            Arc::new(HashJoinExec::try_new(
+                left,
+                right,
+                on,
+                join.filter.as_ref().map(decode_join_filter).transpose()?,
+                &decode_join_type(join.join_type)?,
+                partition_mode,
+                &join.null_equals_null,
+            )?)
+        }
+        PhysicalPlanType::CrossJoin(join) => Arc::new(CrossJoinExec::try_new(
+            decode_input(&join.left, "CrossJoinExecNode.left")?,
+            decode_input(&join.right, "CrossJoinExecNode.right")?,
+        )?),
+        PhysicalPlanType::Sort(sort) => {
+            let input = decode_input(&sort.input, "SortExecNode.input")?;
+            let expr = decode_sort_exprs(&sort.expr, &input.schema())?;
+            let fetch = usize::try_from(sort.fetch).ok();
+            Arc::new(SortExec::new_with_partitioning(
+                expr,
+                input,
+                sort.preserve_partitioning,
+                fetch,
+            ))
+        }
+        PhysicalPlanType::SortPreservingMerge(merge) => {
+            let input = decode_input(&merge.input, "SortPreservingMergeExecNode.input")?;
+            let expr = decode_sort_exprs(&merge.expr, &input.schema())?;
+            Arc::new(SortPreservingMergeExec::new(expr, input))
+        }
+        PhysicalPlanType::Repartition(repartition) => {
+            let input = decode_input(&repartition.input, "RepartitionExecNode.input")?;
+            let partitioning = match repartition.partition_method.as_ref() {
+                Some(PartitionMethod::RoundRobin(count)) => {
+                    Partitioning::RoundRobinBatch(*count as usize)
+                }
+                Some(PartitionMethod::Hash(hash)) => {
+                    decode_hash_partitioning(hash, &input.schema())?
+                }
+                Some(PartitionMethod::Unknown(count)) => {
+                    Partitioning::UnknownPartitioning(*count as usize)
+                }
+                None => return Err(missing("RepartitionExecNode.partition_method")),
+            };
+            Arc::new(RepartitionExec::try_new(input, partitioning)?)
+        }
+        PhysicalPlanType::CoalesceBatches(coalesce) => Arc::new(CoalesceBatchesExec::new(
+            decode_input(&coalesce.input, "CoalesceBatchesExecNode.input")?,
+            coalesce.target_batch_size as usize,
+        )),
+        PhysicalPlanType::Merge(merge) => Arc::new(CoalescePartitionsExec::new(decode_input(
+            &merge.input,
+            "CoalescePartitionsExecNode.input",
+        )?)),
+        PhysicalPlanType::GlobalLimit(limit) => Arc::new(GlobalLimitExec::new(
+            decode_input(&limit.input, "GlobalLimitExecNode.input")?,
+            limit.skip as usize,
+            usize::try_from(limit.fetch).ok(),
+        )),
+        PhysicalPlanType::LocalLimit(limit) => Arc::new(LocalLimitExec::new(
+            decode_input(&limit.input, "LocalLimitExecNode.input")?,
+            limit.fetch as usize,
+        )),
+        PhysicalPlanType::Empty(empty) => Arc::new(EmptyExec::new(
+            empty.produce_one_row,
+            decode_schema(&empty.schema, "EmptyExecNode.schema")?,
+        )),
+        PhysicalPlanType::ShuffleWriter(writer) => {
+            let input = decode_input(&writer.input, "ShuffleWriterExecNode.input")?;
+            let output_partitioning = writer
+                .output_partitioning
+                .as_ref()
+                .map(|hash| decode_hash_partitioning(hash, &input.schema()))
+                .transpose()?;
+            // the executor running the task sets its own work dir
+            Arc::new(ShuffleWriterExec::try_new(
+                writer.job_id.clone(),
+                writer.stage_id as usize,
+                input,
+                String::new(),
+                output_partitioning,
+            )?)
+        }
+        PhysicalPlanType::ShuffleReader(reader) => Arc::new(ShuffleReaderExec::new(
+            reader
+                .partitions
+                .iter()
+                .map(|partition| partition.locations.clone())
+                .collect(),
+            decode_schema(&reader.schema, "ShuffleReaderExecNode.schema")?,
+        )),
+    })
+}
+
+fn encode_hash_partitioning(
+    exprs: &[Arc<dyn PhysicalExpr>],
+    count: usize,
+) -> Result<proto::PhysicalHashRepartition> {
+    Ok(proto::PhysicalHashRepartition {
+        hash_expr: encode_exprs(exprs)?,
+        partition_count: count as u64,
+    })
+}
+
+fn decode_hash_partitioning(
+    hash: &proto::PhysicalHashRepartition,
+    input_schema: &Schema,
+) -> Result<Partitioning> {
+    Ok(Partitioning::Hash(
+        decode_exprs(&hash.hash_expr, input_schema)?,
+        hash.partition_count as usize,
+    ))
+}
+
+fn encode_join_type(join_type: &JoinType) -> proto::JoinType {
+    match join_type {
+        JoinType::Inner => proto::JoinType::Inner,
+        JoinType::Left => proto::JoinType::Left,
+        JoinType::Right => proto::JoinType::Right,
+        JoinType::Full => proto::JoinType::Full,
+        JoinType::LeftSemi => proto::JoinType::LeftSemi,
+        JoinType::LeftAnti => proto::JoinType::LeftAnti,
+        JoinType::RightSemi => proto::JoinType::RightSemi,
+        JoinType::RightAnti => proto::JoinType::RightAnti,
+    }
+}
+
+fn decode_join_type(join_type: i32) -> Result<JoinType> {
+    Ok(match proto::JoinType::from_i32(join_type) {
+        Some(proto::JoinType::Inner) => JoinType::Inner,
+        Some(proto::JoinType::Left) => JoinType::Left,
+        Some(proto::JoinType::Right) => JoinType::Right,
+        Some(proto::JoinType::Full) => JoinType::Full,
+        Some(proto::JoinType::LeftSemi) => JoinType::LeftSemi,
+        Some(proto::JoinType::LeftAnti) => JoinType::LeftAnti,
+        Some(proto::JoinType::RightSemi) => JoinType::RightSemi,
+        Some(proto::JoinType::RightAnti) => JoinType::RightAnti,
+        None => {
+            return Err(DataFusionError::Internal(format!(
+                "Unknown join type {}",
+                join_type
+            )))
+        }
+    })
+}
+
+fn encode_join_filter(filter: &JoinFilter) -> Result<proto::JoinFilter> {
+    Ok(proto::JoinFilter {
+        expression: Some(encode_expr(filter.expression())?),
+        column_indices: filter
+            .column_indices()
+            .iter()
+            .map(|column| {
+                let side = match column.side {
+                    JoinSide::Left => proto::JoinSide::Left,
+                    JoinSide::Right => proto::JoinSide::Right,
+                };
+                proto::ColumnIndex {
+                    index: column.index as u32,
+                    side: side as i32,
+                }
+            })
+            .collect(),
+        schema: Some(filter.schema().into()),
+    })
+}
+
+fn decode_join_filter(filter: &proto::JoinFilter) -> Result<JoinFilter> {
+    let schema = decode_schema(&filter.schema, "JoinFilter.schema")?;
+    let expression = filter
+        .expression
+        .as_ref()
+        .ok_or_else(|| missing("JoinFilter.expression"))?;
+    let column_indices = filter
+        .column_indices
+        .iter()
+        .map(|column| {
+            let side = match proto::JoinSide::from_i32(column.side) {
+                Some(proto::JoinSide::Left) => JoinSide::Left,
+                Some(proto::JoinSide::Right) => JoinSide::Right,
+                None => {
+                    return Err(DataFusionError::Internal(format!(
+                        "Unknown join side {}",
+                        column.side
+                    )))
+                }
+            };
+            Ok(ColumnIndex {
+                index: column.index as usize,
+                side,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(JoinFilter::new(
+        decode_expr(expression, &schema)?,
+        column_indices,
+        schema.as_ref().clone(),
+    ))
+}
+
+fn encode_scan_config(config: &FileScanConfig) -> Result<proto::FileScanExecConf> {
+    Ok(proto::FileScanExecConf {
+        object_store_url: config.object_store_url.to_string(),
+        schema: Some(config.file_schema.as_ref().into()),
+        file_groups: config
+            .file_groups
+            .iter()
+            .map(|files| proto::FileGroup {
+                files: files.iter().map(encode_partitioned_file).collect(),
+            })
+            .collect(),
+        statistics: Some(encode_statistics(&config.statistics)),
+        projection: config
+            .projection
+            .as_ref()
+            .map(|columns| proto::ProjectionColumns {
+                columns: columns.iter().map(|c| *c as u32).collect(),
+            }),
+        limit: config.limit.map(|limit| proto::ScanLimit {
+            limit: limit as u32,
+        }),
+        table_partition_cols: config.table_partition_cols.clone(),
+    })
+}
+
+fn decode_scan_config(config: &Option<proto::FileScanExecConf>) -> Result<FileScanConfig> {
+    let config = config.as_ref().ok_or_else(|| missing("FileScanExecConf"))?;
+    let statistics = config
+        .statistics
+        .as_ref()
+        .map(decode_statistics)
+        .transpose()?
+        .unwrap_or_default();
+    Ok(FileScanConfig {
+        object_store_url: ObjectStoreUrl::parse(&config.object_store_url)?,
+        file_schema: decode_schema(&config.schema, "FileScanExecConf.schema")?,
+        file_groups: config
+            .file_groups
+            .iter()
+            .map(|group| group.files.iter().map(decode_partitioned_file).collect())
+            .collect::<Result<Vec<_>>>()?,
+        statistics,
+        projection: config
+            .projection
+            .as_ref()
+            .map(|projection| projection.columns.iter().map(|c| *c as usize).collect()),
+        limit: config.limit.as_ref().map(|limit| limit.limit as usize),
+        table_partition_cols: config.table_partition_cols.clone(),
+        // executors read files with their own options
+        config_options: ConfigOptions::new().into_shareable(),
+    })
+}
+
+fn encode_partitioned_file(file: &PartitionedFile) -> proto::PartitionedFile {
+    proto::PartitionedFile {
+        path: file.object_meta.location.to_string(),
+        size: file.object_meta.size as u64,
+        last_modified: file.object_meta.last_modified.timestamp_nanos(),
+        partition_values: file.partition_values.iter().map(Into::into).collect(),
+        range: file.range.as_ref().map(|range| proto::FileRange {
+            start: range.start,
+            end: range.end,
+        }),
+    }
+}
+
+fn decode_partitioned_file(file: &proto::PartitionedFile) -> Result<PartitionedFile> {
+    Ok(PartitionedFile {
+        object_meta: ObjectMeta {
+            location: Path::from(file.path.as_str()),
+            last_modified: Utc.timestamp_nanos(file.last_modified),
+            size: file.size as usize,
+        },
+        partition_values: file
+            .partition_values
+            .iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<_>>>()?,
+        range: file.range.as_ref().map(|range| FileRange {
+            start: range.start,
+            end: range.end,
+        }),
+        extensions: None,
+    })
+}
+
+/// The compression of CSV files is not part of `CsvExec`'s public API, so it
+/// is derived from the file extension of the scanned files
+fn file_compression_type(config: &FileScanConfig) -> FileCompressionType {
+    let extension = config
+        .file_groups
+        .iter()
+        .flatten()
+        .next()
+        .and_then(|file| file.object_meta.location.extension().map(str::to_owned));
+    match extension.as_deref() {
+        Some("gz") => FileCompressionType::GZIP,
+        Some("bz2") => FileCompressionType::BZIP2,
+        _ => FileCompressionType::UNCOMPRESSED,
+    }
+}
+
+/// Unknown counts are sent as -1
+fn encode_count(count: Option<usize>) -> i64 {
+    count.map(|count| count as i64).unwrap_or(-1)
+}
+
+fn decode_count(count: i64) -> Option<usize> {
+    usize::try_from(count).ok()
+}
+
+fn encode_statistics(statistics: &Statistics) -> proto::Statistics {
+    proto::Statistics {
+        num_rows: encode_count(statistics.num_rows),
+        total_byte_size: encode_count(statistics.total_byte_size),
+        column_stats: statistics
+            .column_statistics
+            .iter()
+            .flatten()
+            .map(|column| proto::ColumnStats {
+                min_value: column.min_value.as_ref().map(Into::into),
+                max_value: column.max_value.as_ref().map(Into::into),
+                null_count: encode_count(column.null_count),
+                distinct_count: encode_count(column.distinct_count),
+            })
+            .collect(),
+        is_exact: statistics.is_exact,
+    }
+}
+
+fn decode_statistics(statistics: &proto::Statistics) -> Result<Statistics> {
+    let column_statistics = statistics
+        .column_stats
+        .iter()
+        .map(|column| {
+            Ok(ColumnStatistics {
+                null_count: decode_count(column.null_count),
+                max_value: column
+                    .max_value
+                    .as_ref()
+                    .map(TryInto::try_into)
+                    .transpose()?,
+                min_value: column
+                    .min_value
+                    .as_ref()
+                    .map(TryInto::try_into)
+                    .transpose()?,
+                distinct_count: decode_count(column.distinct_count),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Statistics {
+        num_rows: decode_count(statistics.num_rows),
+        total_byte_size: decode_count(statistics.total_byte_size),
+        // an empty list is no column statistics at all
+        column_statistics: if column_statistics.is_empty() {
+            None
+        } else {
+            Some(column_statistics)
+        },
+        is_exact: statistics.is_exact,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion::arrow::datatypes::{DataType, Field};
+    use datafusion::logical_expr::Operator;
+    use datafusion::physical_plan::expressions::{binary, col, lit};
+    use datafusion::scalar::ScalarValue;
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int64, false),
+            Field::new("b", DataType::Utf8, true),
+        ]))
+    }
+
+    fn round_trip(plan: Arc<dyn ExecutionPlan>) {
+        let decoded = decode_plan(&encode_plan(&plan).unwrap()).unwrap();
+        assert_eq!(format!("{:?}", decoded), format!("{:?}", plan));
+    }
+
+    #[test]
+    fn test_filter_projection_round_trip() {
+        let schema = schema();
+        let empty: Arc<dyn ExecutionPlan> = Arc::new(EmptyExec::new(true, schema.clone()));
+        let predicate = binary(
+            col("a", &schema).unwrap(),
+            Operator::Gt,
+            lit(ScalarValue::Int64(Some(1))),
+            &schema,
+        )
+        .unwrap();
+        let filter = Arc::new(FilterExec::try_new(predicate, empty).unwrap());
+        let projection =
+            ProjectionExec::try_new(vec![(col("b", &schema).unwrap(), "b".to_string())], filter)
+                .unwrap();
+        round_trip(Arc::new(projection));
+    }
+
+    #[test]
+    fn test_shuffle_writer_round_trip() {
+        let schema = schema();
+        let empty: Arc<dyn ExecutionPlan> = Arc::new(EmptyExec::new(false, schema.clone()));
+        let repartition =
+            Arc::new(RepartitionExec::try_new(empty, Partitioning::RoundRobinBatch(4)).unwrap());
+        let writer = ShuffleWriterExec::try_new(
+            "job".to_string(),
+            1,
+            repartition,
+            String::new(),
+            Some(Partitioning::Hash(vec![col("a", &schema).unwrap()], 2)),
+        )
+        .unwrap();
+        round_trip(Arc::new(writer));
+    }
+}
@@ -0,0 +1,567 @@
+//! Codecs of arrow data types, schemas and scalar values.
+//!
+//! DataFusion and `common::scalar` are built on different arrow versions, so
+//! the codec is written once as a macro and expanded for both. The macro body
+//! refers to `DataType`, `Field`, `IntervalUnit`, `Schema`, `TimeUnit`,
+//! `UnionMode`, `ScalarValue` and `Error` as imported where it is expanded.
+
+macro_rules! arrow_codec {
+    () => {
+        use crate::proto;
+        use crate::proto::arrow_type::ArrowTypeEnum;
+        use crate::proto::scalar_value::Value;
+
+        fn missing(field: &str) -> Error {
+            Error::Internal(format!("Missing required field {}", field))
+        }
+
+        fn encode_time_unit(unit: &TimeUnit) -> i32 {
+            let unit = match unit {
+                TimeUnit::Second => proto::TimeUnit::Second,
+                TimeUnit::Millisecond => proto::TimeUnit::Millisecond,
+                TimeUnit::Microsecond => proto::TimeUnit::Microsecond,
+                TimeUnit::Nanosecond => proto::TimeUnit::Nanosecond,
+            };
+            unit as i32
+        }
+
+        fn decode_time_unit(unit: i32) -> Result<TimeUnit, Error> {
+            match proto::TimeUnit::from_i32(unit) {
+                Some(proto::TimeUnit::Second) => Ok(TimeUnit::Second),
+                Some(proto::TimeUnit::Millisecond) => Ok(TimeUnit::Millisecond),
+                Some(proto::TimeUnit::Microsecond) => Ok(TimeUnit::Microsecond),
+                Some(proto::TimeUnit::Nanosecond) => Ok(TimeUnit::Nanosecond),
+                None => Err(Error::Internal(format!("Unknown time unit {}", unit))),
+            }
+        }
+
+        fn encode_interval_unit(unit: &IntervalUnit) -> i32 {
+            let unit = match unit {
+                IntervalUnit::YearMonth => proto::IntervalUnit::YearMonth,
+                IntervalUnit::DayTime => proto::IntervalUnit::DayTime,
+                IntervalUnit::MonthDayNano => proto::IntervalUnit::MonthDayNano,
+            };
+            unit as i32
+        }
+
+        fn decode_interval_unit(unit: i32) -> Result<IntervalUnit, Error> {
+            match proto::IntervalUnit::from_i32(unit) {
+                Some(proto::IntervalUnit::YearMonth) => Ok(IntervalUnit::YearMonth),
+                Some(proto::IntervalUnit::DayTime) => Ok(IntervalUnit::DayTime),
+                Some(proto::IntervalUnit::MonthDayNano) => Ok(IntervalUnit::MonthDayNano),
+                None => Err(Error::Internal(format!("Unknown interval unit {}", unit))),
+            }
+        }
+
+        /// Timezones are never empty, so an empty string means no timezone
+        fn decode_timezone(timezone: &str) -> Option<String> {
+            if timezone.is_empty() {
+                None
+            } else {
+                Some(timezone.to_string())
+            }
+        }
+
+        fn decode_i128(bytes: &[u8]) -> Result<i128, Error> {
+            <[u8; 16]>::try_from(bytes)
+                .map(i128::from_le_bytes)
+                .map_err(|_| Error::Internal(format!("Expected 16 bytes, got {}", bytes.len())))
+        }
+
+        fn decode_fields(fields: &[proto::Field]) -> Result<Vec<Field>, Error> {
+            fields.iter().map(Field::try_from).collect()
+        }
+
+        fn decode_boxed_field(
+            field: &Option<Box<proto::Field>>,
+            name: &str,
+        ) -> Result<Box<Field>, Error> {
+            let field = field.as_deref().ok_or_else(|| missing(name))?;
+            Ok(Box::new(Field::try_from(field)?))
+        }
+
+        fn decode_boxed_type(
+            data_type: &Option<Box<proto::ArrowType>>,
+            name: &str,
+        ) -> Result<Box<DataType>, Error> {
+            let data_type = data_type.as_deref().ok_or_else(|| missing(name))?;
+            Ok(Box::new(DataType::try_from(data_type)?))
+        }
+
+        impl From<&Field> for proto::Field {
+            fn from(field: &Field) -> Self {
+                Self {
+                    name: field.name().to_string(),
+                    arrow_type: Some(Box::new(field.data_type().into())),
+                    nullable: field.is_nullable(),
+                }
+            }
+        }
+
+        impl TryFrom<&proto::Field> for Field {
+            type Error = Error;
+
+            fn try_from(field: &proto::Field) -> Result<Self, Error> {
+                let data_type = decode_boxed_type(&field.arrow_type, "Field.arrow_type")?;
+                Ok(Field::new(&field.name, *data_type, field.nullable))
+            }
+        }
+
+        impl From<&Schema> for proto::Schema {
+            fn from(schema: &Schema) -> Self {
+                Self {
+                    fields: schema.fields().iter().map(Into::into).collect(),
+                    metadata: schema.metadata().clone(),
+                }
+            }
+        }
+
+        impl TryFrom<&proto::Schema> for Schema {
+            type Error = Error;
+
+            fn try_from(schema: &proto::Schema) -> Result<Self, Error> {
+                Ok(Schema::new_with_metadata(
+                    decode_fields(&schema.fields)?,
+                    schema.metadata.clone(),
+                ))
+            }
+        }
+
+        impl From<&DataType> for proto::ArrowType {
+            fn from(data_type: &DataType) -> Self {
+                let empty = proto::EmptyMessage {};
+                let arrow_type_enum = match data_type {
+                    DataType::Null => ArrowTypeEnum::None(empty),
+                    DataType::Boolean => ArrowTypeEnum::Bool(empty),
+                    DataType::Int8 => ArrowTypeEnum::Int8(empty),
+                    DataType::Int16 => ArrowTypeEnum::Int16(empty),
+                    DataType::Int32 => ArrowTypeEnum::Int32(empty),
+                    DataType::Int64 => ArrowTypeEnum::Int64(empty),
+                    DataType::UInt8 => ArrowTypeEnum::Uint8(empty),
+                    DataType::UInt16 => ArrowTypeEnum::Uint16(empty),
+                    DataType::UInt32 => ArrowTypeEnum::Uint32(empty),
+                    DataType::UInt64 => ArrowTypeEnum::Uint64(empty),
+                    DataType::Float16 => ArrowTypeEnum::Float16(empty),
+                    DataType::Float32 => ArrowTypeEnum::Float32(empty),
+                    DataType::Float64 => ArrowTypeEnum::Float64(empty),
+                    DataType::Timestamp(unit, timezone) => {
+                        ArrowTypeEnum::Timestamp(proto::Timestamp {
+                            time_unit: encode_time_unit(unit),
+                            timezone: timezone.clone().unwrap_or_default(),
+                        })
+                    }
+                    DataType::Date32 => ArrowTypeEnum::Date32(empty),
+                    DataType::Date64 => ArrowTypeEnum::Date64(empty),
+                    DataType::Time32(unit) => ArrowTypeEnum::Time32(encode_time_unit(unit)),
+                    DataType::Time64(unit) => ArrowTypeEnum::Time64(encode_time_unit(unit)),
+                    DataType::Duration(unit) => ArrowTypeEnum::Duration(encode_time_unit(unit)),
+                    DataType::Interval(unit) => ArrowTypeEnum::Interval(encode_interval_unit(unit)),
+                    DataType::Binary => ArrowTypeEnum::Binary(empty),
+                    DataType::FixedSizeBinary(size) => ArrowTypeEnum::FixedSizeBinary(*size),
+                    DataType::LargeBinary => ArrowTypeEnum::LargeBinary(empty),
+                    DataType::Utf8 => ArrowTypeEnum::Utf8(empty),
+                    DataType::LargeUtf8 => ArrowTypeEnum::LargeUtf8(empty),
+                    DataType::List(field) => ArrowTypeEnum::List(Box::new(proto::List {
+                        field: Some(Box::new(field.as_ref().into())),
+                    })),
+                    DataType::FixedSizeList(field, size) => {
+                        ArrowTypeEnum::FixedSizeList(Box::new(proto::FixedSizeList {
+                            field: Some(Box::new(field.as_ref().into())),
+                            size: *size,
+                        }))
+                    }
+                    DataType::LargeList(field) => ArrowTypeEnum::LargeList(Box::new(proto::List {
+                        field: Some(Box::new(field.as_ref().into())),
+                    })),
+                    DataType::Struct(fields) => ArrowTypeEnum::Struct(proto::Struct {
+                        fields: fields.iter().map(Into::into).collect(),
+                    }),
+                    DataType::Union(fields, type_ids, mode) => {
+                        let mode = match mode {
+                            UnionMode::Sparse => proto::UnionMode::Sparse,
+                            UnionMode::Dense => proto::UnionMode::Dense,
+                        };
+                        ArrowTypeEnum::Union(proto::Union {
+                            fields: fields.iter().map(Into::into).collect(),
+                            type_ids: type_ids.iter().map(|id| *id as i32).collect(),
+                            mode: mode as i32,
+                        })
+                    }
+                    DataType::Dictionary(key, value) => {
+                        ArrowTypeEnum::Dictionary(Box::new(proto::Dictionary {
+                            key: Some(Box::new(key.as_ref().into())),
+                            value: Some(Box::new(value.as_ref().into())),
+                        }))
+                    }
+                    DataType::Decimal128(precision, scale) => {
+                        ArrowTypeEnum::Decimal128(proto::Decimal {
+                            precision: *precision as u32,
+                            scale: *scale as i32,
+                        })
+                    }
+                    DataType::Decimal256(precision, scale) => {
+                        ArrowTypeEnum::Decimal256(proto::Decimal {
+                            precision: *precision as u32,
+                            scale: *scale as i32,
+                        })
+                    }
+                    DataType::Map(field, keys_sorted) => ArrowTypeEnum::Map(Box::new(proto::Map {
+                        field: Some(Box::new(field.as_ref().into())),
+                        keys_sorted: *keys_sorted,
+                    })),
+                };
+                Self {
+                    arrow_type_enum: Some(arrow_type_enum),
+                }
+            }
+        }
+
+        impl TryFrom<&proto::ArrowType> for DataType {
+            type Error = Error;
+
+            fn try_from(arrow_type: &proto::ArrowType) -> Result<Self, Error> {
+                let arrow_type_enum = arrow_type
+                    .arrow_type_enum
+                    .as_ref()
+                    .ok_or_else(|| missing("ArrowType.arrow_type_enum"))?;
+                Ok(match arrow_type_enum {
+                    ArrowTypeEnum::None(_) => DataType::Null,
+                    ArrowTypeEnum::Bool(_) => DataType::Boolean,
+                    ArrowTypeEnum::Int8(_) => DataType::Int8,
+                    ArrowTypeEnum::Int16(_) => DataType::Int16,
+                    ArrowTypeEnum::Int32(_) => DataType::Int32,
+                    ArrowTypeEnum::Int64(_) => DataType::Int64,
+                    ArrowTypeEnum::Uint8(_) => DataType::UInt8,
+                    ArrowTypeEnum::Uint16(_) => DataType::UInt16,
+                    ArrowTypeEnum::Uint32(_) => DataType::UInt32,
+                    ArrowTypeEnum::Uint64(_) => DataType::UInt64,
+                    ArrowTypeEnum::Float16(_) => DataType::Float16,
+                    ArrowTypeEnum::Float32(_) => DataType::Float32,
+                    ArrowTypeEnum::Float64(_) => DataType::Float64,
+                    ArrowTypeEnum::Utf8(_) => DataType::Utf8,
+                    ArrowTypeEnum::LargeUtf8(_) => DataType::LargeUtf8,
+                    ArrowTypeEnum::Binary(_) => DataType::Binary,
+                    ArrowTypeEnum::FixedSizeBinary(size) => DataType::FixedSizeBinary(*size),
+                    ArrowTypeEnum::LargeBinary(_) => DataType::LargeBinary,
+                    ArrowTypeEnum::Date32(_) => DataType::Date32,
+                    ArrowTypeEnum::Date64(_) => DataType::Date64,
+                    ArrowTypeEnum::Duration(unit) => DataType::Duration(decode_time_unit(*unit)?),
+                    ArrowTypeEnum::Timestamp(timestamp) => DataType::Timestamp(
+                        decode_time_unit(timestamp.time_unit)?,
+                        decode_timezone(&timestamp.timezone),
+                    ),
+                    ArrowTypeEnum::Time32(unit) => DataType::Time32(decode_time_unit(*unit)?),
+                    ArrowTypeEnum::Time64(unit) => DataType::Time64(decode_time_unit(*unit)?),
+                    ArrowTypeEnum::Interval(unit) => {
+                        DataType::Interval(decode_interval_unit(*unit)?)
+                    }
+                    ArrowTypeEnum::Decimal128(decimal) => {
+                        DataType::Decimal128(decimal.precision as _, decimal.scale as _)
+                    }
+                    ArrowTypeEnum::Decimal256(decimal) => {
+                        DataType::Decimal256(decimal.precision as _, decimal.scale as _)
+                    }
+                    ArrowTypeEnum::List(list) => {
+                        DataType::List(decode_boxed_field(&list.field, "List.field")?)
+                    }
+                    ArrowTypeEnum::LargeList(list) => {
+                        DataType::LargeList(decode_boxed_field(&list.field, "List.field")?)
+                    }
+                    ArrowTypeEnum::FixedSizeList(list) => DataType::FixedSizeList(
+                        decode_boxed_field(&list.field, "FixedSizeList.field")?,
+                        list.size,
+                    ),
+                    ArrowTypeEnum::Struct(fields) => {
+                        DataType::Struct(decode_fields(&fields.fields)?)
+                    }
+                    ArrowTypeEnum::Union(union) => {
+                        let mode = match proto::UnionMode::from_i32(union.mode) {
+                            Some(proto::UnionMode::Sparse) => UnionMode::Sparse,
+                            Some(proto::UnionMode::Dense) => UnionMode::Dense,
+                            None => {
+                                return Err(Error::Internal(format!(
+                                    "Unknown union mode {}",
+                                    union.mode
+                                )))
+                            }
+                        };
+                        DataType::Union(
+                            decode_fields(&union.fields)?,
+                            union.type_ids.iter().map(|id| *id as i8).collect(),
+                            mode,
+                        )
+                    }
+                    ArrowTypeEnum::Dictionary(dictionary) => DataType::Dictionary(
+                        decode_boxed_type(&dictionary.key, "Dictionary.key")?,
+                        decode_boxed_type(&dictionary.value, "Dictionary.value")?,
+                    ),
+                    ArrowTypeEnum::Map(map) => DataType::Map(
+                        decode_boxed_field(&map.field, "Map.field")?,
+                        map.keys_sorted,
+                    ),
+                })
+            }
+        }
+
+        fn encode_timestamp(unit: TimeUnit, value: i64, timezone: &Option<String>) -> Value {
+            Value::TimestampValue(proto::ScalarTimestamp {
+                time_unit: encode_time_unit(&unit),
+                value,
+                timezone: timezone.clone().unwrap_or_default(),
+            })
+        }
+
+        impl From<&ScalarValue> for proto::ScalarValue {
+            fn from(value: &ScalarValue) -> Self {
+                let value = match value {
+                    ScalarValue::Boolean(Some(v)) => Value::BoolValue(*v),
+                    ScalarValue::Float32(Some(v)) => Value::Float32Value(*v),
+                    ScalarValue::Float64(Some(v)) => Value::Float64Value(*v),
+                    ScalarValue::Decimal128(Some(v), precision, scale) => {
+                        Value::Decimal128Value(proto::ScalarDecimal128 {
+                            value: v.to_le_bytes().to_vec(),
+                            precision: *precision as u32,
+                            scale: *scale as i32,
+                        })
+                    }
+                    ScalarValue::Int8(Some(v)) => Value::Int8Value(*v as i32),
+                    ScalarValue::Int16(Some(v)) => Value::Int16Value(*v as i32),
+                    ScalarValue::Int32(Some(v)) => Value::Int32Value(*v),
+                    ScalarValue::Int64(Some(v)) => Value::Int64Value(*v),
+                    ScalarValue::UInt8(Some(v)) => Value::Uint8Value(*v as u32),
+                    ScalarValue::UInt16(Some(v)) => Value::Uint16Value(*v as u32),
+                    ScalarValue::UInt32(Some(v)) => Value::Uint32Value(*v),
+                    ScalarValue::UInt64(Some(v)) => Value::Uint64Value(*v),
+                    ScalarValue::Utf8(Some(v)) => Value::Utf8Value(v.clone()),
+                    ScalarValue::LargeUtf8(Some(v)) => Value::LargeUtf8Value(v.clone()),
+                    ScalarValue::Binary(Some(v)) => Value::BinaryValue(v.clone()),
+                    ScalarValue::FixedSizeBinary(size, Some(v)) => {
+                        Value::FixedSizeBinaryValue(proto::ScalarFixedSizeBinary {
+                            size: *size,
+                            value: v.clone(),
+                        })
+                    }
+                    ScalarValue::LargeBinary(Some(v)) => Value::LargeBinaryValue(v.clone()),
+                    ScalarValue::List(Some(values), field) => Value::ListValue(proto::ScalarList {
+                        field: Some(field.as_ref().into()),
+                        values: values.iter().map(Into::into).collect(),
+                    }),
+                    ScalarValue::Date32(Some(v)) => Value::Date32Value(*v),
+                    ScalarValue::Date64(Some(v)) => Value::Date64Value(*v),
+                    ScalarValue::Time64(Some(v)) => Value::Time64Value(*v),
+                    ScalarValue::TimestampSecond(Some(v), timezone) => {
+                        encode_timestamp(TimeUnit::Second, *v, timezone)
+                    }
+                    ScalarValue::TimestampMillisecond(Some(v), timezone) => {
+                        encode_timestamp(TimeUnit::Millisecond, *v, timezone)
+                    }
+                    ScalarValue::TimestampMicrosecond(Some(v), timezone) => {
+                        encode_timestamp(TimeUnit::Microsecond, *v, timezone)
+                    }
+                    ScalarValue::TimestampNanosecond(Some(v), timezone) => {
+                        encode_timestamp(TimeUnit::Nanosecond, *v, timezone)
+                    }
+                    ScalarValue::IntervalYearMonth(Some(v)) => Value::IntervalYearMonthValue(*v),
+                    ScalarValue::IntervalDayTime(Some(v)) => Value::IntervalDayTimeValue(*v),
+                    ScalarValue::IntervalMonthDayNano(Some(v)) => {
+                        Value::IntervalMonthDayNanoValue(v.to_le_bytes().to_vec())
+                    }
+                    ScalarValue::Struct(Some(values), fields) => {
+                        Value::StructValue(proto::ScalarStruct {
+                            fields: fields.iter().map(Into::into).collect(),
+                            values: values.iter().map(Into::into).collect(),
+                        })
+                    }
+                    ScalarValue::Dictionary(index_type, value) => {
+                        Value::DictionaryValue(Box::new(proto::ScalarDictionary {
+                            index_type: Some(index_type.as_ref().into()),
+                            value: Some(Box::new(value.as_ref().into())),
+                        }))
+                    }
+                    // every other value is a null, sent with its type
+                    _ => Value::NullValue((&value.get_datatype()).into()),
+                };
+                Self { value: Some(value) }
+            }
+        }
+
+        impl TryFrom<&proto::ScalarValue> for ScalarValue {
+            type Error = Error;
+
+            fn try_from(value: &proto::ScalarValue) -> Result<Self, Error> {
+                let value = value
+                    .value
+                    .as_ref()
+                    .ok_or_else(|| missing("ScalarValue.value"))?;
+                Ok(match value {
+                    Value::NullValue(data_type) => match DataType::try_from(data_type)? {
+                        DataType::Null => ScalarValue::Null,
+                        data_type => ScalarValue::try_from(&data_type)?,
+                    },
+                    Value::BoolValue(v) => ScalarValue::Boolean(Some(*v)),
+                    Value::Utf8Value(v) => ScalarValue::Utf8(Some(v.clone())),
+                    Value::LargeUtf8Value(v) => ScalarValue::LargeUtf8(Some(v.clone())),
+                    Value::Int8Value(v) => ScalarValue::Int8(Some(*v as i8)),
+                    Value::Int16Value(v) => ScalarValue::Int16(Some(*v as i16)),
+                    Value::Int32Value(v) => ScalarValue::Int32(Some(*v)),
+                    Value::Int64Value(v) => ScalarValue::Int64(Some(*v)),
+                    Value::Uint8Value(v) => ScalarValue::UInt8(Some(*v as u8)),
+                    Value::Uint16Value(v) => ScalarValue::UInt16(Some(*v as u16)),
+                    Value::Uint32Value(v) => ScalarValue::UInt32(Some(*v)),
+                    Value::Uint64Value(v) => ScalarValue::UInt64(Some(*v)),
+                    Value::Float32Value(v) => ScalarValue::Float32(Some(*v)),
+                    Value::Float64Value(v) => ScalarValue::Float64(Some(*v)),
+                    Value::Date32Value(v) => ScalarValue::Date32(Some(*v)),
+                    Value::Date64Value(v) => ScalarValue::Date64(Some(*v)),
+                    Value::Time64Value(v) => ScalarValue::Time64(Some(*v)),
+                    Value::TimestampValue(timestamp) => {
+                        let value = Some(timestamp.value);
+                        let timezone = decode_timezone(&timestamp.timezone);
+                        match decode_time_unit(timestamp.time_unit)? {
+                            TimeUnit::Second => ScalarValue::TimestampSecond(value, timezone),
+                            TimeUnit::Millisecond => {
+                                ScalarValue::TimestampMillisecond(value, timezone)
+                            }
+                            TimeUnit::Microsecond => {
+                                ScalarValue::TimestampMicrosecond(value, timezone)
+                            }
+                            TimeUnit::Nanosecond => {
+                                ScalarValue::TimestampNanosecond(value, timezone)
+                            }
+                        }
+                    }
+                    Value::IntervalYearMonthValue(v) => ScalarValue::IntervalYearMonth(Some(*v)),
+                    Value::IntervalDayTimeValue(v) => ScalarValue::IntervalDayTime(Some(*v)),
+                    Value::IntervalMonthDayNanoValue(v) => {
+                        ScalarValue::IntervalMonthDayNano(Some(decode_i128(v)?))
+                    }
+                    Value::Decimal128Value(decimal) => ScalarValue::Decimal128(
+                        Some(decode_i128(&decimal.value)?),
+                        decimal.precision as _,
+                        decimal.scale as _,
+                    ),
+                    Value::BinaryValue(v) => ScalarValue::Binary(Some(v.clone())),
+                    Value::LargeBinaryValue(v) => ScalarValue::LargeBinary(Some(v.clone())),
+                    Value::FixedSizeBinaryValue(v) => {
+                        ScalarValue::FixedSizeBinary(v.size, Some(v.value.clone()))
+                    }
+                    Value::ListValue(list) => {
+                        let field = list
+                            .field
+                            .as_ref()
+                            .ok_or_else(|| missing("ScalarList.field"))?;
+                        let values = list
+                            .values
+                            .iter()
+                            .map(ScalarValue::try_from)
+                            .collect::<Result<Vec<_>, Error>>()?;
+                        ScalarValue::List(Some(values), Box::new(Field::try_from(field)?))
+                    }
+                    Value::StructValue(value) => {
+                        let values = value
+                            .values
+                            .iter()
+                            .map(ScalarValue::try_from)
+                            .collect::<Result<Vec<_>, Error>>()?;
+                        ScalarValue::Struct(Some(values), Box::new(decode_fields(&value.fields)?))
+                    }
+                    Value::DictionaryValue(dictionary) => {
+                        let index_type = dictionary
+                            .index_type
+                            .as_ref()
+                            .ok_or_else(|| missing("ScalarDictionary.index_type"))?;
+                        let value = dictionary
+                            .value
+                            .as_deref()
+                            .ok_or_else(|| missing("ScalarDictionary.value"))?;
+                        ScalarValue::Dictionary(
+                            Box::new(DataType::try_from(index_type)?),
+                            Box::new(ScalarValue::try_from(value)?),
+                        )
+                    }
+                })
+            }
+        }
+    };
+}
+
+/// Types of DataFusion plans
+mod datafusion_types {
+    use datafusion::arrow::datatypes::{
+        DataType, Field, IntervalUnit, Schema, TimeUnit, UnionMode,
+    };
+    use datafusion::error::DataFusionError as Error;
+    use datafusion::scalar::ScalarValue;
+
+    arrow_codec!();
+}
+
+/// Types of `common::scalar`, on the arrow version of the common crate
+mod common_types {
+    use arrow::datatypes::{DataType, Field, IntervalUnit, Schema, TimeUnit, UnionMode};
+    use common::error::RapidashError as Error;
+    use common::scalar::ScalarValue;
+
+    arrow_codec!();
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::arrow::datatypes::{DataType, Field, IntervalUnit, TimeUnit};
+    use datafusion::scalar::ScalarValue;
+
+    use crate::proto;
+
+    fn round_trip_type(data_type: DataType) {
+        let encoded = proto::ArrowType::from(&data_type);
+        assert_eq!(DataType::try_from(&encoded).unwrap(), data_type);
+    }
+
+    fn round_trip_scalar(value: ScalarValue) {
+        let encoded = proto::ScalarValue::from(&value);
+        assert_eq!(ScalarValue::try_from(&encoded).unwrap(), value);
+    }
+
+    #[test]
+    fn test_data_type_round_trip() {
+        round_trip_type(DataType::Int32);
+        round_trip_type(DataType::Decimal128(38, 10));
+        round_trip_type(DataType::Timestamp(
+            TimeUnit::Millisecond,
+            Some("UTC".to_string()),
+        ));
+        round_trip_type(DataType::Interval(IntervalUnit::MonthDayNano));
+        round_trip_type(DataType::List(Box::new(Field::new(
+            "item",
+            DataType::Utf8,
+            true,
+        ))));
+        round_trip_type(DataType::Dictionary(
+            Box::new(DataType::Int16),
+            Box::new(DataType::Utf8),
+        ));
+        round_trip_type(DataType::Struct(vec![
+            Field::new("a", DataType::Float64, false),
+            Field::new("b", DataType::Date32, true),
+        ]));
+    }
+
+    #[test]
+    fn test_scalar_value_round_trip() {
+        round_trip_scalar(ScalarValue::Null);
+        round_trip_scalar(ScalarValue::Int8(Some(-3)));
+        round_trip_scalar(ScalarValue::Int64(None));
+        round_trip_scalar(ScalarValue::Utf8(Some("rapidash".to_string())));
+        round_trip_scalar(ScalarValue::Decimal128(Some(-12345), 10, 2));
+        round_trip_scalar(ScalarValue::TimestampNanosecond(Some(1), None));
+        round_trip_scalar(ScalarValue::IntervalMonthDayNano(Some(i128::MAX)));
+        round_trip_scalar(ScalarValue::List(
+            Some(vec![ScalarValue::Int32(Some(1)), ScalarValue::Int32(None)]),
+            Box::new(Field::new("item", DataType::Int32, true)),
+        ));
+        round_trip_scalar(ScalarValue::Dictionary(
+            Box::new(DataType::Int32),
+            Box::new(ScalarValue::Utf8(Some("a".to_string()))),
+        ));
+    }
+}
@@ -0,0 +1,119 @@
+//! Compression of rpc messages and of shuffle data.
+//!
+//! Rpc messages are compressed by tonic, servers and clients accept gzip
+//! whatever their own setting so differently configured peers still talk.
+//! Shuffle data is compressed separately with arrow IPC body compression,
+//! both in shuffle files and in the flight streams serving them.
+
+use common::config::Config;
+use datafusion::arrow::error::Result as ArrowResult;
+use datafusion::arrow::ipc::writer::IpcWriteOptions;
+use datafusion::arrow::ipc::CompressionType;
+use tonic::codec::CompressionEncoding;
+
+/// Compression settings, read from `rapidash.rpc.compression` and
+/// `rapidash.shuffle.compression`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CompressionConfig {
+    /// Encoding of sent rpc messages, None sends them uncompressed
+    pub rpc: Option<CompressionEncoding>,
+    /// Compression of IPC bodies of shuffle data, None writes them uncompressed
+    pub shuffle: Option<CompressionType>,
+}
+
+impl CompressionConfig {
+    pub fn from_config(config: &Config) -> Self {
+        // the values are validated by the configuration
+        let rpc = match config.rpc_compression().as_str() {
+            "gzip" => Some(CompressionEncoding::Gzip),
+            _ => None,
+        };
+        let shuffle = match config.shuffle_compression().as_str() {
+            "lz4" => Some(CompressionType::LZ4_FRAME),
+            "zstd" => Some(CompressionType::ZSTD),
+            _ => None,
+        };
+        Self { rpc, shuffle }
+    }
+
+    /// IPC options of shuffle files and flight streams
+    pub fn ipc_write_options(&self) -> ArrowResult<IpcWriteOptions> {
+        IpcWriteOptions::default().try_with_compression(self.shuffle)
+    }
+}
+
+/// Apply the rpc compression of a `CompressionConfig` to a client or server
+/// generated by tonic
+#[macro_export]
+macro_rules! compressed {
+    ($service:expr, $compression:expr) => {{
+        let service = $service.accept_compressed(::tonic::codec::CompressionEncoding::Gzip);
+        match $compression.rpc {
+            Some(encoding) => service.send_compressed(encoding),
+            None => service,
+        }
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use common::config::{RPC_COMPRESSION, SHUFFLE_COMPRESSION};
+    use datafusion::arrow::array::Int64Array;
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use datafusion::arrow::ipc::reader::StreamReader;
+    use datafusion::arrow::ipc::writer::StreamWriter;
+    use datafusion::arrow::record_batch::RecordBatch;
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[test]
+    fn test_from_config() {
+        let compression = CompressionConfig::from_config(&Config::new().unwrap());
+        assert_eq!(compression, CompressionConfig::default());
+
+        let config = Config::builder()
+            .set(RPC_COMPRESSION, "gzip")
+            .set(SHUFFLE_COMPRESSION, "zstd")
+            .build()
+            .unwrap();
+        let compression = CompressionConfig::from_config(&config);
+        assert_eq!(compression.rpc, Some(CompressionEncoding::Gzip));
+        assert_eq!(compression.shuffle, Some(CompressionType::ZSTD));
+
+        assert!(Config::builder()
+            .set(SHUFFLE_COMPRESSION, "snappy")
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn test_compressed_ipc() {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, false)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int64Array::from(vec![7; 4096]))],
+        )
+        .unwrap();
+
+        let write = |compression: CompressionConfig| {
+            let options = compression.ipc_write_options().unwrap();
+            let mut writer =
+                StreamWriter::try_new_with_options(Vec::new(), &schema, options).unwrap();
+            writer.write(&batch).unwrap();
+            writer.finish().unwrap();
+            writer.into_inner().unwrap()
+        };
+        let plain = write(CompressionConfig::default());
+        for shuffle in [CompressionType::LZ4_FRAME, CompressionType::ZSTD] {
+            let compressed = write(CompressionConfig {
+                shuffle: Some(shuffle),
+                ..Default::default()
+            });
+            assert!(compressed.len() < plain.len());
+
+            let mut reader = StreamReader::try_new(compressed.as_slice(), None).unwrap();
+            assert_eq!(reader.next().unwrap().unwrap(), batch);
+        }
+    }
+}
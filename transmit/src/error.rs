@@ -0,0 +1,311 @@
+//! Conversion between `RapidashError` and gRPC status.
+//!
+//! The status message is the display of the error, the details are an encoded
+//! `ErrorDetail` so the receiving side can rebuild the same variant. Errors
+//! wrapping foreign error types are rebuilt from their message.
+
+use std::io;
+
+use arrow::error::ArrowError;
+use common::error::{RapidashError, SchemaError};
+use parquet::errors::ParquetError;
+use prost::Message;
+use sqlparser::parser::ParserError;
+use tonic::codegen::Bytes;
+use tonic::{Code, Status};
+
+use crate::proto::{
+    ErrorCode, ErrorDetail, FetchPartitionError, SchemaErrorDetail, SchemaErrorKind,
+};
+
+/// Stable code of the error, the code of the innermost error for a context
+pub fn error_code(error: &RapidashError) -> ErrorCode {
+    match error {
+        RapidashError::General(_) => ErrorCode::General,
+        RapidashError::GrpcConnectionError(_) => ErrorCode::GrpcConnection,
+        RapidashError::GrpcActionError(_) => ErrorCode::GrpcAction,
+        RapidashError::FetchFailed(..) => ErrorCode::FetchFailed,
+        RapidashError::Cancelled => ErrorCode::Cancelled,
+        RapidashError::ArrowError(_) => ErrorCode::Arrow,
+        RapidashError::ParquetError(_) => ErrorCode::Parquet,
+        RapidashError::IoError(_) => ErrorCode::Io,
+        RapidashError::SQL(_) => ErrorCode::Sql,
+        RapidashError::NotImplemented(_) => ErrorCode::NotImplemented,
+        RapidashError::Internal(_) => ErrorCode::Internal,
+        RapidashError::SchemaError(_) => ErrorCode::Schema,
+        RapidashError::Execution(_) => ErrorCode::Execution,
+        RapidashError::ResourcesExhausted(_) => ErrorCode::ResourcesExhausted,
+        RapidashError::External(_) => ErrorCode::External,
+        RapidashError::Incompatible(_) => ErrorCode::Incompatible,
+        RapidashError::Context(_, error) => error_code(error),
+    }
+}
+
+/// Whether running the failed action again may succeed
+pub fn is_retryable(error: &RapidashError) -> bool {
+    match error {
+        RapidashError::GrpcConnectionError(_)
+        | RapidashError::FetchFailed(..)
+        | RapidashError::IoError(_)
+        | RapidashError::ResourcesExhausted(_) => true,
+        RapidashError::Context(_, error) => is_retryable(error),
+        _ => false,
+    }
+}
+
+fn grpc_code(code: ErrorCode) -> Code {
+    match code {
+        ErrorCode::General => Code::Unknown,
+        ErrorCode::GrpcConnection => Code::Unavailable,
+        ErrorCode::FetchFailed => Code::Aborted,
+        ErrorCode::Cancelled => Code::Cancelled,
+        ErrorCode::Sql | ErrorCode::Schema => Code::InvalidArgument,
+        ErrorCode::NotImplemented => Code::Unimplemented,
+        ErrorCode::ResourcesExhausted => Code::ResourceExhausted,
+        ErrorCode::Incompatible => Code::FailedPrecondition,
+        ErrorCode::GrpcAction
+        | ErrorCode::Arrow
+        | ErrorCode::Parquet
+        | ErrorCode::Io
+        | ErrorCode::Internal
+        | ErrorCode::Execution
+        | ErrorCode::External => Code::Internal,
+    }
+}
+
+/// Status of the error, with an `ErrorDetail` in its details
+pub fn to_status(error: &RapidashError) -> Status {
+    let code = error_code(error);
+    let mut detail = ErrorDetail {
+        code: code as i32,
+        retryable: is_retryable(error),
+        ..Default::default()
+    };
+
+    let mut inner = error;
+    while let RapidashError::Context(context, error) = inner {
+        detail.context.push(context.clone());
+        inner = error;
+    }
+    detail.message = match inner {
+        RapidashError::General(message)
+        | RapidashError::GrpcConnectionError(message)
+        | RapidashError::GrpcActionError(message)
+        | RapidashError::NotImplemented(message)
+        | RapidashError::Internal(message)
+        | RapidashError::Execution(message)
+        | RapidashError::ResourcesExhausted(message)
+        | RapidashError::Incompatible(message) => message.clone(),
+        RapidashError::FetchFailed(executor_id, stage_id, partition_id, message) => {
+            detail.fetch_failed = Some(FetchPartitionError {
+                executor_id: executor_id.clone(),
+                map_stage_id: *stage_id as u32,
+                map_partition_id: *partition_id as u32,
+            });
+            message.clone()
+        }
+        RapidashError::SQL(ParserError::TokenizerError(message))
+        | RapidashError::SQL(ParserError::ParserError(message)) => message.clone(),
+        RapidashError::SchemaError(error) => {
+            detail.schema_error = Some(encode_schema_error(error));
+            error.to_string()
+        }
+        RapidashError::ArrowError(error) => error.to_string(),
+        RapidashError::ParquetError(error) => error.to_string(),
+        RapidashError::IoError(error) => error.to_string(),
+        RapidashError::External(error) => error.to_string(),
+        RapidashError::Cancelled | RapidashError::Context(..) => String::new(),
+    };
+
+    Status::with_details(
+        grpc_code(code),
+        error.to_string(),
+        Bytes::from(detail.encode_to_vec()),
+    )
+}
+
+/// Rebuild the error of a status from `to_status`, other statuses become grpc errors
+pub fn from_status(status: &Status) -> RapidashError {
+    let detail = match ErrorDetail::decode(status.details()) {
+        // an empty message decodes too, but only `to_status` sets details
+        Ok(detail) if !status.details().is_empty() => detail,
+        _ => {
+            let message = status.message().to_string();
+            return match status.code() {
+                Code::Unavailable => RapidashError::GrpcConnectionError(message),
+                Code::Cancelled => RapidashError::Cancelled,
+                Code::Unimplemented => RapidashError::NotImplemented(message),
+                Code::ResourceExhausted => RapidashError::ResourcesExhausted(message),
+                _ => RapidashError::GrpcActionError(message),
+            };
+        }
+    };
+
+    let code = detail.code();
+    let message = detail.message;
+    let error = match code {
+        ErrorCode::General => RapidashError::General(message),
+        ErrorCode::GrpcConnection => RapidashError::GrpcConnectionError(message),
+        ErrorCode::GrpcAction => RapidashError::GrpcActionError(message),
+        ErrorCode::FetchFailed => {
+            let fetch = detail.fetch_failed.unwrap_or_default();
+            RapidashError::FetchFailed(
+                fetch.executor_id,
+                fetch.map_stage_id as usize,
+                fetch.map_partition_id as usize,
+                message,
+            )
+        }
+        ErrorCode::Cancelled => RapidashError::Cancelled,
+        ErrorCode::Arrow => RapidashError::ArrowError(ArrowError::ExternalError(message.into())),
+        ErrorCode::Parquet => RapidashError::ParquetError(ParquetError::General(message)),
+        ErrorCode::Io => RapidashError::IoError(io::Error::other(message)),
+        ErrorCode::Sql => RapidashError::SQL(ParserError::ParserError(message)),
+        ErrorCode::NotImplemented => RapidashError::NotImplemented(message),
+        ErrorCode::Internal => RapidashError::Internal(message),
+        ErrorCode::Schema => match detail.schema_error {
+            Some(error) => RapidashError::SchemaError(decode_schema_error(error)),
+            None => RapidashError::General(message),
+        },
+        ErrorCode::Execution => RapidashError::Execution(message),
+        ErrorCode::ResourcesExhausted => RapidashError::ResourcesExhausted(message),
+        ErrorCode::External => RapidashError::External(message.into()),
+        ErrorCode::Incompatible => RapidashError::Incompatible(message),
+    };
+
+    detail
+        .context
+        .into_iter()
+        .rev()
+        .fold(error, |error, context| {
+            RapidashError::Context(context, Box::new(error))
+        })
+}
+
+fn encode_schema_error(error: &SchemaError) -> SchemaErrorDetail {
+    match error {
+        SchemaError::AmbiguousReference { qualifier, name } => SchemaErrorDetail {
+            kind: SchemaErrorKind::AmbiguousReference as i32,
+            qualifier: qualifier.clone().unwrap_or_default(),
+            name: name.clone(),
+            ..Default::default()
+        },
+        SchemaError::DuplicateQualifiedField { qualifier, name } => SchemaErrorDetail {
+            kind: SchemaErrorKind::DuplicateQualifiedField as i32,
+            qualifier: qualifier.clone(),
+            name: name.clone(),
+            ..Default::default()
+        },
+        SchemaError::DuplicateUnqualifiedField { name } => SchemaErrorDetail {
+            kind: SchemaErrorKind::DuplicateUnqualifiedField as i32,
+            name: name.clone(),
+            ..Default::default()
+        },
+        SchemaError::FieldNotFound {
+            qualifier,
+            name,
+            valid_fields,
+        } => SchemaErrorDetail {
+            kind: SchemaErrorKind::FieldNotFound as i32,
+            qualifier: qualifier.clone().unwrap_or_default(),
+            name: name.clone(),
+            has_valid_fields: valid_fields.is_some(),
+            valid_fields: valid_fields.clone().unwrap_or_default(),
+        },
+    }
+}
+
+fn decode_schema_error(error: SchemaErrorDetail) -> SchemaError {
+    let kind = error.kind();
+    let qualifier = Some(error.qualifier).filter(|q| !q.is_empty());
+    let name = error.name;
+    match kind {
+        SchemaErrorKind::AmbiguousReference => SchemaError::AmbiguousReference { qualifier, name },
+        SchemaErrorKind::DuplicateQualifiedField => SchemaError::DuplicateQualifiedField {
+            qualifier: qualifier.unwrap_or_default(),
+            name,
+        },
+        SchemaErrorKind::DuplicateUnqualifiedField => {
+            SchemaError::DuplicateUnqualifiedField { name }
+        }
+        SchemaErrorKind::FieldNotFound => SchemaError::FieldNotFound {
+            qualifier,
+            name,
+            valid_fields: Some(error.valid_fields).filter(|_| error.has_valid_fields),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(error: RapidashError) -> RapidashError {
+        from_status(&to_status(&error))
+    }
+
+    #[test]
+    fn test_round_trip_keeps_variant() {
+        let error = round_trip(RapidashError::FetchFailed(
+            "executor".to_string(),
+            1,
+            2,
+            "connection reset".to_string(),
+        ));
+        assert!(matches!(
+            error,
+            RapidashError::FetchFailed(ref executor, 1, 2, ref message)
+                if executor == "executor" && message == "connection reset"
+        ));
+
+        let error = RapidashError::SchemaError(SchemaError::FieldNotFound {
+            qualifier: Some("t".to_string()),
+            name: "a".to_string(),
+            valid_fields: Some(vec!["t.b".to_string()]),
+        });
+        assert_eq!(
+            round_trip(error).to_string(),
+            "Schema error: No field named 't.a'. Valid fields are 't.b'."
+        );
+
+        let error = round_trip(RapidashError::SQL(ParserError::ParserError(
+            "x".to_string(),
+        )));
+        assert!(matches!(error, RapidashError::SQL(ParserError::ParserError(ref m)) if m == "x"));
+        assert!(matches!(
+            round_trip(RapidashError::Cancelled),
+            RapidashError::Cancelled
+        ));
+    }
+
+    #[test]
+    fn test_context_chain() {
+        let error = RapidashError::Context(
+            "running stage 1".to_string(),
+            Box::new(RapidashError::Context(
+                "reading partition 0".to_string(),
+                Box::new(RapidashError::ResourcesExhausted("memory".to_string())),
+            )),
+        );
+        let status = to_status(&error);
+        assert_eq!(status.code(), Code::ResourceExhausted);
+
+        let detail = ErrorDetail::decode(status.details()).unwrap();
+        assert!(detail.retryable);
+        assert_eq!(
+            detail.context,
+            vec!["running stage 1", "reading partition 0"]
+        );
+
+        let rebuilt = from_status(&status);
+        assert_eq!(rebuilt.to_string(), error.to_string());
+        assert!(is_retryable(&rebuilt));
+    }
+
+    #[test]
+    fn test_status_without_details() {
+        let error = from_status(&Status::unavailable("connection refused"));
+        assert!(matches!(error, RapidashError::GrpcConnectionError(_)));
+        assert_eq!(error_code(&error), ErrorCode::GrpcConnection);
+    }
+}
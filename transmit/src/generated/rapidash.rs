@@ -1,16 +1,792 @@
+#[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct QueryRequest {
-    #[prost(string, tag="1")]
+    #[prost(string, tag = "1")]
     pub sql: ::prost::alloc::string::String,
+    /// session returned by CreateSession
+    #[prost(string, tag = "2")]
+    pub session_id: ::prost::alloc::string::String,
 }
 /// get the result by id
+#[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct QueryResponse {
-    #[prost(bool, tag="1")]
+    #[prost(bool, tag = "1")]
     pub success: bool,
-    #[prost(string, tag="2")]
+    #[prost(string, tag = "2")]
     pub id: ::prost::alloc::string::String,
 }
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryStatusRequest {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryStatusResponse {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(enumeration = "QueryState", tag = "2")]
+    pub state: i32,
+    /// finished fraction of the job, from 0 to 1
+    #[prost(float, tag = "3")]
+    pub progress: f32,
+    /// error message when the job failed
+    #[prost(string, tag = "4")]
+    pub error: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FetchResultRequest {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+}
+/// result of a succeeded job encoded as an arrow ipc stream
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FetchResultResponse {
+    #[prost(enumeration = "QueryState", tag = "1")]
+    pub state: i32,
+    #[prost(bytes = "vec", tag = "2")]
+    pub data: ::prost::alloc::vec::Vec<u8>,
+}
+/// one record batch encoded as an arrow ipc stream
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ResultBatch {
+    #[prost(bytes = "vec", tag = "1")]
+    pub data: ::prost::alloc::vec::Vec<u8>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CancelQueryRequest {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+}
+/// cancelled is false when the job had already finished
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CancelQueryResponse {
+    #[prost(bool, tag = "1")]
+    pub cancelled: bool,
+}
+/// settings are validated against the scheduler configuration entries
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateSessionRequest {
+    #[prost(map = "string, string", tag = "1")]
+    pub settings: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateSessionResponse {
+    #[prost(string, tag = "1")]
+    pub session_id: ::prost::alloc::string::String,
+}
+/// settings are merged into the settings of the session
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateSessionRequest {
+    #[prost(string, tag = "1")]
+    pub session_id: ::prost::alloc::string::String,
+    #[prost(map = "string, string", tag = "2")]
+    pub settings: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateSessionResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CloseSessionRequest {
+    #[prost(string, tag = "1")]
+    pub session_id: ::prost::alloc::string::String,
+}
+/// closed is false when the session did not exist
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CloseSessionResponse {
+    #[prost(bool, tag = "1")]
+    pub closed: bool,
+}
+/// what a peer speaks, exchanged by the handshake
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Capabilities {
+    /// crate version of the peer
+    #[prost(string, tag = "1")]
+    pub version: ::prost::alloc::string::String,
+    /// revision of the rpc proto, bumped on incompatible changes
+    #[prost(uint32, tag = "2")]
+    pub proto_revision: u32,
+    /// version of the physical plan codec
+    #[prost(uint32, tag = "3")]
+    pub plan_codec_version: u32,
+    /// compression codecs of rpcs and shuffle data besides uncompressed
+    #[prost(string, repeated, tag = "4")]
+    pub compression_codecs: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// whether the peer serves or fetches shuffle partitions with arrow flight
+    #[prost(bool, tag = "5")]
+    pub flight: bool,
+}
+/// first call of executors and clients, incompatible peers are rejected
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HandshakeRequest {
+    #[prost(enumeration = "PeerRole", tag = "1")]
+    pub role: i32,
+    /// executor id, empty for clients
+    #[prost(string, tag = "2")]
+    pub peer_id: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub capabilities: ::core::option::Option<Capabilities>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HandshakeResponse {
+    #[prost(message, optional, tag = "1")]
+    pub capabilities: ::core::option::Option<Capabilities>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExecutorRegistration {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub host: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "3")]
+    pub port: u32,
+    /// number of tasks the executor can run at the same time
+    #[prost(uint32, tag = "4")]
+    pub task_slots: u32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RegisterExecutorResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    /// seconds between two heartbeats
+    #[prost(uint64, tag = "2")]
+    pub heartbeat_interval: u64,
+    /// the executor polls for tasks instead of having them launched
+    #[prost(bool, tag = "3")]
+    pub pull: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HeartbeatRequest {
+    #[prost(string, tag = "1")]
+    pub executor_id: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub running_tasks: u32,
+    #[prost(uint32, tag = "3")]
+    pub available_slots: u32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HeartbeatResponse {
+    /// the scheduler does not know the executor, it should register again
+    #[prost(bool, tag = "1")]
+    pub reregister: bool,
+}
+/// an executor asks for tasks to fill its free slots
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PollWorkRequest {
+    #[prost(string, tag = "1")]
+    pub executor_id: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub available_slots: u32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PollWorkResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub tasks: ::prost::alloc::vec::Vec<TaskDefinition>,
+}
+/// limits of the resources of running jobs, 0 for no limit
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Quotas {
+    /// estimated MiB of all running jobs
+    #[prost(uint64, tag = "1")]
+    pub cluster_memory: u64,
+    #[prost(uint32, tag = "2")]
+    pub cluster_tasks: u32,
+    /// estimated MiB of the running jobs of a user
+    #[prost(uint64, tag = "3")]
+    pub user_memory: u64,
+    #[prost(uint32, tag = "4")]
+    pub user_tasks: u32,
+    /// jobs over the quotas are rejected instead of waiting in the queue
+    #[prost(bool, tag = "5")]
+    pub reject: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetQuotasRequest {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateQuotasRequest {
+    #[prost(message, optional, tag = "1")]
+    pub quotas: ::core::option::Option<Quotas>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QuotasResponse {
+    #[prost(message, optional, tag = "1")]
+    pub quotas: ::core::option::Option<Quotas>,
+}
+/// one partition of a stage in a job
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PartitionId {
+    #[prost(string, tag = "1")]
+    pub job_id: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub stage_id: u32,
+    #[prost(uint32, tag = "3")]
+    pub partition_id: u32,
+}
+/// a plan fragment run by an executor for one input partition
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TaskDefinition {
+    #[prost(string, tag = "1")]
+    pub task_id: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "2")]
+    pub partition: ::core::option::Option<PartitionId>,
+    #[prost(uint32, tag = "3")]
+    pub attempt: u32,
+    /// encoded physical plan of the stage
+    #[prost(bytes = "vec", tag = "4")]
+    pub plan: ::prost::alloc::vec::Vec<u8>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PartitionStats {
+    #[prost(uint64, tag = "1")]
+    pub num_rows: u64,
+    #[prost(uint64, tag = "2")]
+    pub num_batches: u64,
+    #[prost(uint64, tag = "3")]
+    pub num_bytes: u64,
+}
+/// where an executor wrote one output partition of a task
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ShuffleLocation {
+    /// the output partition
+    #[prost(message, optional, tag = "1")]
+    pub partition: ::core::option::Option<PartitionId>,
+    #[prost(string, tag = "2")]
+    pub executor_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub host: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "4")]
+    pub port: u32,
+    #[prost(string, tag = "5")]
+    pub path: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "6")]
+    pub stats: ::core::option::Option<PartitionStats>,
+    /// the input partition of the task that wrote it
+    #[prost(uint32, tag = "7")]
+    pub map_partition_id: u32,
+}
+/// ticket of an arrow flight DoGet for one shuffle file, the executor
+/// serving it finds the file under its work dir
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FetchPartition {
+    #[prost(string, tag = "1")]
+    pub job_id: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub stage_id: u32,
+    #[prost(uint32, tag = "3")]
+    pub partition_id: u32,
+    /// input partition of the stage that wrote the file
+    #[prost(uint32, tag = "5")]
+    pub map_partition_id: u32,
+}
+/// a shuffle file could not be fetched from an executor
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FetchPartitionError {
+    #[prost(string, tag = "1")]
+    pub executor_id: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub map_stage_id: u32,
+    #[prost(uint32, tag = "3")]
+    pub map_partition_id: u32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TaskMetrics {
+    #[prost(uint64, tag = "1")]
+    pub start_time: u64,
+    #[prost(uint64, tag = "2")]
+    pub end_time: u64,
+    #[prost(uint64, tag = "3")]
+    pub output_rows: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RunningTask {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FailedTask {
+    #[prost(string, tag = "1")]
+    pub error: ::prost::alloc::string::String,
+    /// set when the task failed reading the output of a previous stage
+    #[prost(message, optional, tag = "2")]
+    pub fetch_failed: ::core::option::Option<FetchPartitionError>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SuccessfulTask {
+    #[prost(message, repeated, tag = "1")]
+    pub locations: ::prost::alloc::vec::Vec<ShuffleLocation>,
+    #[prost(message, optional, tag = "2")]
+    pub metrics: ::core::option::Option<TaskMetrics>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TaskStatus {
+    #[prost(string, tag = "1")]
+    pub task_id: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "2")]
+    pub partition: ::core::option::Option<PartitionId>,
+    #[prost(uint32, tag = "3")]
+    pub attempt: u32,
+    #[prost(string, tag = "4")]
+    pub executor_id: ::prost::alloc::string::String,
+    #[prost(oneof = "task_status::Status", tags = "5, 6, 7")]
+    pub status: ::core::option::Option<task_status::Status>,
+}
+/// Nested message and enum types in `TaskStatus`.
+pub mod task_status {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Status {
+        #[prost(message, tag = "5")]
+        Running(super::RunningTask),
+        #[prost(message, tag = "6")]
+        Failed(super::FailedTask),
+        #[prost(message, tag = "7")]
+        Successful(super::SuccessfulTask),
+    }
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateTaskStatusRequest {
+    #[prost(string, tag = "1")]
+    pub executor_id: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "2")]
+    pub statuses: ::prost::alloc::vec::Vec<TaskStatus>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateTaskStatusResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LaunchTaskRequest {
+    #[prost(message, repeated, tag = "1")]
+    pub tasks: ::prost::alloc::vec::Vec<TaskDefinition>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LaunchTaskResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CancelTaskRequest {
+    #[prost(string, repeated, tag = "1")]
+    pub task_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// shuffle data of these jobs is removed as well
+    #[prost(string, repeated, tag = "2")]
+    pub clean_job_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CancelTaskResponse {
+    #[prost(bool, tag = "1")]
+    pub cancelled: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SchemaErrorDetail {
+    #[prost(enumeration = "SchemaErrorKind", tag = "1")]
+    pub kind: i32,
+    /// empty for an unqualified field
+    #[prost(string, tag = "2")]
+    pub qualifier: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(bool, tag = "4")]
+    pub has_valid_fields: bool,
+    #[prost(string, repeated, tag = "5")]
+    pub valid_fields: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+/// details of a status carrying a RapidashError
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ErrorDetail {
+    #[prost(enumeration = "ErrorCode", tag = "1")]
+    pub code: i32,
+    /// whether running the failed action again may succeed
+    #[prost(bool, tag = "2")]
+    pub retryable: bool,
+    /// context messages of the error, outermost first
+    #[prost(string, repeated, tag = "3")]
+    pub context: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// message of the innermost error
+    #[prost(string, tag = "4")]
+    pub message: ::prost::alloc::string::String,
+    /// set for ERROR_CODE_FETCH_FAILED
+    #[prost(message, optional, tag = "5")]
+    pub fetch_failed: ::core::option::Option<FetchPartitionError>,
+    /// set for ERROR_CODE_SCHEMA
+    #[prost(message, optional, tag = "6")]
+    pub schema_error: ::core::option::Option<SchemaErrorDetail>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SessionRecord {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(map = "string, string", tag = "2")]
+    pub settings: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
+    #[prost(string, tag = "3")]
+    pub principal: ::prost::alloc::string::String,
+    #[prost(int64, tag = "4")]
+    pub start_time: i64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StageRecord {
+    #[prost(uint32, tag = "1")]
+    pub id: u32,
+    #[prost(enumeration = "ExecutionStateRecord", tag = "2")]
+    pub state: i32,
+    #[prost(enumeration = "ExecutionStateRecord", repeated, tag = "3")]
+    pub tasks: ::prost::alloc::vec::Vec<i32>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct JobRecord {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub session_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub sql: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub principal: ::prost::alloc::string::String,
+    #[prost(enumeration = "JobStateRecord", tag = "6")]
+    pub state: i32,
+    #[prost(int64, tag = "7")]
+    pub submit_time: i64,
+    #[prost(int64, tag = "8")]
+    pub start_time: i64,
+    #[prost(int64, tag = "9")]
+    pub end_time: i64,
+    #[prost(message, repeated, tag = "10")]
+    pub stages: ::prost::alloc::vec::Vec<StageRecord>,
+    #[prost(string, tag = "11")]
+    pub error: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "13")]
+    pub priority: u32,
+    #[prost(uint32, tag = "14")]
+    pub weight: u32,
+    #[prost(uint64, tag = "15")]
+    pub estimated_memory: u64,
+    #[prost(uint32, tag = "16")]
+    pub estimated_tasks: u32,
+    /// settings of the session of the job
+    #[prost(map = "string, string", tag = "17")]
+    pub settings: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
+}
+/// a table of the catalog, recreated by running its ddl again
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TableRecord {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub sql: ::prost::alloc::string::String,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum QueryState {
+    Queued = 0,
+    Running = 1,
+    Succeeded = 2,
+    Failed = 3,
+    Cancelled = 4,
+}
+impl QueryState {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            QueryState::Queued => "QUEUED",
+            QueryState::Running => "RUNNING",
+            QueryState::Succeeded => "SUCCEEDED",
+            QueryState::Failed => "FAILED",
+            QueryState::Cancelled => "CANCELLED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "QUEUED" => Some(Self::Queued),
+            "RUNNING" => Some(Self::Running),
+            "SUCCEEDED" => Some(Self::Succeeded),
+            "FAILED" => Some(Self::Failed),
+            "CANCELLED" => Some(Self::Cancelled),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum PeerRole {
+    Client = 0,
+    Executor = 1,
+    Scheduler = 2,
+}
+impl PeerRole {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            PeerRole::Client => "PEER_ROLE_CLIENT",
+            PeerRole::Executor => "PEER_ROLE_EXECUTOR",
+            PeerRole::Scheduler => "PEER_ROLE_SCHEDULER",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "PEER_ROLE_CLIENT" => Some(Self::Client),
+            "PEER_ROLE_EXECUTOR" => Some(Self::Executor),
+            "PEER_ROLE_SCHEDULER" => Some(Self::Scheduler),
+            _ => None,
+        }
+    }
+}
+/// stable code of a RapidashError variant
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ErrorCode {
+    General = 0,
+    GrpcConnection = 1,
+    GrpcAction = 2,
+    FetchFailed = 3,
+    Cancelled = 4,
+    Arrow = 5,
+    Parquet = 6,
+    Io = 7,
+    Sql = 8,
+    NotImplemented = 9,
+    Internal = 10,
+    Schema = 11,
+    Execution = 12,
+    ResourcesExhausted = 13,
+    External = 14,
+    Incompatible = 15,
+}
+impl ErrorCode {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            ErrorCode::General => "ERROR_CODE_GENERAL",
+            ErrorCode::GrpcConnection => "ERROR_CODE_GRPC_CONNECTION",
+            ErrorCode::GrpcAction => "ERROR_CODE_GRPC_ACTION",
+            ErrorCode::FetchFailed => "ERROR_CODE_FETCH_FAILED",
+            ErrorCode::Cancelled => "ERROR_CODE_CANCELLED",
+            ErrorCode::Arrow => "ERROR_CODE_ARROW",
+            ErrorCode::Parquet => "ERROR_CODE_PARQUET",
+            ErrorCode::Io => "ERROR_CODE_IO",
+            ErrorCode::Sql => "ERROR_CODE_SQL",
+            ErrorCode::NotImplemented => "ERROR_CODE_NOT_IMPLEMENTED",
+            ErrorCode::Internal => "ERROR_CODE_INTERNAL",
+            ErrorCode::Schema => "ERROR_CODE_SCHEMA",
+            ErrorCode::Execution => "ERROR_CODE_EXECUTION",
+            ErrorCode::ResourcesExhausted => "ERROR_CODE_RESOURCES_EXHAUSTED",
+            ErrorCode::External => "ERROR_CODE_EXTERNAL",
+            ErrorCode::Incompatible => "ERROR_CODE_INCOMPATIBLE",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "ERROR_CODE_GENERAL" => Some(Self::General),
+            "ERROR_CODE_GRPC_CONNECTION" => Some(Self::GrpcConnection),
+            "ERROR_CODE_GRPC_ACTION" => Some(Self::GrpcAction),
+            "ERROR_CODE_FETCH_FAILED" => Some(Self::FetchFailed),
+            "ERROR_CODE_CANCELLED" => Some(Self::Cancelled),
+            "ERROR_CODE_ARROW" => Some(Self::Arrow),
+            "ERROR_CODE_PARQUET" => Some(Self::Parquet),
+            "ERROR_CODE_IO" => Some(Self::Io),
+            "ERROR_CODE_SQL" => Some(Self::Sql),
+            "ERROR_CODE_NOT_IMPLEMENTED" => Some(Self::NotImplemented),
+            "ERROR_CODE_INTERNAL" => Some(Self::Internal),
+            "ERROR_CODE_SCHEMA" => Some(Self::Schema),
+            "ERROR_CODE_EXECUTION" => Some(Self::Execution),
+            "ERROR_CODE_RESOURCES_EXHAUSTED" => Some(Self::ResourcesExhausted),
+            "ERROR_CODE_EXTERNAL" => Some(Self::External),
+            "ERROR_CODE_INCOMPATIBLE" => Some(Self::Incompatible),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum SchemaErrorKind {
+    AmbiguousReference = 0,
+    DuplicateQualifiedField = 1,
+    DuplicateUnqualifiedField = 2,
+    FieldNotFound = 3,
+}
+impl SchemaErrorKind {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            SchemaErrorKind::AmbiguousReference => {
+                "SCHEMA_ERROR_KIND_AMBIGUOUS_REFERENCE"
+            }
+            SchemaErrorKind::DuplicateQualifiedField => {
+                "SCHEMA_ERROR_KIND_DUPLICATE_QUALIFIED_FIELD"
+            }
+            SchemaErrorKind::DuplicateUnqualifiedField => {
+                "SCHEMA_ERROR_KIND_DUPLICATE_UNQUALIFIED_FIELD"
+            }
+            SchemaErrorKind::FieldNotFound => "SCHEMA_ERROR_KIND_FIELD_NOT_FOUND",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "SCHEMA_ERROR_KIND_AMBIGUOUS_REFERENCE" => Some(Self::AmbiguousReference),
+            "SCHEMA_ERROR_KIND_DUPLICATE_QUALIFIED_FIELD" => {
+                Some(Self::DuplicateQualifiedField)
+            }
+            "SCHEMA_ERROR_KIND_DUPLICATE_UNQUALIFIED_FIELD" => {
+                Some(Self::DuplicateUnqualifiedField)
+            }
+            "SCHEMA_ERROR_KIND_FIELD_NOT_FOUND" => Some(Self::FieldNotFound),
+            _ => None,
+        }
+    }
+}
+/// state of a job as kept by the scheduler state store
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum JobStateRecord {
+    Queued = 0,
+    Planning = 1,
+    Running = 2,
+    Succeeded = 3,
+    Failed = 4,
+    Cancelled = 5,
+}
+impl JobStateRecord {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            JobStateRecord::Queued => "JOB_STATE_RECORD_QUEUED",
+            JobStateRecord::Planning => "JOB_STATE_RECORD_PLANNING",
+            JobStateRecord::Running => "JOB_STATE_RECORD_RUNNING",
+            JobStateRecord::Succeeded => "JOB_STATE_RECORD_SUCCEEDED",
+            JobStateRecord::Failed => "JOB_STATE_RECORD_FAILED",
+            JobStateRecord::Cancelled => "JOB_STATE_RECORD_CANCELLED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "JOB_STATE_RECORD_QUEUED" => Some(Self::Queued),
+            "JOB_STATE_RECORD_PLANNING" => Some(Self::Planning),
+            "JOB_STATE_RECORD_RUNNING" => Some(Self::Running),
+            "JOB_STATE_RECORD_SUCCEEDED" => Some(Self::Succeeded),
+            "JOB_STATE_RECORD_FAILED" => Some(Self::Failed),
+            "JOB_STATE_RECORD_CANCELLED" => Some(Self::Cancelled),
+            _ => None,
+        }
+    }
+}
+/// state of a stage or task as kept by the scheduler state store
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ExecutionStateRecord {
+    Pending = 0,
+    Running = 1,
+    Succeeded = 2,
+    Failed = 3,
+    Cancelled = 4,
+}
+impl ExecutionStateRecord {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            ExecutionStateRecord::Pending => "EXECUTION_STATE_RECORD_PENDING",
+            ExecutionStateRecord::Running => "EXECUTION_STATE_RECORD_RUNNING",
+            ExecutionStateRecord::Succeeded => "EXECUTION_STATE_RECORD_SUCCEEDED",
+            ExecutionStateRecord::Failed => "EXECUTION_STATE_RECORD_FAILED",
+            ExecutionStateRecord::Cancelled => "EXECUTION_STATE_RECORD_CANCELLED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "EXECUTION_STATE_RECORD_PENDING" => Some(Self::Pending),
+            "EXECUTION_STATE_RECORD_RUNNING" => Some(Self::Running),
+            "EXECUTION_STATE_RECORD_SUCCEEDED" => Some(Self::Succeeded),
+            "EXECUTION_STATE_RECORD_FAILED" => Some(Self::Failed),
+            "EXECUTION_STATE_RECORD_CANCELLED" => Some(Self::Cancelled),
+            _ => None,
+        }
+    }
+}
 /// Generated client implementations.
 pub mod scheduler_proto_client {
     #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
@@ -80,6 +856,82 @@ pub mod scheduler_proto_client {
             self.inner = self.inner.accept_compressed(encoding);
             self
         }
+        pub async fn handshake(
+            &mut self,
+            request: impl tonic::IntoRequest<super::HandshakeRequest>,
+        ) -> Result<tonic::Response<super::HandshakeResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rapidash.SchedulerProto/Handshake",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn create_session(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CreateSessionRequest>,
+        ) -> Result<tonic::Response<super::CreateSessionResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rapidash.SchedulerProto/CreateSession",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn update_session(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateSessionRequest>,
+        ) -> Result<tonic::Response<super::UpdateSessionResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rapidash.SchedulerProto/UpdateSession",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn close_session(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CloseSessionRequest>,
+        ) -> Result<tonic::Response<super::CloseSessionResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rapidash.SchedulerProto/CloseSession",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
         pub async fn query(
             &mut self,
             request: impl tonic::IntoRequest<super::QueryRequest>,
@@ -99,5 +951,2320 @@ pub mod scheduler_proto_client {
             );
             self.inner.unary(request.into_request(), path, codec).await
         }
+        pub async fn get_query_status(
+            &mut self,
+            request: impl tonic::IntoRequest<super::QueryStatusRequest>,
+        ) -> Result<tonic::Response<super::QueryStatusResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rapidash.SchedulerProto/GetQueryStatus",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn fetch_result(
+            &mut self,
+            request: impl tonic::IntoRequest<super::FetchResultRequest>,
+        ) -> Result<tonic::Response<super::FetchResultResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rapidash.SchedulerProto/FetchResult",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// stream the result of a succeeded job batch by batch
+        pub async fn stream_result(
+            &mut self,
+            request: impl tonic::IntoRequest<super::FetchResultRequest>,
+        ) -> Result<
+            tonic::Response<tonic::codec::Streaming<super::ResultBatch>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rapidash.SchedulerProto/StreamResult",
+            );
+            self.inner.server_streaming(request.into_request(), path, codec).await
+        }
+        pub async fn cancel_query(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CancelQueryRequest>,
+        ) -> Result<tonic::Response<super::CancelQueryResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rapidash.SchedulerProto/CancelQuery",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn register_executor(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ExecutorRegistration>,
+        ) -> Result<tonic::Response<super::RegisterExecutorResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rapidash.SchedulerProto/RegisterExecutor",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn heartbeat(
+            &mut self,
+            request: impl tonic::IntoRequest<super::HeartbeatRequest>,
+        ) -> Result<tonic::Response<super::HeartbeatResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rapidash.SchedulerProto/Heartbeat",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// executors of a pull scheduler take their tasks
+        pub async fn poll_work(
+            &mut self,
+            request: impl tonic::IntoRequest<super::PollWorkRequest>,
+        ) -> Result<tonic::Response<super::PollWorkResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rapidash.SchedulerProto/PollWork",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// executors report the progress of their tasks
+        pub async fn update_task_status(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateTaskStatusRequest>,
+        ) -> Result<tonic::Response<super::UpdateTaskStatusResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rapidash.SchedulerProto/UpdateTaskStatus",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// admin rpcs, reserved to `rapidash.admin.principals`
+        pub async fn get_quotas(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetQuotasRequest>,
+        ) -> Result<tonic::Response<super::QuotasResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rapidash.SchedulerProto/GetQuotas",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn update_quotas(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateQuotasRequest>,
+        ) -> Result<tonic::Response<super::QuotasResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rapidash.SchedulerProto/UpdateQuotas",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+    }
+}
+/// Generated client implementations.
+pub mod executor_proto_client {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    use tonic::codegen::http::Uri;
+    #[derive(Debug, Clone)]
+    pub struct ExecutorProtoClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl ExecutorProtoClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: std::convert::TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> ExecutorProtoClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> ExecutorProtoClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+            >>::Error: Into<StdError> + Send + Sync,
+        {
+            ExecutorProtoClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        pub async fn launch_task(
+            &mut self,
+            request: impl tonic::IntoRequest<super::LaunchTaskRequest>,
+        ) -> Result<tonic::Response<super::LaunchTaskResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rapidash.ExecutorProto/LaunchTask",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn cancel_task(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CancelTaskRequest>,
+        ) -> Result<tonic::Response<super::CancelTaskResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/rapidash.ExecutorProto/CancelTask",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+    }
+}
+/// Generated server implementations.
+pub mod scheduler_proto_server {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with SchedulerProtoServer.
+    #[async_trait]
+    pub trait SchedulerProto: Send + Sync + 'static {
+        async fn handshake(
+            &self,
+            request: tonic::Request<super::HandshakeRequest>,
+        ) -> Result<tonic::Response<super::HandshakeResponse>, tonic::Status>;
+        async fn create_session(
+            &self,
+            request: tonic::Request<super::CreateSessionRequest>,
+        ) -> Result<tonic::Response<super::CreateSessionResponse>, tonic::Status>;
+        async fn update_session(
+            &self,
+            request: tonic::Request<super::UpdateSessionRequest>,
+        ) -> Result<tonic::Response<super::UpdateSessionResponse>, tonic::Status>;
+        async fn close_session(
+            &self,
+            request: tonic::Request<super::CloseSessionRequest>,
+        ) -> Result<tonic::Response<super::CloseSessionResponse>, tonic::Status>;
+        async fn query(
+            &self,
+            request: tonic::Request<super::QueryRequest>,
+        ) -> Result<tonic::Response<super::QueryResponse>, tonic::Status>;
+        async fn get_query_status(
+            &self,
+            request: tonic::Request<super::QueryStatusRequest>,
+        ) -> Result<tonic::Response<super::QueryStatusResponse>, tonic::Status>;
+        async fn fetch_result(
+            &self,
+            request: tonic::Request<super::FetchResultRequest>,
+        ) -> Result<tonic::Response<super::FetchResultResponse>, tonic::Status>;
+        /// Server streaming response type for the StreamResult method.
+        type StreamResultStream: futures_core::Stream<
+                Item = Result<super::ResultBatch, tonic::Status>,
+            >
+            + Send
+            + 'static;
+        /// stream the result of a succeeded job batch by batch
+        async fn stream_result(
+            &self,
+            request: tonic::Request<super::FetchResultRequest>,
+        ) -> Result<tonic::Response<Self::StreamResultStream>, tonic::Status>;
+        async fn cancel_query(
+            &self,
+            request: tonic::Request<super::CancelQueryRequest>,
+        ) -> Result<tonic::Response<super::CancelQueryResponse>, tonic::Status>;
+        async fn register_executor(
+            &self,
+            request: tonic::Request<super::ExecutorRegistration>,
+        ) -> Result<tonic::Response<super::RegisterExecutorResponse>, tonic::Status>;
+        async fn heartbeat(
+            &self,
+            request: tonic::Request<super::HeartbeatRequest>,
+        ) -> Result<tonic::Response<super::HeartbeatResponse>, tonic::Status>;
+        /// executors of a pull scheduler take their tasks
+        async fn poll_work(
+            &self,
+            request: tonic::Request<super::PollWorkRequest>,
+        ) -> Result<tonic::Response<super::PollWorkResponse>, tonic::Status>;
+        /// executors report the progress of their tasks
+        async fn update_task_status(
+            &self,
+            request: tonic::Request<super::UpdateTaskStatusRequest>,
+        ) -> Result<tonic::Response<super::UpdateTaskStatusResponse>, tonic::Status>;
+        /// admin rpcs, reserved to `rapidash.admin.principals`
+        async fn get_quotas(
+            &self,
+            request: tonic::Request<super::GetQuotasRequest>,
+        ) -> Result<tonic::Response<super::QuotasResponse>, tonic::Status>;
+        async fn update_quotas(
+            &self,
+            request: tonic::Request<super::UpdateQuotasRequest>,
+        ) -> Result<tonic::Response<super::QuotasResponse>, tonic::Status>;
+    }
+    #[derive(Debug)]
+    pub struct SchedulerProtoServer<T: SchedulerProto> {
+        inner: _Inner<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+    }
+    struct _Inner<T>(Arc<T>);
+    impl<T: SchedulerProto> SchedulerProtoServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            let inner = _Inner(inner);
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+            }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        /// Enable decompressing requests with the given encoding.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.accept_compression_encodings.enable(encoding);
+            self
+        }
+        /// Compress responses with the given encoding, if the client supports it.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.send_compression_encodings.enable(encoding);
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for SchedulerProtoServer<T>
+    where
+        T: SchedulerProto,
+        B: Body + Send + 'static,
+        B::Error: Into<StdError> + Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = std::convert::Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            let inner = self.inner.clone();
+            match req.uri().path() {
+                "/rapidash.SchedulerProto/Handshake" => {
+                    #[allow(non_camel_case_types)]
+                    struct HandshakeSvc<T: SchedulerProto>(pub Arc<T>);
+                    impl<
+                        T: SchedulerProto,
+                    > tonic::server::UnaryService<super::HandshakeRequest>
+                    for HandshakeSvc<T> {
+                        type Response = super::HandshakeResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::HandshakeRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).handshake(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = HandshakeSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rapidash.SchedulerProto/CreateSession" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateSessionSvc<T: SchedulerProto>(pub Arc<T>);
+                    impl<
+                        T: SchedulerProto,
+                    > tonic::server::UnaryService<super::CreateSessionRequest>
+                    for CreateSessionSvc<T> {
+                        type Response = super::CreateSessionResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateSessionRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).create_session(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateSessionSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rapidash.SchedulerProto/UpdateSession" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateSessionSvc<T: SchedulerProto>(pub Arc<T>);
+                    impl<
+                        T: SchedulerProto,
+                    > tonic::server::UnaryService<super::UpdateSessionRequest>
+                    for UpdateSessionSvc<T> {
+                        type Response = super::UpdateSessionResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateSessionRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).update_session(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpdateSessionSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rapidash.SchedulerProto/CloseSession" => {
+                    #[allow(non_camel_case_types)]
+                    struct CloseSessionSvc<T: SchedulerProto>(pub Arc<T>);
+                    impl<
+                        T: SchedulerProto,
+                    > tonic::server::UnaryService<super::CloseSessionRequest>
+                    for CloseSessionSvc<T> {
+                        type Response = super::CloseSessionResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CloseSessionRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).close_session(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CloseSessionSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rapidash.SchedulerProto/Query" => {
+                    #[allow(non_camel_case_types)]
+                    struct QuerySvc<T: SchedulerProto>(pub Arc<T>);
+                    impl<
+                        T: SchedulerProto,
+                    > tonic::server::UnaryService<super::QueryRequest> for QuerySvc<T> {
+                        type Response = super::QueryResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::QueryRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).query(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = QuerySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rapidash.SchedulerProto/GetQueryStatus" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetQueryStatusSvc<T: SchedulerProto>(pub Arc<T>);
+                    impl<
+                        T: SchedulerProto,
+                    > tonic::server::UnaryService<super::QueryStatusRequest>
+                    for GetQueryStatusSvc<T> {
+                        type Response = super::QueryStatusResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::QueryStatusRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).get_query_status(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetQueryStatusSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rapidash.SchedulerProto/FetchResult" => {
+                    #[allow(non_camel_case_types)]
+                    struct FetchResultSvc<T: SchedulerProto>(pub Arc<T>);
+                    impl<
+                        T: SchedulerProto,
+                    > tonic::server::UnaryService<super::FetchResultRequest>
+                    for FetchResultSvc<T> {
+                        type Response = super::FetchResultResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::FetchResultRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).fetch_result(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = FetchResultSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rapidash.SchedulerProto/StreamResult" => {
+                    #[allow(non_camel_case_types)]
+                    struct StreamResultSvc<T: SchedulerProto>(pub Arc<T>);
+                    impl<
+                        T: SchedulerProto,
+                    > tonic::server::ServerStreamingService<super::FetchResultRequest>
+                    for StreamResultSvc<T> {
+                        type Response = super::ResultBatch;
+                        type ResponseStream = T::StreamResultStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::FetchResultRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).stream_result(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = StreamResultSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rapidash.SchedulerProto/CancelQuery" => {
+                    #[allow(non_camel_case_types)]
+                    struct CancelQuerySvc<T: SchedulerProto>(pub Arc<T>);
+                    impl<
+                        T: SchedulerProto,
+                    > tonic::server::UnaryService<super::CancelQueryRequest>
+                    for CancelQuerySvc<T> {
+                        type Response = super::CancelQueryResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CancelQueryRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).cancel_query(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CancelQuerySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rapidash.SchedulerProto/RegisterExecutor" => {
+                    #[allow(non_camel_case_types)]
+                    struct RegisterExecutorSvc<T: SchedulerProto>(pub Arc<T>);
+                    impl<
+                        T: SchedulerProto,
+                    > tonic::server::UnaryService<super::ExecutorRegistration>
+                    for RegisterExecutorSvc<T> {
+                        type Response = super::RegisterExecutorResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ExecutorRegistration>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).register_executor(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = RegisterExecutorSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rapidash.SchedulerProto/Heartbeat" => {
+                    #[allow(non_camel_case_types)]
+                    struct HeartbeatSvc<T: SchedulerProto>(pub Arc<T>);
+                    impl<
+                        T: SchedulerProto,
+                    > tonic::server::UnaryService<super::HeartbeatRequest>
+                    for HeartbeatSvc<T> {
+                        type Response = super::HeartbeatResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::HeartbeatRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).heartbeat(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = HeartbeatSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rapidash.SchedulerProto/PollWork" => {
+                    #[allow(non_camel_case_types)]
+                    struct PollWorkSvc<T: SchedulerProto>(pub Arc<T>);
+                    impl<
+                        T: SchedulerProto,
+                    > tonic::server::UnaryService<super::PollWorkRequest>
+                    for PollWorkSvc<T> {
+                        type Response = super::PollWorkResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::PollWorkRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).poll_work(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = PollWorkSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rapidash.SchedulerProto/UpdateTaskStatus" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateTaskStatusSvc<T: SchedulerProto>(pub Arc<T>);
+                    impl<
+                        T: SchedulerProto,
+                    > tonic::server::UnaryService<super::UpdateTaskStatusRequest>
+                    for UpdateTaskStatusSvc<T> {
+                        type Response = super::UpdateTaskStatusResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateTaskStatusRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).update_task_status(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpdateTaskStatusSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rapidash.SchedulerProto/GetQuotas" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetQuotasSvc<T: SchedulerProto>(pub Arc<T>);
+                    impl<
+                        T: SchedulerProto,
+                    > tonic::server::UnaryService<super::GetQuotasRequest>
+                    for GetQuotasSvc<T> {
+                        type Response = super::QuotasResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetQuotasRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).get_quotas(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetQuotasSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rapidash.SchedulerProto/UpdateQuotas" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateQuotasSvc<T: SchedulerProto>(pub Arc<T>);
+                    impl<
+                        T: SchedulerProto,
+                    > tonic::server::UnaryService<super::UpdateQuotasRequest>
+                    for UpdateQuotasSvc<T> {
+                        type Response = super::QuotasResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateQuotasRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).update_quotas(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpdateQuotasSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => {
+                    Box::pin(async move {
+                        Ok(
+                            http::Response::builder()
+                                .status(200)
+                                .header("grpc-status", "12")
+                                .header("content-type", "application/grpc")
+                                .body(empty_body())
+                                .unwrap(),
+                        )
+                    })
+                }
+            }
+        }
+    }
+    impl<T: SchedulerProto> Clone for SchedulerProtoServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+            }
+        }
+    }
+    impl<T: SchedulerProto> Clone for _Inner<T> {
+        fn clone(&self) -> Self {
+            Self(self.0.clone())
+        }
+    }
+    impl<T: std::fmt::Debug> std::fmt::Debug for _Inner<T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{:?}", self.0)
+        }
+    }
+    impl<T: SchedulerProto> tonic::server::NamedService for SchedulerProtoServer<T> {
+        const NAME: &'static str = "rapidash.SchedulerProto";
+    }
+}
+/// Generated server implementations.
+pub mod executor_proto_server {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with ExecutorProtoServer.
+    #[async_trait]
+    pub trait ExecutorProto: Send + Sync + 'static {
+        async fn launch_task(
+            &self,
+            request: tonic::Request<super::LaunchTaskRequest>,
+        ) -> Result<tonic::Response<super::LaunchTaskResponse>, tonic::Status>;
+        async fn cancel_task(
+            &self,
+            request: tonic::Request<super::CancelTaskRequest>,
+        ) -> Result<tonic::Response<super::CancelTaskResponse>, tonic::Status>;
+    }
+    #[derive(Debug)]
+    pub struct ExecutorProtoServer<T: ExecutorProto> {
+        inner: _Inner<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+    }
+    struct _Inner<T>(Arc<T>);
+    impl<T: ExecutorProto> ExecutorProtoServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            let inner = _Inner(inner);
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+            }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        /// Enable decompressing requests with the given encoding.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.accept_compression_encodings.enable(encoding);
+            self
+        }
+        /// Compress responses with the given encoding, if the client supports it.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.send_compression_encodings.enable(encoding);
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for ExecutorProtoServer<T>
+    where
+        T: ExecutorProto,
+        B: Body + Send + 'static,
+        B::Error: Into<StdError> + Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = std::convert::Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            let inner = self.inner.clone();
+            match req.uri().path() {
+                "/rapidash.ExecutorProto/LaunchTask" => {
+                    #[allow(non_camel_case_types)]
+                    struct LaunchTaskSvc<T: ExecutorProto>(pub Arc<T>);
+                    impl<
+                        T: ExecutorProto,
+                    > tonic::server::UnaryService<super::LaunchTaskRequest>
+                    for LaunchTaskSvc<T> {
+                        type Response = super::LaunchTaskResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::LaunchTaskRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).launch_task(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = LaunchTaskSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/rapidash.ExecutorProto/CancelTask" => {
+                    #[allow(non_camel_case_types)]
+                    struct CancelTaskSvc<T: ExecutorProto>(pub Arc<T>);
+                    impl<
+                        T: ExecutorProto,
+                    > tonic::server::UnaryService<super::CancelTaskRequest>
+                    for CancelTaskSvc<T> {
+                        type Response = super::CancelTaskResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CancelTaskRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).cancel_task(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CancelTaskSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => {
+                    Box::pin(async move {
+                        Ok(
+                            http::Response::builder()
+                                .status(200)
+                                .header("grpc-status", "12")
+                                .header("content-type", "application/grpc")
+                                .body(empty_body())
+                                .unwrap(),
+                        )
+                    })
+                }
+            }
+        }
+    }
+    impl<T: ExecutorProto> Clone for ExecutorProtoServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+            }
+        }
+    }
+    impl<T: ExecutorProto> Clone for _Inner<T> {
+        fn clone(&self) -> Self {
+            Self(self.0.clone())
+        }
+    }
+    impl<T: std::fmt::Debug> std::fmt::Debug for _Inner<T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{:?}", self.0)
+        }
+    }
+    impl<T: ExecutorProto> tonic::server::NamedService for ExecutorProtoServer<T> {
+        const NAME: &'static str = "rapidash.ExecutorProto";
+    }
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EmptyMessage {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Field {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(message, optional, boxed, tag = "2")]
+    pub arrow_type: ::core::option::Option<::prost::alloc::boxed::Box<ArrowType>>,
+    #[prost(bool, tag = "3")]
+    pub nullable: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Schema {
+    #[prost(message, repeated, tag = "1")]
+    pub fields: ::prost::alloc::vec::Vec<Field>,
+    #[prost(map = "string, string", tag = "2")]
+    pub metadata: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Timestamp {
+    #[prost(enumeration = "TimeUnit", tag = "1")]
+    pub time_unit: i32,
+    /// empty without timezone
+    #[prost(string, tag = "2")]
+    pub timezone: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Decimal {
+    #[prost(uint32, tag = "1")]
+    pub precision: u32,
+    #[prost(int32, tag = "2")]
+    pub scale: i32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct List {
+    #[prost(message, optional, boxed, tag = "1")]
+    pub field: ::core::option::Option<::prost::alloc::boxed::Box<Field>>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FixedSizeList {
+    #[prost(message, optional, boxed, tag = "1")]
+    pub field: ::core::option::Option<::prost::alloc::boxed::Box<Field>>,
+    #[prost(int32, tag = "2")]
+    pub size: i32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Struct {
+    #[prost(message, repeated, tag = "1")]
+    pub fields: ::prost::alloc::vec::Vec<Field>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Union {
+    #[prost(message, repeated, tag = "1")]
+    pub fields: ::prost::alloc::vec::Vec<Field>,
+    #[prost(int32, repeated, tag = "2")]
+    pub type_ids: ::prost::alloc::vec::Vec<i32>,
+    #[prost(enumeration = "UnionMode", tag = "3")]
+    pub mode: i32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Dictionary {
+    #[prost(message, optional, boxed, tag = "1")]
+    pub key: ::core::option::Option<::prost::alloc::boxed::Box<ArrowType>>,
+    #[prost(message, optional, boxed, tag = "2")]
+    pub value: ::core::option::Option<::prost::alloc::boxed::Box<ArrowType>>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Map {
+    #[prost(message, optional, boxed, tag = "1")]
+    pub field: ::core::option::Option<::prost::alloc::boxed::Box<Field>>,
+    #[prost(bool, tag = "2")]
+    pub keys_sorted: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ArrowType {
+    #[prost(
+        oneof = "arrow_type::ArrowTypeEnum",
+        tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34"
+    )]
+    pub arrow_type_enum: ::core::option::Option<arrow_type::ArrowTypeEnum>,
+}
+/// Nested message and enum types in `ArrowType`.
+pub mod arrow_type {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum ArrowTypeEnum {
+        #[prost(message, tag = "1")]
+        None(super::EmptyMessage),
+        #[prost(message, tag = "2")]
+        Bool(super::EmptyMessage),
+        #[prost(message, tag = "3")]
+        Int8(super::EmptyMessage),
+        #[prost(message, tag = "4")]
+        Int16(super::EmptyMessage),
+        #[prost(message, tag = "5")]
+        Int32(super::EmptyMessage),
+        #[prost(message, tag = "6")]
+        Int64(super::EmptyMessage),
+        #[prost(message, tag = "7")]
+        Uint8(super::EmptyMessage),
+        #[prost(message, tag = "8")]
+        Uint16(super::EmptyMessage),
+        #[prost(message, tag = "9")]
+        Uint32(super::EmptyMessage),
+        #[prost(message, tag = "10")]
+        Uint64(super::EmptyMessage),
+        #[prost(message, tag = "11")]
+        Float16(super::EmptyMessage),
+        #[prost(message, tag = "12")]
+        Float32(super::EmptyMessage),
+        #[prost(message, tag = "13")]
+        Float64(super::EmptyMessage),
+        #[prost(message, tag = "14")]
+        Utf8(super::EmptyMessage),
+        #[prost(message, tag = "15")]
+        LargeUtf8(super::EmptyMessage),
+        #[prost(message, tag = "16")]
+        Binary(super::EmptyMessage),
+        #[prost(int32, tag = "17")]
+        FixedSizeBinary(i32),
+        #[prost(message, tag = "18")]
+        LargeBinary(super::EmptyMessage),
+        #[prost(message, tag = "19")]
+        Date32(super::EmptyMessage),
+        #[prost(message, tag = "20")]
+        Date64(super::EmptyMessage),
+        #[prost(enumeration = "super::TimeUnit", tag = "21")]
+        Duration(i32),
+        #[prost(message, tag = "22")]
+        Timestamp(super::Timestamp),
+        #[prost(enumeration = "super::TimeUnit", tag = "23")]
+        Time32(i32),
+        #[prost(enumeration = "super::TimeUnit", tag = "24")]
+        Time64(i32),
+        #[prost(enumeration = "super::IntervalUnit", tag = "25")]
+        Interval(i32),
+        #[prost(message, tag = "26")]
+        Decimal128(super::Decimal),
+        #[prost(message, tag = "27")]
+        Decimal256(super::Decimal),
+        #[prost(message, tag = "28")]
+        List(::prost::alloc::boxed::Box<super::List>),
+        #[prost(message, tag = "29")]
+        LargeList(::prost::alloc::boxed::Box<super::List>),
+        #[prost(message, tag = "30")]
+        FixedSizeList(::prost::alloc::boxed::Box<super::FixedSizeList>),
+        #[prost(message, tag = "31")]
+        Struct(super::Struct),
+        #[prost(message, tag = "32")]
+        Union(super::Union),
+        #[prost(message, tag = "33")]
+        Dictionary(::prost::alloc::boxed::Box<super::Dictionary>),
+        #[prost(message, tag = "34")]
+        Map(::prost::alloc::boxed::Box<super::Map>),
+    }
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ScalarTimestamp {
+    #[prost(enumeration = "TimeUnit", tag = "1")]
+    pub time_unit: i32,
+    #[prost(int64, tag = "2")]
+    pub value: i64,
+    /// empty without timezone
+    #[prost(string, tag = "3")]
+    pub timezone: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ScalarDecimal128 {
+    /// little endian i128
+    #[prost(bytes = "vec", tag = "1")]
+    pub value: ::prost::alloc::vec::Vec<u8>,
+    #[prost(uint32, tag = "2")]
+    pub precision: u32,
+    #[prost(int32, tag = "3")]
+    pub scale: i32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ScalarFixedSizeBinary {
+    #[prost(int32, tag = "1")]
+    pub size: i32,
+    #[prost(bytes = "vec", tag = "2")]
+    pub value: ::prost::alloc::vec::Vec<u8>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ScalarList {
+    #[prost(message, optional, tag = "1")]
+    pub field: ::core::option::Option<Field>,
+    #[prost(message, repeated, tag = "2")]
+    pub values: ::prost::alloc::vec::Vec<ScalarValue>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ScalarStruct {
+    #[prost(message, repeated, tag = "1")]
+    pub fields: ::prost::alloc::vec::Vec<Field>,
+    #[prost(message, repeated, tag = "2")]
+    pub values: ::prost::alloc::vec::Vec<ScalarValue>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ScalarDictionary {
+    #[prost(message, optional, tag = "1")]
+    pub index_type: ::core::option::Option<ArrowType>,
+    #[prost(message, optional, boxed, tag = "2")]
+    pub value: ::core::option::Option<::prost::alloc::boxed::Box<ScalarValue>>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ScalarValue {
+    #[prost(
+        oneof = "scalar_value::Value",
+        tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28"
+    )]
+    pub value: ::core::option::Option<scalar_value::Value>,
+}
+/// Nested message and enum types in `ScalarValue`.
+pub mod scalar_value {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Value {
+        /// null of the type
+        #[prost(message, tag = "1")]
+        NullValue(super::ArrowType),
+        #[prost(bool, tag = "2")]
+        BoolValue(bool),
+        #[prost(string, tag = "3")]
+        Utf8Value(::prost::alloc::string::String),
+        #[prost(string, tag = "4")]
+        LargeUtf8Value(::prost::alloc::string::String),
+        #[prost(int32, tag = "5")]
+        Int8Value(i32),
+        #[prost(int32, tag = "6")]
+        Int16Value(i32),
+        #[prost(int32, tag = "7")]
+        Int32Value(i32),
+        #[prost(int64, tag = "8")]
+        Int64Value(i64),
+        #[prost(uint32, tag = "9")]
+        Uint8Value(u32),
+        #[prost(uint32, tag = "10")]
+        Uint16Value(u32),
+        #[prost(uint32, tag = "11")]
+        Uint32Value(u32),
+        #[prost(uint64, tag = "12")]
+        Uint64Value(u64),
+        #[prost(float, tag = "13")]
+        Float32Value(f32),
+        #[prost(double, tag = "14")]
+        Float64Value(f64),
+        #[prost(int32, tag = "15")]
+        Date32Value(i32),
+        #[prost(int64, tag = "16")]
+        Date64Value(i64),
+        #[prost(int64, tag = "17")]
+        Time64Value(i64),
+        #[prost(message, tag = "18")]
+        TimestampValue(super::ScalarTimestamp),
+        #[prost(int32, tag = "19")]
+        IntervalYearMonthValue(i32),
+        #[prost(int64, tag = "20")]
+        IntervalDayTimeValue(i64),
+        /// little endian i128
+        #[prost(bytes, tag = "21")]
+        IntervalMonthDayNanoValue(::prost::alloc::vec::Vec<u8>),
+        #[prost(message, tag = "22")]
+        Decimal128Value(super::ScalarDecimal128),
+        #[prost(bytes, tag = "23")]
+        BinaryValue(::prost::alloc::vec::Vec<u8>),
+        #[prost(bytes, tag = "24")]
+        LargeBinaryValue(::prost::alloc::vec::Vec<u8>),
+        #[prost(message, tag = "25")]
+        FixedSizeBinaryValue(super::ScalarFixedSizeBinary),
+        #[prost(message, tag = "26")]
+        ListValue(super::ScalarList),
+        #[prost(message, tag = "27")]
+        StructValue(super::ScalarStruct),
+        #[prost(message, tag = "28")]
+        DictionaryValue(::prost::alloc::boxed::Box<super::ScalarDictionary>),
+    }
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PhysicalColumn {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub index: u32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PhysicalBinaryExprNode {
+    #[prost(message, optional, boxed, tag = "1")]
+    pub l: ::core::option::Option<::prost::alloc::boxed::Box<PhysicalExprNode>>,
+    #[prost(message, optional, boxed, tag = "2")]
+    pub r: ::core::option::Option<::prost::alloc::boxed::Box<PhysicalExprNode>>,
+    /// name of the operator variant
+    #[prost(string, tag = "3")]
+    pub op: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PhysicalCastNode {
+    #[prost(message, optional, boxed, tag = "1")]
+    pub expr: ::core::option::Option<::prost::alloc::boxed::Box<PhysicalExprNode>>,
+    #[prost(message, optional, tag = "2")]
+    pub arrow_type: ::core::option::Option<ArrowType>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PhysicalUnaryNode {
+    #[prost(message, optional, boxed, tag = "1")]
+    pub expr: ::core::option::Option<::prost::alloc::boxed::Box<PhysicalExprNode>>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PhysicalWhenThen {
+    #[prost(message, optional, tag = "1")]
+    pub when_expr: ::core::option::Option<PhysicalExprNode>,
+    #[prost(message, optional, tag = "2")]
+    pub then_expr: ::core::option::Option<PhysicalExprNode>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PhysicalCaseNode {
+    #[prost(message, optional, boxed, tag = "1")]
+    pub expr: ::core::option::Option<::prost::alloc::boxed::Box<PhysicalExprNode>>,
+    #[prost(message, repeated, tag = "2")]
+    pub when_then_expr: ::prost::alloc::vec::Vec<PhysicalWhenThen>,
+    #[prost(message, optional, boxed, tag = "3")]
+    pub else_expr: ::core::option::Option<::prost::alloc::boxed::Box<PhysicalExprNode>>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PhysicalInListNode {
+    #[prost(message, optional, boxed, tag = "1")]
+    pub expr: ::core::option::Option<::prost::alloc::boxed::Box<PhysicalExprNode>>,
+    #[prost(message, repeated, tag = "2")]
+    pub list: ::prost::alloc::vec::Vec<PhysicalExprNode>,
+    #[prost(bool, tag = "3")]
+    pub negated: bool,
+}
+/// built in scalar function
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PhysicalScalarFunctionNode {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "2")]
+    pub args: ::prost::alloc::vec::Vec<PhysicalExprNode>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PhysicalExprNode {
+    #[prost(
+        oneof = "physical_expr_node::ExprType",
+        tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12"
+    )]
+    pub expr_type: ::core::option::Option<physical_expr_node::ExprType>,
+}
+/// Nested message and enum types in `PhysicalExprNode`.
+pub mod physical_expr_node {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum ExprType {
+        #[prost(message, tag = "1")]
+        Column(super::PhysicalColumn),
+        #[prost(message, tag = "2")]
+        Literal(super::ScalarValue),
+        #[prost(message, tag = "3")]
+        BinaryExpr(::prost::alloc::boxed::Box<super::PhysicalBinaryExprNode>),
+        #[prost(message, tag = "4")]
+        Cast(::prost::alloc::boxed::Box<super::PhysicalCastNode>),
+        #[prost(message, tag = "5")]
+        TryCast(::prost::alloc::boxed::Box<super::PhysicalCastNode>),
+        #[prost(message, tag = "6")]
+        IsNullExpr(::prost::alloc::boxed::Box<super::PhysicalUnaryNode>),
+        #[prost(message, tag = "7")]
+        IsNotNullExpr(::prost::alloc::boxed::Box<super::PhysicalUnaryNode>),
+        #[prost(message, tag = "8")]
+        NotExpr(::prost::alloc::boxed::Box<super::PhysicalUnaryNode>),
+        #[prost(message, tag = "9")]
+        Negative(::prost::alloc::boxed::Box<super::PhysicalUnaryNode>),
+        #[prost(message, tag = "10")]
+        Case(::prost::alloc::boxed::Box<super::PhysicalCaseNode>),
+        #[prost(message, tag = "11")]
+        InList(::prost::alloc::boxed::Box<super::PhysicalInListNode>),
+        #[prost(message, tag = "12")]
+        ScalarFunction(super::PhysicalScalarFunctionNode),
+    }
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PhysicalNamedExpr {
+    #[prost(message, optional, tag = "1")]
+    pub expr: ::core::option::Option<PhysicalExprNode>,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PhysicalSortExprNode {
+    #[prost(message, optional, tag = "1")]
+    pub expr: ::core::option::Option<PhysicalExprNode>,
+    #[prost(bool, tag = "2")]
+    pub asc: bool,
+    #[prost(bool, tag = "3")]
+    pub nulls_first: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PhysicalAggregateExprNode {
+    #[prost(enumeration = "AggregateFunction", tag = "1")]
+    pub fun: i32,
+    #[prost(message, repeated, tag = "2")]
+    pub expr: ::prost::alloc::vec::Vec<PhysicalExprNode>,
+    #[prost(bool, tag = "3")]
+    pub distinct: bool,
+    #[prost(string, tag = "4")]
+    pub name: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FileRange {
+    #[prost(int64, tag = "1")]
+    pub start: i64,
+    #[prost(int64, tag = "2")]
+    pub end: i64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PartitionedFile {
+    #[prost(string, tag = "1")]
+    pub path: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "2")]
+    pub size: u64,
+    /// nanoseconds since unix epoch
+    #[prost(int64, tag = "3")]
+    pub last_modified: i64,
+    #[prost(message, repeated, tag = "4")]
+    pub partition_values: ::prost::alloc::vec::Vec<ScalarValue>,
+    #[prost(message, optional, tag = "5")]
+    pub range: ::core::option::Option<FileRange>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FileGroup {
+    #[prost(message, repeated, tag = "1")]
+    pub files: ::prost::alloc::vec::Vec<PartitionedFile>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ColumnStats {
+    #[prost(message, optional, tag = "1")]
+    pub min_value: ::core::option::Option<ScalarValue>,
+    #[prost(message, optional, tag = "2")]
+    pub max_value: ::core::option::Option<ScalarValue>,
+    /// -1 when unknown
+    #[prost(int64, tag = "3")]
+    pub null_count: i64,
+    #[prost(int64, tag = "4")]
+    pub distinct_count: i64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Statistics {
+    /// -1 when unknown
+    #[prost(int64, tag = "1")]
+    pub num_rows: i64,
+    #[prost(int64, tag = "2")]
+    pub total_byte_size: i64,
+    #[prost(message, repeated, tag = "3")]
+    pub column_stats: ::prost::alloc::vec::Vec<ColumnStats>,
+    #[prost(bool, tag = "4")]
+    pub is_exact: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ProjectionColumns {
+    #[prost(uint32, repeated, tag = "1")]
+    pub columns: ::prost::alloc::vec::Vec<u32>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ScanLimit {
+    #[prost(uint32, tag = "1")]
+    pub limit: u32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FileScanExecConf {
+    #[prost(string, tag = "1")]
+    pub object_store_url: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "2")]
+    pub schema: ::core::option::Option<Schema>,
+    #[prost(message, repeated, tag = "3")]
+    pub file_groups: ::prost::alloc::vec::Vec<FileGroup>,
+    #[prost(message, optional, tag = "4")]
+    pub statistics: ::core::option::Option<Statistics>,
+    #[prost(message, optional, tag = "5")]
+    pub projection: ::core::option::Option<ProjectionColumns>,
+    #[prost(message, optional, tag = "6")]
+    pub limit: ::core::option::Option<ScanLimit>,
+    #[prost(string, repeated, tag = "7")]
+    pub table_partition_cols: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ParquetScanExecNode {
+    #[prost(message, optional, tag = "1")]
+    pub base_conf: ::core::option::Option<FileScanExecConf>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CsvScanExecNode {
+    #[prost(message, optional, tag = "1")]
+    pub base_conf: ::core::option::Option<FileScanExecConf>,
+    #[prost(bool, tag = "2")]
+    pub has_header: bool,
+    #[prost(uint32, tag = "3")]
+    pub delimiter: u32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AvroScanExecNode {
+    #[prost(message, optional, tag = "1")]
+    pub base_conf: ::core::option::Option<FileScanExecConf>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ProjectionExecNode {
+    #[prost(message, optional, boxed, tag = "1")]
+    pub input: ::core::option::Option<::prost::alloc::boxed::Box<PhysicalPlanNode>>,
+    #[prost(message, repeated, tag = "2")]
+    pub expr: ::prost::alloc::vec::Vec<PhysicalNamedExpr>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FilterExecNode {
+    #[prost(message, optional, boxed, tag = "1")]
+    pub input: ::core::option::Option<::prost::alloc::boxed::Box<PhysicalPlanNode>>,
+    #[prost(message, optional, tag = "2")]
+    pub predicate: ::core::option::Option<PhysicalExprNode>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AggregateExecNode {
+    #[prost(message, optional, boxed, tag = "1")]
+    pub input: ::core::option::Option<::prost::alloc::boxed::Box<PhysicalPlanNode>>,
+    #[prost(enumeration = "AggregateMode", tag = "2")]
+    pub mode: i32,
+    #[prost(message, repeated, tag = "3")]
+    pub group_expr: ::prost::alloc::vec::Vec<PhysicalNamedExpr>,
+    #[prost(message, repeated, tag = "4")]
+    pub null_expr: ::prost::alloc::vec::Vec<PhysicalNamedExpr>,
+    /// grouping sets, group_expr.len() flags for every set
+    #[prost(bool, repeated, tag = "5")]
+    pub groups: ::prost::alloc::vec::Vec<bool>,
+    #[prost(message, repeated, tag = "6")]
+    pub aggr_expr: ::prost::alloc::vec::Vec<PhysicalAggregateExprNode>,
+    /// schema of the partial aggregation input
+    #[prost(message, optional, tag = "7")]
+    pub input_schema: ::core::option::Option<Schema>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct JoinOn {
+    #[prost(message, optional, tag = "1")]
+    pub left: ::core::option::Option<PhysicalColumn>,
+    #[prost(message, optional, tag = "2")]
+    pub right: ::core::option::Option<PhysicalColumn>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ColumnIndex {
+    #[prost(uint32, tag = "1")]
+    pub index: u32,
+    #[prost(enumeration = "JoinSide", tag = "2")]
+    pub side: i32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct JoinFilter {
+    #[prost(message, optional, tag = "1")]
+    pub expression: ::core::option::Option<PhysicalExprNode>,
+    #[prost(message, repeated, tag = "2")]
+    pub column_indices: ::prost::alloc::vec::Vec<ColumnIndex>,
+    #[prost(message, optional, tag = "3")]
+    pub schema: ::core::option::Option<Schema>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HashJoinExecNode {
+    #[prost(message, optional, boxed, tag = "1")]
+    pub left: ::core::option::Option<::prost::alloc::boxed::Box<PhysicalPlanNode>>,
+    #[prost(message, optional, boxed, tag = "2")]
+    pub right: ::core::option::Option<::prost::alloc::boxed::Box<PhysicalPlanNode>>,
+    #[prost(message, repeated, tag = "3")]
+    pub on: ::prost::alloc::vec::Vec<JoinOn>,
+    #[prost(enumeration = "JoinType", tag = "4")]
+    pub join_type: i32,
+    #[prost(enumeration = "PartitionMode", tag = "5")]
+    pub partition_mode: i32,
+    #[prost(bool, tag = "6")]
+    pub null_equals_null: bool,
+    #[prost(message, optional, tag = "7")]
+    pub filter: ::core::option::Option<JoinFilter>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CrossJoinExecNode {
+    #[prost(message, optional, boxed, tag = "1")]
+    pub left: ::core::option::Option<::prost::alloc::boxed::Box<PhysicalPlanNode>>,
+    #[prost(message, optional, boxed, tag = "2")]
+    pub right: ::core::option::Option<::prost::alloc::boxed::Box<PhysicalPlanNode>>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SortExecNode {
+    #[prost(message, optional, boxed, tag = "1")]
+    pub input: ::core::option::Option<::prost::alloc::boxed::Box<PhysicalPlanNode>>,
+    #[prost(message, repeated, tag = "2")]
+    pub expr: ::prost::alloc::vec::Vec<PhysicalSortExprNode>,
+    /// -1 without fetch
+    #[prost(int64, tag = "3")]
+    pub fetch: i64,
+    #[prost(bool, tag = "4")]
+    pub preserve_partitioning: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SortPreservingMergeExecNode {
+    #[prost(message, optional, boxed, tag = "1")]
+    pub input: ::core::option::Option<::prost::alloc::boxed::Box<PhysicalPlanNode>>,
+    #[prost(message, repeated, tag = "2")]
+    pub expr: ::prost::alloc::vec::Vec<PhysicalSortExprNode>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PhysicalHashRepartition {
+    #[prost(message, repeated, tag = "1")]
+    pub hash_expr: ::prost::alloc::vec::Vec<PhysicalExprNode>,
+    #[prost(uint64, tag = "2")]
+    pub partition_count: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RepartitionExecNode {
+    #[prost(message, optional, boxed, tag = "1")]
+    pub input: ::core::option::Option<::prost::alloc::boxed::Box<PhysicalPlanNode>>,
+    #[prost(oneof = "repartition_exec_node::PartitionMethod", tags = "2, 3, 4")]
+    pub partition_method: ::core::option::Option<repartition_exec_node::PartitionMethod>,
+}
+/// Nested message and enum types in `RepartitionExecNode`.
+pub mod repartition_exec_node {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum PartitionMethod {
+        #[prost(uint64, tag = "2")]
+        RoundRobin(u64),
+        #[prost(message, tag = "3")]
+        Hash(super::PhysicalHashRepartition),
+        #[prost(uint64, tag = "4")]
+        Unknown(u64),
+    }
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CoalesceBatchesExecNode {
+    #[prost(message, optional, boxed, tag = "1")]
+    pub input: ::core::option::Option<::prost::alloc::boxed::Box<PhysicalPlanNode>>,
+    #[prost(uint32, tag = "2")]
+    pub target_batch_size: u32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CoalescePartitionsExecNode {
+    #[prost(message, optional, boxed, tag = "1")]
+    pub input: ::core::option::Option<::prost::alloc::boxed::Box<PhysicalPlanNode>>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GlobalLimitExecNode {
+    #[prost(message, optional, boxed, tag = "1")]
+    pub input: ::core::option::Option<::prost::alloc::boxed::Box<PhysicalPlanNode>>,
+    #[prost(uint32, tag = "2")]
+    pub skip: u32,
+    /// -1 without fetch
+    #[prost(int64, tag = "3")]
+    pub fetch: i64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LocalLimitExecNode {
+    #[prost(message, optional, boxed, tag = "1")]
+    pub input: ::core::option::Option<::prost::alloc::boxed::Box<PhysicalPlanNode>>,
+    #[prost(uint32, tag = "2")]
+    pub fetch: u32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EmptyExecNode {
+    #[prost(bool, tag = "1")]
+    pub produce_one_row: bool,
+    #[prost(message, optional, tag = "2")]
+    pub schema: ::core::option::Option<Schema>,
+}
+/// the work dir is not sent, every executor writes to its own
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ShuffleWriterExecNode {
+    #[prost(string, tag = "1")]
+    pub job_id: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub stage_id: u32,
+    #[prost(message, optional, boxed, tag = "3")]
+    pub input: ::core::option::Option<::prost::alloc::boxed::Box<PhysicalPlanNode>>,
+    #[prost(message, optional, tag = "4")]
+    pub output_partitioning: ::core::option::Option<PhysicalHashRepartition>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ShuffleReaderPartition {
+    #[prost(message, repeated, tag = "1")]
+    pub locations: ::prost::alloc::vec::Vec<ShuffleLocation>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ShuffleReaderExecNode {
+    #[prost(message, repeated, tag = "1")]
+    pub partitions: ::prost::alloc::vec::Vec<ShuffleReaderPartition>,
+    #[prost(message, optional, tag = "2")]
+    pub schema: ::core::option::Option<Schema>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PhysicalPlanNode {
+    #[prost(
+        oneof = "physical_plan_node::PhysicalPlanType",
+        tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18"
+    )]
+    pub physical_plan_type: ::core::option::Option<physical_plan_node::PhysicalPlanType>,
+}
+/// Nested message and enum types in `PhysicalPlanNode`.
+pub mod physical_plan_node {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum PhysicalPlanType {
+        #[prost(message, tag = "1")]
+        ParquetScan(super::ParquetScanExecNode),
+        #[prost(message, tag = "2")]
+        CsvScan(super::CsvScanExecNode),
+        #[prost(message, tag = "3")]
+        AvroScan(super::AvroScanExecNode),
+        #[prost(message, tag = "4")]
+        Projection(::prost::alloc::boxed::Box<super::ProjectionExecNode>),
+        #[prost(message, tag = "5")]
+        Filter(::prost::alloc::boxed::Box<super::FilterExecNode>),
+        #[prost(message, tag = "6")]
+        Aggregate(::prost::alloc::boxed::Box<super::AggregateExecNode>),
+        #[prost(message, tag = "7")]
+        HashJoin(::prost::alloc::boxed::Box<super::HashJoinExecNode>),
+        #[prost(message, tag = "8")]
+        CrossJoin(::prost::alloc::boxed::Box<super::CrossJoinExecNode>),
+        #[prost(message, tag = "9")]
+        Sort(::prost::alloc::boxed::Box<super::SortExecNode>),
+        #[prost(message, tag = "10")]
+        SortPreservingMerge(
+            ::prost::alloc::boxed::Box<super::SortPreservingMergeExecNode>,
+        ),
+        #[prost(message, tag = "11")]
+        Repartition(::prost::alloc::boxed::Box<super::RepartitionExecNode>),
+        #[prost(message, tag = "12")]
+        CoalesceBatches(::prost::alloc::boxed::Box<super::CoalesceBatchesExecNode>),
+        #[prost(message, tag = "13")]
+        Merge(::prost::alloc::boxed::Box<super::CoalescePartitionsExecNode>),
+        #[prost(message, tag = "14")]
+        GlobalLimit(::prost::alloc::boxed::Box<super::GlobalLimitExecNode>),
+        #[prost(message, tag = "15")]
+        LocalLimit(::prost::alloc::boxed::Box<super::LocalLimitExecNode>),
+        #[prost(message, tag = "16")]
+        Empty(super::EmptyExecNode),
+        #[prost(message, tag = "17")]
+        ShuffleWriter(::prost::alloc::boxed::Box<super::ShuffleWriterExecNode>),
+        #[prost(message, tag = "18")]
+        ShuffleReader(super::ShuffleReaderExecNode),
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum TimeUnit {
+    Second = 0,
+    Millisecond = 1,
+    Microsecond = 2,
+    Nanosecond = 3,
+}
+impl TimeUnit {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            TimeUnit::Second => "TIME_UNIT_SECOND",
+            TimeUnit::Millisecond => "TIME_UNIT_MILLISECOND",
+            TimeUnit::Microsecond => "TIME_UNIT_MICROSECOND",
+            TimeUnit::Nanosecond => "TIME_UNIT_NANOSECOND",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "TIME_UNIT_SECOND" => Some(Self::Second),
+            "TIME_UNIT_MILLISECOND" => Some(Self::Millisecond),
+            "TIME_UNIT_MICROSECOND" => Some(Self::Microsecond),
+            "TIME_UNIT_NANOSECOND" => Some(Self::Nanosecond),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum IntervalUnit {
+    YearMonth = 0,
+    DayTime = 1,
+    MonthDayNano = 2,
+}
+impl IntervalUnit {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            IntervalUnit::YearMonth => "INTERVAL_UNIT_YEAR_MONTH",
+            IntervalUnit::DayTime => "INTERVAL_UNIT_DAY_TIME",
+            IntervalUnit::MonthDayNano => "INTERVAL_UNIT_MONTH_DAY_NANO",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "INTERVAL_UNIT_YEAR_MONTH" => Some(Self::YearMonth),
+            "INTERVAL_UNIT_DAY_TIME" => Some(Self::DayTime),
+            "INTERVAL_UNIT_MONTH_DAY_NANO" => Some(Self::MonthDayNano),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum UnionMode {
+    Sparse = 0,
+    Dense = 1,
+}
+impl UnionMode {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            UnionMode::Sparse => "UNION_MODE_SPARSE",
+            UnionMode::Dense => "UNION_MODE_DENSE",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "UNION_MODE_SPARSE" => Some(Self::Sparse),
+            "UNION_MODE_DENSE" => Some(Self::Dense),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum AggregateFunction {
+    Min = 0,
+    Max = 1,
+    Sum = 2,
+    Avg = 3,
+    Count = 4,
+}
+impl AggregateFunction {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            AggregateFunction::Min => "AGGREGATE_FUNCTION_MIN",
+            AggregateFunction::Max => "AGGREGATE_FUNCTION_MAX",
+            AggregateFunction::Sum => "AGGREGATE_FUNCTION_SUM",
+            AggregateFunction::Avg => "AGGREGATE_FUNCTION_AVG",
+            AggregateFunction::Count => "AGGREGATE_FUNCTION_COUNT",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "AGGREGATE_FUNCTION_MIN" => Some(Self::Min),
+            "AGGREGATE_FUNCTION_MAX" => Some(Self::Max),
+            "AGGREGATE_FUNCTION_SUM" => Some(Self::Sum),
+            "AGGREGATE_FUNCTION_AVG" => Some(Self::Avg),
+            "AGGREGATE_FUNCTION_COUNT" => Some(Self::Count),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum AggregateMode {
+    Partial = 0,
+    Final = 1,
+    FinalPartitioned = 2,
+}
+impl AggregateMode {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            AggregateMode::Partial => "AGGREGATE_MODE_PARTIAL",
+            AggregateMode::Final => "AGGREGATE_MODE_FINAL",
+            AggregateMode::FinalPartitioned => "AGGREGATE_MODE_FINAL_PARTITIONED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "AGGREGATE_MODE_PARTIAL" => Some(Self::Partial),
+            "AGGREGATE_MODE_FINAL" => Some(Self::Final),
+            "AGGREGATE_MODE_FINAL_PARTITIONED" => Some(Self::FinalPartitioned),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum JoinType {
+    Inner = 0,
+    Left = 1,
+    Right = 2,
+    Full = 3,
+    LeftSemi = 4,
+    LeftAnti = 5,
+    RightSemi = 6,
+    RightAnti = 7,
+}
+impl JoinType {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            JoinType::Inner => "JOIN_TYPE_INNER",
+            JoinType::Left => "JOIN_TYPE_LEFT",
+            JoinType::Right => "JOIN_TYPE_RIGHT",
+            JoinType::Full => "JOIN_TYPE_FULL",
+            JoinType::LeftSemi => "JOIN_TYPE_LEFT_SEMI",
+            JoinType::LeftAnti => "JOIN_TYPE_LEFT_ANTI",
+            JoinType::RightSemi => "JOIN_TYPE_RIGHT_SEMI",
+            JoinType::RightAnti => "JOIN_TYPE_RIGHT_ANTI",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "JOIN_TYPE_INNER" => Some(Self::Inner),
+            "JOIN_TYPE_LEFT" => Some(Self::Left),
+            "JOIN_TYPE_RIGHT" => Some(Self::Right),
+            "JOIN_TYPE_FULL" => Some(Self::Full),
+            "JOIN_TYPE_LEFT_SEMI" => Some(Self::LeftSemi),
+            "JOIN_TYPE_LEFT_ANTI" => Some(Self::LeftAnti),
+            "JOIN_TYPE_RIGHT_SEMI" => Some(Self::RightSemi),
+            "JOIN_TYPE_RIGHT_ANTI" => Some(Self::RightAnti),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum PartitionMode {
+    CollectLeft = 0,
+    Partitioned = 1,
+}
+impl PartitionMode {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            PartitionMode::CollectLeft => "PARTITION_MODE_COLLECT_LEFT",
+            PartitionMode::Partitioned => "PARTITION_MODE_PARTITIONED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "PARTITION_MODE_COLLECT_LEFT" => Some(Self::CollectLeft),
+            "PARTITION_MODE_PARTITIONED" => Some(Self::Partitioned),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum JoinSide {
+    Left = 0,
+    Right = 1,
+}
+impl JoinSide {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            JoinSide::Left => "JOIN_SIDE_LEFT",
+            JoinSide::Right => "JOIN_SIDE_RIGHT",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "JOIN_SIDE_LEFT" => Some(Self::Left),
+            "JOIN_SIDE_RIGHT" => Some(Self::Right),
+            _ => None,
+        }
     }
 }
@@ -0,0 +1,161 @@
+//! Version and capability handshake between the scheduler and its peers.
+//!
+//! Executors and clients call `Handshake` before anything else. Both sides
+//! check the capabilities of the other, so binaries of different releases
+//! mixed during a rolling upgrade fail loudly instead of misreading messages.
+
+use common::config::{RPC_COMPRESSION_CODECS, SHUFFLE_COMPRESSION_CODECS};
+use common::error::{RapidashError, Result};
+
+use crate::proto::{Capabilities, HandshakeRequest, PeerRole};
+
+/// Crate version of this binary
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Revision of `rapidash.proto`, bump it on changes older peers can't read
+pub const PROTO_REVISION: u32 = 1;
+/// Version of the physical plan codec in `crate::codec`
+pub const PLAN_CODEC_VERSION: u32 = 1;
+
+/// Capabilities of this binary
+pub fn capabilities() -> Capabilities {
+    Capabilities {
+        version: VERSION.to_string(),
+        proto_revision: PROTO_REVISION,
+        plan_codec_version: PLAN_CODEC_VERSION,
+        compression_codecs: codecs(RPC_COMPRESSION_CODECS)
+            .chain(codecs(SHUFFLE_COMPRESSION_CODECS))
+            .map(str::to_string)
+            .collect(),
+        flight: true,
+    }
+}
+
+/// Compression codecs of the values of a setting
+fn codecs(values: &'static [&'static str]) -> impl Iterator<Item = &'static str> {
+    values.iter().copied().filter(|codec| *codec != "none")
+}
+
+/// Handshake request of a peer with the capabilities of this binary
+pub fn handshake_request(role: PeerRole, peer_id: &str) -> HandshakeRequest {
+    HandshakeRequest {
+        role: role as i32,
+        peer_id: peer_id.to_string(),
+        capabilities: Some(capabilities()),
+    }
+}
+
+/// Check this binary can talk with a peer of the given role
+pub fn check_compatible(role: PeerRole, peer: &Capabilities) -> Result<()> {
+    let local = capabilities();
+    if !same_release_line(&local.version, &peer.version)? {
+        return Err(RapidashError::Incompatible(format!(
+            "{:?} version {} is not compatible with version {}",
+            role, peer.version, local.version
+        )));
+    }
+    if peer.proto_revision != local.proto_revision {
+        return Err(RapidashError::Incompatible(format!(
+            "{:?} speaks proto revision {}, expected {}",
+            role, peer.proto_revision, local.proto_revision
+        )));
+    }
+    // plans travel from the scheduler to executors, clients never decode them
+    if role != PeerRole::Client && peer.plan_codec_version != local.plan_codec_version {
+        return Err(RapidashError::Incompatible(format!(
+            "{:?} uses plan codec version {}, expected {}",
+            role, peer.plan_codec_version, local.plan_codec_version
+        )));
+    }
+    // any peer may receive compressed rpcs, clients never read shuffle data
+    let mut required: Vec<&str> = codecs(RPC_COMPRESSION_CODECS).collect();
+    if role != PeerRole::Client {
+        required.extend(codecs(SHUFFLE_COMPRESSION_CODECS));
+    }
+    let missing: Vec<&str> = required
+        .into_iter()
+        .filter(|codec| !peer.compression_codecs.iter().any(|c| c == codec))
+        .collect();
+    if !missing.is_empty() {
+        return Err(RapidashError::Incompatible(format!(
+            "{:?} does not support compression codecs {}",
+            role,
+            missing.join(", ")
+        )));
+    }
+    // executors exchange shuffle partitions with arrow flight
+    if role == PeerRole::Executor && !peer.flight {
+        return Err(RapidashError::Incompatible(format!(
+            "{:?} does not support arrow flight",
+            role
+        )));
+    }
+    Ok(())
+}
+
+/// Whether two versions are semver compatible, before 1.0 minor versions break
+fn same_release_line(local: &str, peer: &str) -> Result<bool> {
+    let (local, peer) = (parse_version(local)?, parse_version(peer)?);
+    Ok(local.0 == peer.0 && (local.0 > 0 || local.1 == peer.1))
+}
+
+fn parse_version(version: &str) -> Result<(u64, u64)> {
+    let invalid = || RapidashError::Incompatible(format!("Invalid version '{}'", version));
+    let mut parts = version.split('.');
+    let mut next = || -> Result<u64> {
+        parts
+            .next()
+            .and_then(|part| part.parse().ok())
+            .ok_or_else(invalid)
+    };
+    Ok((next()?, next()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_release_line() {
+        assert!(same_release_line("0.1.0", "0.1.7").unwrap());
+        assert!(!same_release_line("0.1.0", "0.2.0").unwrap());
+        assert!(same_release_line("1.2.0", "1.3.1").unwrap());
+        assert!(!same_release_line("1.2.0", "2.2.0").unwrap());
+        assert!(same_release_line("0.1.0", "latest").is_err());
+    }
+
+    #[test]
+    fn test_check_compatible() {
+        assert!(check_compatible(PeerRole::Executor, &capabilities()).is_ok());
+
+        let old = Capabilities {
+            proto_revision: 0,
+            ..capabilities()
+        };
+        assert!(matches!(
+            check_compatible(PeerRole::Executor, &old),
+            Err(RapidashError::Incompatible(_))
+        ));
+
+        let no_flight = Capabilities {
+            flight: false,
+            plan_codec_version: 0,
+            ..capabilities()
+        };
+        assert!(check_compatible(PeerRole::Executor, &no_flight).is_err());
+        // clients neither decode plans nor fetch shuffle partitions
+        assert!(check_compatible(PeerRole::Client, &no_flight).is_ok());
+
+        assert_eq!(capabilities().compression_codecs, ["gzip", "lz4", "zstd"]);
+        let no_zstd = Capabilities {
+            compression_codecs: vec!["gzip".to_string(), "lz4".to_string()],
+            ..capabilities()
+        };
+        assert!(check_compatible(PeerRole::Executor, &no_zstd).is_err());
+        assert!(check_compatible(PeerRole::Client, &no_zstd).is_ok());
+        let no_gzip = Capabilities {
+            compression_codecs: vec!["lz4".to_string(), "zstd".to_string()],
+            ..capabilities()
+        };
+        assert!(check_compatible(PeerRole::Client, &no_gzip).is_err());
+    }
+}
@@ -0,0 +1,75 @@
+//! Arrow ipc encoding of record batches.
+
+use std::io::Cursor;
+
+use datafusion::arrow::datatypes::{Schema, SchemaRef};
+use datafusion::arrow::error::{ArrowError, Result};
+use datafusion::arrow::ipc::reader::StreamReader;
+use datafusion::arrow::ipc::writer::StreamWriter;
+use datafusion::arrow::record_batch::RecordBatch;
+
+/// Encode batches as one arrow ipc stream
+pub fn encode_batches(schema: &Schema, batches: &[RecordBatch]) -> Result<Vec<u8>> {
+    let mut writer = StreamWriter::try_new(Vec::new(), schema)?;
+    for batch in batches {
+        writer.write(batch)?;
+    }
+    writer.finish()?;
+    writer.into_inner()
+}
+
+/// Decode batches from an arrow ipc stream
+pub fn decode_batches(data: &[u8]) -> Result<Vec<RecordBatch>> {
+    let reader = StreamReader::try_new(Cursor::new(data), None)?;
+    reader.collect()
+}
+
+/// Decode the schema and the batches of an arrow ipc stream
+pub fn decode_stream(data: &[u8]) -> Result<(SchemaRef, Vec<RecordBatch>)> {
+    let reader = StreamReader::try_new(Cursor::new(data), None)?;
+    let schema = reader.schema();
+    Ok((schema, reader.collect::<Result<_>>()?))
+}
+
+/// Decode the only batch of an arrow ipc stream
+pub fn decode_batch(data: &[u8]) -> Result<RecordBatch> {
+    decode_batches(data)?
+        .pop()
+        .ok_or_else(|| ArrowError::ParseError("No record batch in arrow ipc stream".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::{Float64Array, StringArray};
+    use datafusion::arrow::datatypes::{DataType, Field};
+
+    use super::*;
+
+    #[test]
+    fn test_ipc_round_trip() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("symbol", DataType::Utf8, false),
+            Field::new("factor", DataType::Float64, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(vec!["600000.SH", "000001.SZ"])),
+                Arc::new(Float64Array::from(vec![Some(0.5), None])),
+            ],
+        )
+        .unwrap();
+
+        let data = encode_batches(&schema, &[batch.clone(), batch.clone()]).unwrap();
+        let batches = decode_batches(&data).unwrap();
+
+        assert_eq!(batches, vec![batch.clone(), batch]);
+
+        // the schema survives a stream without batches
+        let (decoded, batches) = decode_stream(&encode_batches(&schema, &[]).unwrap()).unwrap();
+        assert_eq!(decoded, schema);
+        assert!(batches.is_empty());
+    }
+}
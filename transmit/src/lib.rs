@@ -1,4 +1,15 @@
 //! Library for transmit.
+pub mod auth;
+pub mod codec;
+pub mod compression;
+pub mod error;
+pub mod handshake;
+pub mod ipc;
+pub mod rpc;
+pub mod shuffle;
+pub mod stream;
+pub mod tls;
+
 pub mod proto {
     std::include!("generated/rapidash.rs");
 }
@@ -0,0 +1,381 @@
+//! Rpc between client scheduler or executor
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use common::config::Config;
+use common::error::{RapidashError, Result};
+use log::{info, warn};
+use rand::Rng;
+use tonic::transport::Channel;
+use tonic::{Code, Status};
+
+use crate::auth::TokenInterceptor;
+use crate::compression::CompressionConfig;
+use crate::error::from_status;
+use crate::tls::TlsConfig;
+
+/// Timeouts, retries and circuit breaking of rpc clients, from the
+/// `rapidash.rpc.*` settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcConfig {
+    pub connect_timeout: Duration,
+    pub request_timeout: Duration,
+    pub keepalive_interval: Duration,
+    pub keepalive_timeout: Duration,
+    /// Idle time before tcp keepalive probes, None disables them
+    pub tcp_keepalive: Option<Duration>,
+    /// Retries of an idempotent call
+    pub max_retries: usize,
+    /// Backoff before the first retry, doubled by every retry
+    pub backoff: Duration,
+    pub max_backoff: Duration,
+    /// Consecutive failures opening the circuit of a server
+    pub breaker_failures: usize,
+    /// Time an open circuit rejects calls before letting them through again
+    pub breaker_reset: Duration,
+    pub compression: CompressionConfig,
+}
+
+impl Default for RpcConfig {
+    fn default() -> Self {
+        // infallible because the default settings are valid
+        Self::from_config(&Config::new().unwrap())
+    }
+}
+
+impl RpcConfig {
+    pub fn from_config(config: &Config) -> Self {
+        let secs = |value: usize| Duration::from_secs(value as u64);
+        let millis = |value: usize| Duration::from_millis(value as u64);
+        Self {
+            connect_timeout: secs(config.rpc_connect_timeout()),
+            request_timeout: secs(config.rpc_request_timeout()),
+            keepalive_interval: secs(config.rpc_keepalive_interval()),
+            keepalive_timeout: secs(config.rpc_keepalive_timeout()),
+            tcp_keepalive: config.rpc_tcp_keepalive().map(secs),
+            max_retries: config.rpc_retry_max(),
+            backoff: millis(config.rpc_retry_backoff()),
+            max_backoff: millis(config.rpc_retry_backoff_max()),
+            breaker_failures: config.rpc_breaker_failures().max(1),
+            breaker_reset: secs(config.rpc_breaker_reset()),
+            compression: CompressionConfig::from_config(config),
+        }
+    }
+
+    /// Backoff before a retry, a random time up to the capped exponential backoff
+    /// so clients retrying together spread out
+    pub fn backoff(&self, retry: usize) -> Duration {
+        let exponential = self.backoff.saturating_mul(1 << retry.min(16));
+        let cap = exponential.min(self.max_backoff).as_millis() as u64;
+        Duration::from_millis(rand::thread_rng().gen_range(0..=cap))
+    }
+}
+
+/// Connect to a server url, over TLS when it is enabled
+pub async fn create_client_conn(
+    url: String,
+    tls: &TlsConfig,
+    config: &RpcConfig,
+) -> Result<Channel> {
+    let endpoint = tls
+        .endpoint(url)?
+        .connect_timeout(config.connect_timeout)
+        .timeout(config.request_timeout)
+        // Disable Nagle's Algorithm since we don't want packets to wait
+        .tcp_nodelay(true)
+        .tcp_keepalive(config.tcp_keepalive)
+        .http2_keep_alive_interval(config.keepalive_interval)
+        .keep_alive_timeout(config.keepalive_timeout)
+        .keep_alive_while_idle(true);
+    endpoint
+        .connect()
+        .await
+        .map_err(|e| RapidashError::GrpcConnectionError(e.to_string()))
+}
+
+/// Whether the server was unreachable or did not answer in time. tonic
+/// reports broken connections as unknown errors, which our servers never
+/// send without details.
+fn is_transient(status: &Status) -> bool {
+    match status.code() {
+        Code::Unavailable | Code::DeadlineExceeded => true,
+        Code::Unknown => status.details().is_empty(),
+        _ => false,
+    }
+}
+
+/// Consecutive failures of a server
+#[derive(Debug, Clone, Default)]
+struct CircuitBreaker {
+    failures: usize,
+    /// Time the circuit opened, None while it is closed
+    opened_at: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    /// An open circuit lets calls through again after the reset time, the
+    /// first failure opens it again
+    fn allows(&self, reset: Duration) -> bool {
+        self.opened_at
+            .is_none_or(|opened_at| opened_at.elapsed() >= reset)
+    }
+
+    fn record(&mut self, success: bool, threshold: usize) {
+        if success {
+            *self = Self::default();
+        } else {
+            self.failures += 1;
+            if self.failures >= threshold {
+                self.opened_at = Some(Instant::now());
+            }
+        }
+    }
+}
+
+/// Channels to servers cached by url, with retries and a circuit breaker per server
+#[derive(Clone, Default)]
+pub struct ConnectionManager {
+    tls: TlsConfig,
+    config: RpcConfig,
+    /// Token the servers authenticate the calls with
+    token: TokenInterceptor,
+    channels: Arc<Mutex<HashMap<String, Channel>>>,
+    breakers: Arc<Mutex<HashMap<String, CircuitBreaker>>>,
+}
+
+impl ConnectionManager {
+    pub fn new(tls: TlsConfig, config: RpcConfig) -> Self {
+        Self {
+            tls,
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Connections with the settings of the config, fails when its token is
+    /// invalid rather than sending calls without it
+    pub fn from_config(config: &Config) -> Result<Self> {
+        Ok(Self::new(
+            TlsConfig::from_config(config),
+            RpcConfig::from_config(config),
+        )
+        .with_token(TokenInterceptor::from_config(config)?))
+    }
+
+    pub fn with_token(mut self, token: TokenInterceptor) -> Self {
+        self.token = token;
+        self
+    }
+
+    /// Url of a server, https when TLS is enabled
+    pub fn url(&self, host: &str, port: u16) -> String {
+        self.tls.url(host, port)
+    }
+
+    /// Compression clients on the channels should apply
+    pub fn compression(&self) -> CompressionConfig {
+        self.config.compression
+    }
+
+    /// Interceptor attaching the token to the calls of clients on the channels
+    pub fn token(&self) -> TokenInterceptor {
+        self.token.clone()
+    }
+
+    /// Channel to the url, connected on first use
+    pub async fn channel(&self, url: &str) -> Result<Channel> {
+        if let Some(channel) = self.channels.lock().unwrap().get(url) {
+            return Ok(channel.clone());
+        }
+
+        let channel = create_client_conn(url.to_string(), &self.tls, &self.config).await?;
+        self.channels
+            .lock()
+            .unwrap()
+            .insert(url.to_string(), channel.clone());
+        Ok(channel)
+    }
+
+    /// Forget the channel to the url, the next call connects again
+    pub fn invalidate(&self, url: &str) {
+        self.channels.lock().unwrap().remove(url);
+    }
+
+    /// Whether calls to the url are rejected after too many failures
+    pub fn is_open(&self, url: &str) -> bool {
+        self.breakers
+            .lock()
+            .unwrap()
+            .get(url)
+            .is_some_and(|breaker| !breaker.allows(self.config.breaker_reset))
+    }
+
+    fn record(&self, url: &str, success: bool) {
+        let mut breakers = self.breakers.lock().unwrap();
+        let breaker = breakers.entry(url.to_string()).or_default();
+        let was_open = breaker.opened_at.is_some();
+        breaker.record(success, self.config.breaker_failures);
+        match (was_open, breaker.opened_at.is_some()) {
+            (false, true) => warn!(
+                "Circuit to {} opened after {} failures",
+                url, breaker.failures
+            ),
+            (true, false) => info!("Circuit to {} closed", url),
+            _ => {}
+        }
+    }
+
+    /// Call the server at the url. Calls failing to connect, and idempotent
+    /// calls failing on transient errors, are retried with backoff.
+    pub async fn call<T, F, Fut>(&self, url: &str, idempotent: bool, mut f: F) -> Result<T>
+    where
+        F: FnMut(Channel) -> Fut,
+        Fut: Future<Output = std::result::Result<T, Status>>,
+    {
+        let mut retry = 0;
+        loop {
+            if self.is_open(url) {
+                return Err(RapidashError::GrpcConnectionError(format!(
+                    "Circuit to {} is open after too many failures",
+                    url
+                )));
+            }
+
+            let (error, sent) = match self.channel(url).await {
+                Ok(channel) => match f(channel).await {
+                    Ok(response) => {
+                        self.record(url, true);
+                        return Ok(response);
+                    }
+                    Err(status) if is_transient(&status) => (from_status(&status), true),
+                    Err(status) => {
+                        // the server answered, so it is healthy
+                        self.record(url, true);
+                        return Err(from_status(&status));
+                    }
+                },
+                Err(e) => (e, false),
+            };
+            self.record(url, false);
+            // the server may have restarted, connect again on the next call
+            self.invalidate(url);
+
+            // a request that never left can always be sent again
+            if retry >= self.config.max_retries || (sent && !idempotent) {
+                return Err(error);
+            }
+            let backoff = self.config.backoff(retry);
+            warn!(
+                "Call to {} failed, retry {} in {:?}: {}",
+                url,
+                retry + 1,
+                backoff,
+                error
+            );
+            tokio::time::sleep(backoff).await;
+            retry += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_token() {
+        let config = Config::builder()
+            .set(common::config::AUTH_TOKEN, "bad\ntoken")
+            .build()
+            .unwrap();
+        assert!(ConnectionManager::from_config(&config).is_err());
+        let config = Config::builder()
+            .set(common::config::AUTH_TOKEN, "secret")
+            .build()
+            .unwrap();
+        assert!(ConnectionManager::from_config(&config).is_ok());
+    }
+
+    fn config() -> RpcConfig {
+        RpcConfig {
+            connect_timeout: Duration::from_millis(500),
+            max_retries: 5,
+            backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(4),
+            breaker_failures: 3,
+            breaker_reset: Duration::from_secs(60),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_backoff() {
+        let config = config();
+        for retry in 0..20 {
+            assert!(config.backoff(retry) <= Duration::from_millis(4));
+        }
+    }
+
+    #[test]
+    fn test_circuit_breaker() {
+        let mut breaker = CircuitBreaker::default();
+        breaker.record(false, 2);
+        assert!(breaker.allows(Duration::from_secs(60)));
+        breaker.record(false, 2);
+        assert!(!breaker.allows(Duration::from_secs(60)));
+        // the reset time has passed
+        assert!(breaker.allows(Duration::ZERO));
+        breaker.record(true, 2);
+        assert!(breaker.allows(Duration::from_secs(60)));
+    }
+
+    #[tokio::test]
+    async fn test_call_opens_circuit() {
+        let connections = ConnectionManager::new(TlsConfig::default(), config());
+        // nothing listens on port 1
+        let url = connections.url("127.0.0.1", 1);
+        let result = connections
+            .call(&url, true, |_channel| async { Ok(()) })
+            .await;
+
+        // the circuit opens before the retries run out
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains("Circuit"), "{}", error);
+        assert!(connections.is_open(&url));
+    }
+
+    #[tokio::test]
+    async fn test_call_retries_until_connected() {
+        let config = RpcConfig {
+            backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_millis(100),
+            max_retries: 20,
+            breaker_failures: 100,
+            ..config()
+        };
+        let connections = ConnectionManager::new(TlsConfig::default(), config);
+        // a free port the server only listens on later
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let server = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            let listener = tokio::net::TcpListener::bind(("127.0.0.1", port))
+                .await
+                .unwrap();
+            let (_stream, _) = listener.accept().await.unwrap();
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        });
+
+        let url = connections.url("127.0.0.1", port);
+        connections
+            .call(&url, false, |_channel| async { Ok(()) })
+            .await
+            .unwrap();
+        server.abort();
+    }
+}
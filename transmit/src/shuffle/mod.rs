@@ -0,0 +1,177 @@
+//! Shuffle data between the stages of a job.
+
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use common::error::RapidashError;
+use datafusion::arrow::error::ArrowError;
+use datafusion::error::DataFusionError;
+
+use crate::proto::{FetchPartitionError, PartitionStats};
+
+mod reader;
+mod writer;
+
+pub use reader::ShuffleReaderExec;
+pub use writer::{shuffle_write_schema, ShuffleWriterExec};
+
+/// Statistics of a written output partition
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ShufflePartitionStats {
+    pub num_rows: u64,
+    pub num_batches: u64,
+    pub num_bytes: u64,
+}
+
+impl From<ShufflePartitionStats> for PartitionStats {
+    fn from(stats: ShufflePartitionStats) -> Self {
+        Self {
+            num_rows: stats.num_rows,
+            num_batches: stats.num_batches,
+            num_bytes: stats.num_bytes,
+        }
+    }
+}
+
+impl From<PartitionStats> for ShufflePartitionStats {
+    fn from(stats: PartitionStats) -> Self {
+        Self {
+            num_rows: stats.num_rows,
+            num_batches: stats.num_batches,
+            num_bytes: stats.num_bytes,
+        }
+    }
+}
+
+/// An output partition written by a task
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShuffleWritePartition {
+    pub partition_id: usize,
+    pub path: String,
+    pub stats: ShufflePartitionStats,
+}
+
+/// Check the job id is a single directory name, so the paths of its shuffle
+/// data can't lead out of the work dir
+pub fn check_job_id(job_id: &str) -> io::Result<()> {
+    let mut components = Path::new(job_id).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(_)), None) => Ok(()),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Invalid job id {}", job_id),
+        )),
+    }
+}
+
+/// Directory of one output partition of a stage
+pub fn partition_dir(work_dir: &str, job_id: &str, stage_id: usize, partition: usize) -> PathBuf {
+    [
+        work_dir,
+        job_id,
+        &stage_id.to_string(),
+        &partition.to_string(),
+    ]
+    .iter()
+    .collect()
+}
+
+/// File written by one input partition in an output partition directory
+pub fn partition_file(dir: &Path, input_partition: usize) -> PathBuf {
+    dir.join(format!("data-{}.arrow", input_partition))
+}
+
+/// Remove the files written by one input partition of a stage
+pub fn remove_task_output(
+    work_dir: &str,
+    job_id: &str,
+    stage_id: usize,
+    input_partition: usize,
+) -> io::Result<()> {
+    check_job_id(job_id)?;
+    let stage_dir: PathBuf = [work_dir, job_id, &stage_id.to_string()].iter().collect();
+    if !stage_dir.exists() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(stage_dir)? {
+        let file = partition_file(&entry?.path(), input_partition);
+        if file.exists() {
+            fs::remove_file(file)?;
+        }
+    }
+    Ok(())
+}
+
+/// Remove all shuffle data of a job
+pub fn remove_job_output(work_dir: &str, job_id: &str) -> io::Result<()> {
+    check_job_id(job_id)?;
+    let job_dir = Path::new(work_dir).join(job_id);
+    if job_dir.exists() {
+        fs::remove_dir_all(job_dir)?;
+    }
+    Ok(())
+}
+
+/// Find the failed shuffle fetch that caused an error, if any
+pub fn fetch_partition_error(error: &DataFusionError) -> Option<FetchPartitionError> {
+    find_fetch_failed(error)
+}
+
+fn find_fetch_failed(error: &(dyn Error + 'static)) -> Option<FetchPartitionError> {
+    if let Some(RapidashError::FetchFailed(executor_id, stage_id, partition_id, _)) =
+        error.downcast_ref::<RapidashError>()
+    {
+        return Some(FetchPartitionError {
+            executor_id: executor_id.clone(),
+            map_stage_id: *stage_id as u32,
+            map_partition_id: *partition_id as u32,
+        });
+    }
+    match error.downcast_ref::<DataFusionError>() {
+        Some(DataFusionError::External(e)) => return find_fetch_failed(e.as_ref()),
+        Some(DataFusionError::ArrowError(e)) => return find_fetch_failed(e),
+        Some(DataFusionError::Context(_, e)) => return find_fetch_failed(e.as_ref()),
+        _ => {}
+    }
+    match error.downcast_ref::<ArrowError>() {
+        Some(ArrowError::ExternalError(e)) => find_fetch_failed(e.as_ref()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_job_id() {
+        assert!(check_job_id("job").is_ok());
+        for job_id in ["..", "../other", "/etc", "", "a/b"] {
+            let error = check_job_id(job_id).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidInput, "{}", job_id);
+        }
+    }
+
+    #[test]
+    fn test_remove_job_output() {
+        let work_dir =
+            std::env::temp_dir().join(format!("rapidash-shuffle-{}", rand::random::<u64>()));
+        let job_dir = work_dir.join("job");
+        fs::create_dir_all(job_dir.join("1/0")).unwrap();
+        let work_dir = work_dir.to_string_lossy().to_string();
+
+        // ids leading out of the work dir never remove anything
+        for job_id in ["..", "/etc", ""] {
+            let error = remove_job_output(&work_dir, job_id).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+            let error = remove_task_output(&work_dir, job_id, 1, 0).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        }
+        assert!(job_dir.exists());
+
+        remove_job_output(&work_dir, "job").unwrap();
+        assert!(!job_dir.exists());
+    }
+}
@@ -0,0 +1,209 @@
+//! Read the output partitions of previous stages from executors over arrow flight.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use arrow_flight::flight_service_client::FlightServiceClient;
+use arrow_flight::utils::flight_data_to_arrow_batch;
+use arrow_flight::Ticket;
+use common::error::RapidashError;
+use datafusion::arrow::datatypes::{Schema, SchemaRef};
+use datafusion::arrow::error::{ArrowError, Result as ArrowResult};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::{DataFusionError, Result};
+use datafusion::execution::context::TaskContext;
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    DisplayFormatType, ExecutionPlan, Partitioning, SendableRecordBatchStream, Statistics,
+};
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use prost::Message;
+
+use crate::auth::TokenInterceptor;
+use crate::compression::CompressionConfig;
+use crate::proto::{FetchPartition, ShuffleLocation};
+use crate::tls::TlsConfig;
+
+/// Read shuffle files written by a previous stage, every output partition
+/// reads all of its locations one after another.
+#[derive(Debug, Clone)]
+pub struct ShuffleReaderExec {
+    /// Locations to read for every output partition
+    partitions: Vec<Vec<ShuffleLocation>>,
+    schema: SchemaRef,
+}
+
+impl ShuffleReaderExec {
+    pub fn new(partitions: Vec<Vec<ShuffleLocation>>, schema: SchemaRef) -> Self {
+        Self { partitions, schema }
+    }
+
+    pub fn partitions(&self) -> &[Vec<ShuffleLocation>] {
+        &self.partitions
+    }
+}
+
+impl ExecutionPlan for ShuffleReaderExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(self.partitions.len())
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(self)
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let locations = self.partitions.get(partition).cloned().ok_or_else(|| {
+            DataFusionError::Internal(format!("ShuffleReaderExec has no partition {}", partition))
+        })?;
+        // executors set their TLS, compression and token as extensions of the session
+        let tls = context
+            .session_config()
+            .get_extension::<TlsConfig>()
+            .unwrap_or_default();
+        let compression = *context
+            .session_config()
+            .get_extension::<CompressionConfig>()
+            .unwrap_or_default();
+        let token = context
+            .session_config()
+            .get_extension::<TokenInterceptor>()
+            .unwrap_or_default();
+        let stream = futures::stream::iter(locations)
+            .then(move |location| {
+                let tls = tls.clone();
+                let token = token.as_ref().clone();
+                async move { fetch_partition(location, &tls, compression, token).await }
+            })
+            .try_flatten();
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema.clone(),
+            stream,
+        )))
+    }
+
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default => {
+                write!(f, "ShuffleReaderExec: partitions={}", self.partitions.len())
+            }
+        }
+    }
+
+    fn statistics(&self) -> Statistics {
+        let (num_rows, total_byte_size) = self
+            .partitions
+            .iter()
+            .flatten()
+            .filter_map(|location| location.stats.as_ref())
+            .fold((0, 0), |(rows, bytes), stats| {
+                (
+                    rows + stats.num_rows as usize,
+                    bytes + stats.num_bytes as usize,
+                )
+            });
+        Statistics {
+            num_rows: Some(num_rows),
+            total_byte_size: Some(total_byte_size),
+            column_statistics: None,
+            is_exact: false,
+        }
+    }
+}
+
+/// Fetch one shuffle file from the executor that wrote it
+async fn fetch_partition(
+    location: ShuffleLocation,
+    tls: &TlsConfig,
+    compression: CompressionConfig,
+    token: TokenInterceptor,
+) -> ArrowResult<BoxStream<'static, ArrowResult<RecordBatch>>> {
+    let partition = location.partition.clone().unwrap_or_default();
+    let port = u16::try_from(location.port).unwrap_or_default();
+    let endpoint = tls
+        .endpoint(tls.url(&location.host, port))
+        .map_err(|e| fetch_failed(&location, e.to_string()))?;
+    let channel = endpoint
+        .connect()
+        .await
+        .map_err(|e| fetch_failed(&location, e.to_string()))?;
+    let mut client = crate::compressed!(
+        FlightServiceClient::with_interceptor(channel, token),
+        compression
+    );
+
+    let ticket = Ticket {
+        ticket: FetchPartition {
+            job_id: partition.job_id,
+            stage_id: partition.stage_id,
+            partition_id: partition.partition_id,
+            map_partition_id: location.map_partition_id,
+        }
+        .encode_to_vec(),
+    };
+    let mut stream = client
+        .do_get(ticket)
+        .await
+        .map_err(|e| fetch_failed(&location, e.to_string()))?
+        .into_inner();
+
+    // the first message is the schema
+    let schema = match stream
+        .message()
+        .await
+        .map_err(|e| fetch_failed(&location, e.to_string()))?
+    {
+        Some(data) => Arc::new(Schema::try_from(&data)?),
+        None => return Ok(futures::stream::empty().boxed()),
+    };
+    let dictionaries = HashMap::new();
+
+    Ok(stream
+        .map(move |data| {
+            let data = data.map_err(|e| fetch_failed(&location, e.to_string()))?;
+            flight_data_to_arrow_batch(&data, schema.clone(), &dictionaries)
+        })
+        .boxed())
+}
+
+fn fetch_failed(location: &ShuffleLocation, message: String) -> ArrowError {
+    let stage_id = location
+        .partition
+        .as_ref()
+        .map(|p| p.stage_id as usize)
+        .unwrap_or_default();
+    ArrowError::ExternalError(Box::new(RapidashError::FetchFailed(
+        location.executor_id.clone(),
+        stage_id,
+        location.map_partition_id as usize,
+        message,
+    )))
+}
@@ -0,0 +1,321 @@
+//! Write the output partitions of a stage to local disk.
+
+use std::any::Any;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use datafusion::arrow::array::{StringArray, UInt32Array, UInt64Array};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion::arrow::error::ArrowError;
+use datafusion::arrow::ipc::writer::{FileWriter, IpcWriteOptions};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::{DataFusionError, Result};
+use datafusion::execution::context::TaskContext;
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::metrics::Time;
+use datafusion::physical_plan::repartition::BatchPartitioner;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    DisplayFormatType, ExecutionPlan, Partitioning, SendableRecordBatchStream, Statistics,
+};
+use futures::{StreamExt, TryFutureExt};
+
+use crate::compression::CompressionConfig;
+
+use super::{partition_dir, partition_file, ShufflePartitionStats, ShuffleWritePartition};
+
+/// Execute one input partition of the plan and write its output partitions to
+/// `{work_dir}/{job_id}/{stage_id}/{output_partition}/data-{input_partition}.arrow`.
+///
+/// Without output partitioning every input partition is written as the output
+/// partition with the same index.
+#[derive(Debug, Clone)]
+pub struct ShuffleWriterExec {
+    job_id: String,
+    stage_id: usize,
+    plan: Arc<dyn ExecutionPlan>,
+    work_dir: String,
+    output_partitioning: Option<Partitioning>,
+}
+
+impl ShuffleWriterExec {
+    pub fn try_new(
+        job_id: String,
+        stage_id: usize,
+        plan: Arc<dyn ExecutionPlan>,
+        work_dir: String,
+        output_partitioning: Option<Partitioning>,
+    ) -> Result<Self> {
+        match &output_partitioning {
+            None | Some(Partitioning::Hash(_, _)) => Ok(Self {
+                job_id,
+                stage_id,
+                plan,
+                work_dir,
+                output_partitioning,
+            }),
+            Some(other) => Err(DataFusionError::NotImplemented(format!(
+                "Shuffle with partitioning {:?}",
+                other
+            ))),
+        }
+    }
+
+    pub fn job_id(&self) -> &str {
+        &self.job_id
+    }
+
+    pub fn stage_id(&self) -> usize {
+        self.stage_id
+    }
+
+    pub fn work_dir(&self) -> &str {
+        &self.work_dir
+    }
+
+    pub fn shuffle_output_partitioning(&self) -> Option<&Partitioning> {
+        self.output_partitioning.as_ref()
+    }
+
+    /// Number of partitions the next stage reads
+    pub fn output_partition_count(&self) -> usize {
+        match &self.output_partitioning {
+            Some(partitioning) => partitioning.partition_count(),
+            None => self.plan.output_partitioning().partition_count(),
+        }
+    }
+
+    /// Execute one input partition and write it to disk
+    pub async fn write_partition(
+        &self,
+        input_partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<Vec<ShuffleWritePartition>> {
+        // executors with shuffle compression set it as an extension of the session
+        let options = context
+            .session_config()
+            .get_extension::<CompressionConfig>()
+            .unwrap_or_default()
+            .ipc_write_options()?;
+        let mut stream = self.plan.execute(input_partition, context)?;
+        let schema = self.plan.schema();
+        let mut writers: Vec<Option<PartitionWriter>> = match &self.output_partitioning {
+            Some(partitioning) => (0..partitioning.partition_count()).map(|_| None).collect(),
+            None => vec![None],
+        };
+
+        match &self.output_partitioning {
+            None => {
+                while let Some(batch) = stream.next().await {
+                    let path = self.output_path(input_partition, input_partition);
+                    writer(&mut writers[0], &path, &schema, &options)?.write(&batch?)?;
+                }
+            }
+            Some(partitioning) => {
+                let mut partitioner = BatchPartitioner::try_new(partitioning.clone(), Time::new())?;
+                while let Some(batch) = stream.next().await {
+                    partitioner.partition(batch?, |output_partition, output_batch| {
+                        let path = self.output_path(output_partition, input_partition);
+                        writer(&mut writers[output_partition], &path, &schema, &options)?
+                            .write(&output_batch)
+                    })?;
+                }
+            }
+        }
+
+        let mut partitions = vec![];
+        for (index, writer) in writers.into_iter().enumerate() {
+            if let Some(writer) = writer {
+                let partition_id = match self.output_partitioning {
+                    Some(_) => index,
+                    None => input_partition,
+                };
+                partitions.push(writer.finish(partition_id)?);
+            }
+        }
+        Ok(partitions)
+    }
+
+    fn output_path(&self, output_partition: usize, input_partition: usize) -> PathBuf {
+        partition_file(
+            &partition_dir(
+                &self.work_dir,
+                &self.job_id,
+                self.stage_id,
+                output_partition,
+            ),
+            input_partition,
+        )
+    }
+}
+
+/// Summary of the written partitions returned by `execute`
+pub fn shuffle_write_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("partition", DataType::UInt32, false),
+        Field::new("path", DataType::Utf8, false),
+        Field::new("num_rows", DataType::UInt64, false),
+        Field::new("num_batches", DataType::UInt64, false),
+        Field::new("num_bytes", DataType::UInt64, false),
+    ]))
+}
+
+fn summary(partitions: Vec<ShuffleWritePartition>) -> Result<RecordBatch> {
+    let column = |f: fn(&ShufflePartitionStats) -> u64| {
+        Arc::new(UInt64Array::from(
+            partitions.iter().map(|p| f(&p.stats)).collect::<Vec<_>>(),
+        ))
+    };
+    let batch = RecordBatch::try_new(
+        shuffle_write_schema(),
+        vec![
+            Arc::new(UInt32Array::from(
+                partitions
+                    .iter()
+                    .map(|p| p.partition_id as u32)
+                    .collect::<Vec<_>>(),
+            )),
+            Arc::new(StringArray::from(
+                partitions
+                    .iter()
+                    .map(|p| p.path.as_str())
+                    .collect::<Vec<_>>(),
+            )),
+            column(|s| s.num_rows),
+            column(|s| s.num_batches),
+            column(|s| s.num_bytes),
+        ],
+    )?;
+    Ok(batch)
+}
+
+impl ExecutionPlan for ShuffleWriterExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        shuffle_write_schema()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.plan.output_partitioning()
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.plan.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        match children.as_slice() {
+            [plan] => Ok(Arc::new(ShuffleWriterExec::try_new(
+                self.job_id.clone(),
+                self.stage_id,
+                plan.clone(),
+                self.work_dir.clone(),
+                self.output_partitioning.clone(),
+            )?)),
+            _ => Err(DataFusionError::Internal(
+                "ShuffleWriterExec expects exactly one child".to_string(),
+            )),
+        }
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let writer = self.clone();
+        let stream = futures::stream::once(
+            async move { writer.write_partition(partition, context).await }
+                .and_then(|partitions| async move { summary(partitions) })
+                .map_err(|e| ArrowError::ExternalError(Box::new(e))),
+        );
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            shuffle_write_schema(),
+            stream,
+        )))
+    }
+
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default => write!(
+                f,
+                "ShuffleWriterExec: job={}, stage={}, partitioning={:?}",
+                self.job_id, self.stage_id, self.output_partitioning
+            ),
+        }
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+/// Ipc file writer of one output partition
+struct PartitionWriter {
+    path: PathBuf,
+    writer: FileWriter<BufWriter<File>>,
+    num_rows: u64,
+    num_batches: u64,
+}
+
+impl PartitionWriter {
+    fn try_new(path: &Path, schema: &Schema, options: &IpcWriteOptions) -> Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = BufWriter::new(File::create(path)?);
+        Ok(Self {
+            path: path.to_path_buf(),
+            writer: FileWriter::try_new_with_options(file, schema, options.clone())?,
+            num_rows: 0,
+            num_batches: 0,
+        })
+    }
+
+    fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        self.writer.write(batch)?;
+        self.num_rows += batch.num_rows() as u64;
+        self.num_batches += 1;
+        Ok(())
+    }
+
+    fn finish(mut self, partition_id: usize) -> Result<ShuffleWritePartition> {
+        self.writer.finish()?;
+        let num_bytes = fs::metadata(&self.path)?.len();
+        Ok(ShuffleWritePartition {
+            partition_id,
+            path: self.path.to_string_lossy().to_string(),
+            stats: ShufflePartitionStats {
+                num_rows: self.num_rows,
+                num_batches: self.num_batches,
+                num_bytes,
+            },
+        })
+    }
+}
+
+/// Writer of an output partition, created on its first batch
+fn writer<'a>(
+    writer: &'a mut Option<PartitionWriter>,
+    path: &Path,
+    schema: &Schema,
+    options: &IpcWriteOptions,
+) -> Result<&'a mut PartitionWriter> {
+    if writer.is_none() {
+        *writer = Some(PartitionWriter::try_new(path, schema, options)?);
+    }
+    Ok(writer.as_mut().unwrap())
+}
@@ -0,0 +1,38 @@
+//! Record batch streams over rpc.
+
+use datafusion::arrow::error::{ArrowError, Result};
+use datafusion::arrow::record_batch::RecordBatch;
+use futures::{Stream, StreamExt};
+use tonic::codegen::{Body, Bytes, StdError};
+use tonic::Status;
+
+use crate::error::from_status;
+use crate::ipc::decode_batch;
+use crate::proto::scheduler_proto_client::SchedulerProtoClient;
+use crate::proto::FetchResultRequest;
+
+impl<T> SchedulerProtoClient<T>
+where
+    T: tonic::client::GrpcService<tonic::body::BoxBody>,
+    T::Error: Into<StdError>,
+    T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+    <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+{
+    /// Stream the result of a succeeded job as record batches, errors of the
+    /// stream wrap the `RapidashError` sent by the scheduler
+    pub async fn fetch_record_batches(
+        &mut self,
+        id: String,
+    ) -> std::result::Result<impl Stream<Item = Result<RecordBatch>>, Status> {
+        let stream = self
+            .stream_result(FetchResultRequest { id })
+            .await?
+            .into_inner();
+
+        Ok(stream.map(|message| {
+            let message =
+                message.map_err(|e| ArrowError::ExternalError(Box::new(from_status(&e))))?;
+            decode_batch(&message.data)
+        }))
+    }
+}
@@ -0,0 +1,136 @@
+//! TLS of the rpc servers and clients.
+//!
+//! Without a certificate or CA every channel is plaintext. With mutual TLS
+//! servers only accept clients presenting a certificate signed by the CA.
+
+use std::fs;
+
+use common::config::{Config, TLS_CA, TLS_CERT, TLS_KEY};
+use common::error::{RapidashError, Result};
+use tonic::transport::{Certificate, ClientTlsConfig, Endpoint, Identity, ServerTlsConfig};
+
+/// TLS settings, read from the `rapidash.tls.*` configuration entries
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TlsConfig {
+    /// Path of the PEM certificate of servers, and of clients with mutual TLS
+    pub cert: Option<String>,
+    /// Path of the PEM private key of the certificate
+    pub key: Option<String>,
+    /// Path of the PEM CA certificate trusted by clients, and by servers with mutual TLS
+    pub ca: Option<String>,
+    /// Require clients to present a certificate
+    pub client_auth: bool,
+    /// Name to verify server certificates against instead of the host
+    pub domain: Option<String>,
+}
+
+impl TlsConfig {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            cert: config.tls_cert(),
+            key: config.tls_key(),
+            ca: config.tls_ca(),
+            client_auth: config.tls_client_auth(),
+            domain: config.tls_domain(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.cert.is_some() || self.ca.is_some()
+    }
+
+    /// Url of a server, https when TLS is enabled
+    pub fn url(&self, host: &str, port: u16) -> String {
+        let scheme = if self.is_enabled() { "https" } else { "http" };
+        format!("{}://{}:{}", scheme, host, port)
+    }
+
+    /// TLS of a server, None when TLS is disabled
+    pub fn server_tls(&self) -> Result<Option<ServerTlsConfig>> {
+        if !self.is_enabled() {
+            return Ok(None);
+        }
+
+        let mut tls = ServerTlsConfig::new().identity(self.identity()?);
+        if self.client_auth {
+            tls = tls.client_ca_root(self.ca_certificate()?);
+        }
+        Ok(Some(tls))
+    }
+
+    /// TLS of a client, None when TLS is disabled
+    pub fn client_tls(&self) -> Result<Option<ClientTlsConfig>> {
+        if !self.is_enabled() {
+            return Ok(None);
+        }
+
+        let mut tls = ClientTlsConfig::new().ca_certificate(self.ca_certificate()?);
+        if let Some(domain) = &self.domain {
+            tls = tls.domain_name(domain);
+        }
+        if self.client_auth {
+            tls = tls.identity(self.identity()?);
+        }
+        Ok(Some(tls))
+    }
+
+    /// Endpoint of a server url, with the client TLS applied
+    pub fn endpoint(&self, url: String) -> Result<Endpoint> {
+        let endpoint = Endpoint::from_shared(url).map_err(grpc_error)?;
+        match self.client_tls()? {
+            Some(tls) => endpoint.tls_config(tls).map_err(grpc_error),
+            None => Ok(endpoint),
+        }
+    }
+
+    fn identity(&self) -> Result<Identity> {
+        let cert = read_pem(&self.cert, TLS_CERT)?;
+        let key = read_pem(&self.key, TLS_KEY)?;
+        Ok(Identity::from_pem(cert, key))
+    }
+
+    fn ca_certificate(&self) -> Result<Certificate> {
+        Ok(Certificate::from_pem(read_pem(&self.ca, TLS_CA)?))
+    }
+}
+
+fn read_pem(path: &Option<String>, key: &str) -> Result<Vec<u8>> {
+    let path = path
+        .as_ref()
+        .ok_or_else(|| RapidashError::General(format!("TLS requires setting {}", key)))?;
+    Ok(fs::read(path)?)
+}
+
+fn grpc_error(error: tonic::transport::Error) -> RapidashError {
+    RapidashError::GrpcConnectionError(error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use common::config::TLS_CLIENT_AUTH;
+
+    use super::*;
+
+    #[test]
+    fn test_plaintext_by_default() {
+        let tls = TlsConfig::from_config(&Config::new().unwrap());
+        assert!(!tls.is_enabled());
+        assert_eq!(tls.url("localhost", 51008), "http://localhost:51008");
+        assert!(tls.server_tls().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_missing_files() {
+        let config = Config::builder()
+            .set(TLS_CA, "/missing/ca.pem")
+            .set(TLS_CLIENT_AUTH, "true")
+            .build()
+            .unwrap();
+        let tls = TlsConfig::from_config(&config);
+        assert!(tls.is_enabled());
+        assert_eq!(tls.url("localhost", 51008), "https://localhost:51008");
+        // the server has no certificate and the CA does not exist
+        assert!(tls.server_tls().is_err());
+        assert!(tls.client_tls().is_err());
+    }
+}